@@ -0,0 +1,81 @@
+//! How long the embedded text takes to inflate when it's first needed.
+//!
+//! Each book is inflated once per process, so a cold read can only be timed once per process: the
+//! bench runs itself again for every sample, and each run reports its own timings.
+//!
+//!     cargo bench -p fiat-lux --bench embedded
+
+use std::{env, process::Command, time::Instant};
+
+use fiat_lux::{lookup_embedded, Book, Translation};
+
+const SAMPLES: usize = 25;
+
+fn main() {
+    if env::args().any(|arg| arg == "--sample") {
+        sample();
+        return;
+    }
+
+    let exe = env::current_exe().expect("no path to the bench");
+    let mut timings = Vec::with_capacity(SAMPLES);
+    for _ in 0..SAMPLES {
+        let output = Command::new(&exe)
+            .arg("--sample")
+            .output()
+            .expect("couldn't run a sample");
+        let output = String::from_utf8(output.stdout).unwrap();
+        let sample: Vec<u128> = output
+            .split_whitespace()
+            .map(|nanos| nanos.parse().unwrap())
+            .collect();
+        timings.push(sample);
+    }
+
+    let names = [
+        "first lookup (John 3:16)",
+        "first lookup (Psalms 119)",
+        "warm lookup (Psalms 119)",
+        "whole text (Translation::text)",
+    ];
+    println!("{SAMPLES} runs each\n");
+    for (idx, name) in names.iter().enumerate() {
+        let mut nanos: Vec<_> = timings.iter().map(|sample| sample[idx]).collect();
+        nanos.sort_unstable();
+        let mean = nanos.iter().sum::<u128>() / nanos.len() as u128;
+        println!(
+            "{name:<32} mean {:>9}  min {:>9}  max {:>9}",
+            micros(mean),
+            micros(nanos[0]),
+            micros(nanos[nanos.len() - 1]),
+        );
+    }
+}
+
+/// One run's timings, in nanoseconds, each in a process where nothing's been inflated yet.
+fn sample() {
+    let time = |f: &dyn Fn()| {
+        let start = Instant::now();
+        f();
+        start.elapsed().as_nanos()
+    };
+
+    // KJV for the lookups and ASV for the whole text, so each starts cold.
+    let john = time(&|| {
+        let location = Some("3:16".parse().unwrap());
+        lookup_embedded(Book::John, location, Translation::Kjv).unwrap();
+    });
+    let psalms = || {
+        lookup_embedded(Book::Psalms, None, Translation::Kjv).unwrap();
+    };
+    let cold = time(&psalms);
+    let warm = time(&psalms);
+    let text = time(&|| {
+        Translation::Asv.text().unwrap();
+    });
+    println!("{john} {cold} {warm} {text}");
+}
+
+fn micros(nanos: u128) -> String {
+    format!("{:.1} µs", nanos as f64 / 1000.0)
+}
@@ -0,0 +1,63 @@
+//! Prepares the embedded translations. The text is most of the size of the binary, so each dat
+//! file is deflated, a book at a time so that one book can be read without inflating the rest.
+//! Alongside go two tables, all little-endian u32s: one with each book's number and where its
+//! stream starts and ends, and one with every verse's id and where its line starts in its book.
+
+use std::{env, fs, io::Write, path::Path};
+
+use flate2::{write::DeflateEncoder, Compression};
+
+const TRANSLATIONS: [&str; 2] = ["asv", "kjv"];
+
+fn main() {
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let out_dir = Path::new(&out_dir);
+    for name in TRANSLATIONS {
+        let source = format!("resource/{name}.dat");
+        println!("cargo:rerun-if-changed={source}");
+
+        let text = fs::read_to_string(&source).unwrap();
+        let mut books: Vec<(u32, String)> = Vec::new();
+        let mut verses = Vec::new();
+        for line in text.split_inclusive('\n') {
+            let Some(id) = line.get(..8).and_then(|id| id.parse::<u32>().ok()) else {
+                continue;
+            };
+            let book = id / 1_000_000;
+            if books.last().is_none_or(|&(last, _)| last != book) {
+                books.push((book, String::new()));
+            }
+
+            let (_, text) = books.last_mut().unwrap();
+            push(&mut verses, &[id, text.len() as u32]);
+            text.push_str(line);
+        }
+
+        let mut compressed = Vec::new();
+        let mut table = Vec::new();
+        for (book, text) in books {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+            encoder.write_all(text.as_bytes()).unwrap();
+            let stream = encoder.finish().unwrap();
+            push(
+                &mut table,
+                &[
+                    book,
+                    compressed.len() as u32,
+                    (compressed.len() + stream.len()) as u32,
+                ],
+            );
+            compressed.extend_from_slice(&stream);
+        }
+
+        fs::write(out_dir.join(format!("{name}.dat.z")), compressed).unwrap();
+        fs::write(out_dir.join(format!("{name}.books")), table).unwrap();
+        fs::write(out_dir.join(format!("{name}.verses")), verses).unwrap();
+    }
+}
+
+fn push(table: &mut Vec<u8>, fields: &[u32]) {
+    for field in fields {
+        table.extend_from_slice(&field.to_le_bytes());
+    }
+}
@@ -0,0 +1,842 @@
+use std::{fmt, iter, num::NonZeroU8, str::FromStr};
+
+use crate::error::AbbrevStr;
+
+/// The sections the books of the Bible are usually grouped into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Genre {
+    Torah,
+    History,
+    Wisdom,
+    Prophets,
+    Gospels,
+    Epistles,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Book {
+    // Fun fact: setting Genesis as 1 causes this enum to be 1-based, which I am hoping will
+    // enable the optimization where the None variant will simply appear as zero.
+    Genesis = 1,    // Genesis
+    Exodus,         // Exodus
+    Leviticus,      // Leviticus
+    Numbers,        // Numbers
+    Deuteronomy,    // Deuteronomy
+    Joshua,         // Joshua
+    Judges,         // Judges
+    Ruth,           // Ruth
+    Samuel1,        // 1 Samuel
+    Samuel2,        // 2 Samuel
+    Kings1,         // 1 Kings
+    Kings2,         // 2 Kings
+    Chronicles1,    // 1 Chronicles
+    Chronicles2,    // 2 Chronicles
+    Ezra,           // Ezra
+    Nehemiah,       // Nehemiah
+    Esther,         // Esther
+    Job,            // Job
+    Psalms,         // Psalms
+    Proverbs,       // Proverbs
+    Ecclesiastes,   // Ecclesiastes
+    SongofSongs,    // Song of Songs
+    Isaiah,         // Isaiah
+    Jeremiah,       // Jeremiah
+    Lamentations,   // Lamentations
+    Ezekiel,        // Ezekiel
+    Daniel,         // Daniel
+    Hosea,          // Hosea
+    Joel,           // Joel
+    Amos,           // Amos
+    Obadiah,        // Obadiah
+    Jonah,          // Jonah
+    Micah,          // Micah
+    Nahum,          // Nahum
+    Habakkuk,       // Habakkuk
+    Zephaniah,      // Zephaniah
+    Haggai,         // Haggai
+    Zechariah,      // Zechariah
+    Malachi,        // Malachi
+    Matthew,        // Matthew
+    Mark,           // Mark
+    Luke,           // Luke
+    John,           // John
+    Acts,           // Acts
+    Romans,         // Romans
+    Corinthians1,   // 1 Corinthians
+    Corinthians2,   // 2 Corinthians
+    Galatians,      // Galatians
+    Ephesians,      // Ephesians
+    Philippians,    // Philippians
+    Colossians,     // Colossians
+    Thessalonians1, // 1 Thessalonians
+    Thessalonians2, // 2 Thessalonians
+    Timothy1,       // 1 Timothy
+    Timothy2,       // 2 Timothy
+    Titus,          // Titus
+    Philemon,       // Philemon
+    Hebrews,        // Hebrews
+    James,          // James
+    Peter1,         // 1 Peter
+    Peter2,         // 2 Peter
+    John1,          // 1 John
+    John2,          // 2 John
+    John3,          // 3 John
+    Jude,           // Jude
+    Revelation,     // Revelation
+}
+
+impl Book {
+    pub const fn from_u8(u: u8) -> Self {
+        match u {
+            1 => Book::Genesis,
+            2 => Book::Exodus,
+            3 => Book::Leviticus,
+            4 => Book::Numbers,
+            5 => Book::Deuteronomy,
+            6 => Book::Joshua,
+            7 => Book::Judges,
+            8 => Book::Ruth,
+            9 => Book::Samuel1,
+            10 => Book::Samuel2,
+            11 => Book::Kings1,
+            12 => Book::Kings2,
+            13 => Book::Chronicles1,
+            14 => Book::Chronicles2,
+            15 => Book::Ezra,
+            16 => Book::Nehemiah,
+            17 => Book::Esther,
+            18 => Book::Job,
+            19 => Book::Psalms,
+            20 => Book::Proverbs,
+            21 => Book::Ecclesiastes,
+            22 => Book::SongofSongs,
+            23 => Book::Isaiah,
+            24 => Book::Jeremiah,
+            25 => Book::Lamentations,
+            26 => Book::Ezekiel,
+            27 => Book::Daniel,
+            28 => Book::Hosea,
+            29 => Book::Joel,
+            30 => Book::Amos,
+            31 => Book::Obadiah,
+            32 => Book::Jonah,
+            33 => Book::Micah,
+            34 => Book::Nahum,
+            35 => Book::Habakkuk,
+            36 => Book::Zephaniah,
+            37 => Book::Haggai,
+            38 => Book::Zechariah,
+            39 => Book::Malachi,
+            40 => Book::Matthew,
+            41 => Book::Mark,
+            42 => Book::Luke,
+            43 => Book::John,
+            44 => Book::Acts,
+            45 => Book::Romans,
+            46 => Book::Corinthians1,
+            47 => Book::Corinthians2,
+            48 => Book::Galatians,
+            49 => Book::Ephesians,
+            50 => Book::Philippians,
+            51 => Book::Colossians,
+            52 => Book::Thessalonians1,
+            53 => Book::Thessalonians2,
+            54 => Book::Timothy1,
+            55 => Book::Timothy2,
+            56 => Book::Titus,
+            57 => Book::Philemon,
+            58 => Book::Hebrews,
+            59 => Book::James,
+            60 => Book::Peter1,
+            61 => Book::Peter2,
+            62 => Book::John1,
+            63 => Book::John2,
+            64 => Book::John3,
+            65 => Book::Jude,
+            66 => Book::Revelation,
+
+            _ => panic!("invalid conversion"),
+        }
+    }
+
+    /// How many chapters the book has, in the KJV versification.
+    pub const fn chapters(self) -> u16 {
+        #[rustfmt::skip]
+        const CHAPTERS: [u16; 66] = [
+            50, 40, 27, 36, 34, 24, 21, 4, 31, 24, 22, 25, 29, 36, 10, 13, 10, 42, 150, 31, 12, 8,
+            66, 52, 5, 48, 12, 14, 3, 9, 1, 4, 7, 3, 3, 3, 2, 14, 4,
+            28, 16, 24, 21, 28, 16, 16, 13, 6, 6, 4, 4, 5, 3, 6, 4, 3, 1, 13, 5, 5, 3, 5, 1, 1, 1,
+            22,
+        ];
+        CHAPTERS[self as usize - 1]
+    }
+
+    /// The section of the canon the book belongs to. Acts goes with the histories and Revelation
+    /// with the prophets.
+    pub const fn genre(self) -> Genre {
+        match self as u8 {
+            1..=5 => Genre::Torah,
+            6..=17 | 44 => Genre::History,
+            18..=22 => Genre::Wisdom,
+            23..=39 | 66 => Genre::Prophets,
+            40..=43 => Genre::Gospels,
+            _ => Genre::Epistles,
+        }
+    }
+
+    /// Whether the book is mostly poetry, to be set in lines rather than run together as prose.
+    pub const fn is_poetry(self) -> bool {
+        matches!(
+            self,
+            Book::Job
+                | Book::Psalms
+                | Book::Proverbs
+                | Book::Ecclesiastes
+                | Book::SongofSongs
+                | Book::Lamentations
+        )
+    }
+
+    /// The three-character USFM/Paratext identifier for the book, e.g. "1CO".
+    pub const fn usfm_code(self) -> &'static str {
+        match self {
+            Book::Genesis => "GEN",
+            Book::Exodus => "EXO",
+            Book::Leviticus => "LEV",
+            Book::Numbers => "NUM",
+            Book::Deuteronomy => "DEU",
+            Book::Joshua => "JOS",
+            Book::Judges => "JDG",
+            Book::Ruth => "RUT",
+            Book::Samuel1 => "1SA",
+            Book::Samuel2 => "2SA",
+            Book::Kings1 => "1KI",
+            Book::Kings2 => "2KI",
+            Book::Chronicles1 => "1CH",
+            Book::Chronicles2 => "2CH",
+            Book::Ezra => "EZR",
+            Book::Nehemiah => "NEH",
+            Book::Esther => "EST",
+            Book::Job => "JOB",
+            Book::Psalms => "PSA",
+            Book::Proverbs => "PRO",
+            Book::Ecclesiastes => "ECC",
+            Book::SongofSongs => "SNG",
+            Book::Isaiah => "ISA",
+            Book::Jeremiah => "JER",
+            Book::Lamentations => "LAM",
+            Book::Ezekiel => "EZK",
+            Book::Daniel => "DAN",
+            Book::Hosea => "HOS",
+            Book::Joel => "JOL",
+            Book::Amos => "AMO",
+            Book::Obadiah => "OBA",
+            Book::Jonah => "JON",
+            Book::Micah => "MIC",
+            Book::Nahum => "NAM",
+            Book::Habakkuk => "HAB",
+            Book::Zephaniah => "ZEP",
+            Book::Haggai => "HAG",
+            Book::Zechariah => "ZEC",
+            Book::Malachi => "MAL",
+            Book::Matthew => "MAT",
+            Book::Mark => "MRK",
+            Book::Luke => "LUK",
+            Book::John => "JHN",
+            Book::Acts => "ACT",
+            Book::Romans => "ROM",
+            Book::Corinthians1 => "1CO",
+            Book::Corinthians2 => "2CO",
+            Book::Galatians => "GAL",
+            Book::Ephesians => "EPH",
+            Book::Philippians => "PHP",
+            Book::Colossians => "COL",
+            Book::Thessalonians1 => "1TH",
+            Book::Thessalonians2 => "2TH",
+            Book::Timothy1 => "1TI",
+            Book::Timothy2 => "2TI",
+            Book::Titus => "TIT",
+            Book::Philemon => "PHM",
+            Book::Hebrews => "HEB",
+            Book::James => "JAS",
+            Book::Peter1 => "1PE",
+            Book::Peter2 => "2PE",
+            Book::John1 => "1JN",
+            Book::John2 => "2JN",
+            Book::John3 => "3JN",
+            Book::Jude => "JUD",
+            Book::Revelation => "REV",
+        }
+    }
+
+    /// The book with the given USFM/Paratext identifier, if any.
+    pub fn from_usfm_code(code: &str) -> Option<Self> {
+        (1..=66)
+            .map(Book::from_u8)
+            .find(|book| book.usfm_code().eq_ignore_ascii_case(code))
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Book::Genesis => "Genesis",
+            Book::Exodus => "Exodus",
+            Book::Leviticus => "Leviticus",
+            Book::Numbers => "Numbers",
+            Book::Deuteronomy => "Deuteronomy",
+            Book::Joshua => "Joshua",
+            Book::Judges => "Judges",
+            Book::Ruth => "Ruth",
+            Book::Samuel1 => "1 Samuel",
+            Book::Samuel2 => "2 Samuel",
+            Book::Kings1 => "1 Kings",
+            Book::Kings2 => "2 Kings",
+            Book::Chronicles1 => "1 Chronicles",
+            Book::Chronicles2 => "2 Chronicles",
+            Book::Ezra => "Ezra",
+            Book::Nehemiah => "Nehemiah",
+            Book::Esther => "Esther",
+            Book::Job => "Job",
+            Book::Psalms => "Psalms",
+            Book::Proverbs => "Proverbs",
+            Book::Ecclesiastes => "Ecclesiastes",
+            Book::SongofSongs => "Song of Songs",
+            Book::Isaiah => "Isaiah",
+            Book::Jeremiah => "Jeremiah",
+            Book::Lamentations => "Lamentations",
+            Book::Ezekiel => "Ezekiel",
+            Book::Daniel => "Daniel",
+            Book::Hosea => "Hosea",
+            Book::Joel => "Joel",
+            Book::Amos => "Amos",
+            Book::Obadiah => "Obadiah",
+            Book::Jonah => "Jonah",
+            Book::Micah => "Micah",
+            Book::Nahum => "Nahum",
+            Book::Habakkuk => "Habakkuk",
+            Book::Zephaniah => "Zephaniah",
+            Book::Haggai => "Haggai",
+            Book::Zechariah => "Zechariah",
+            Book::Malachi => "Malachi",
+            Book::Matthew => "Matthew",
+            Book::Mark => "Mark",
+            Book::Luke => "Luke",
+            Book::John => "John",
+            Book::Acts => "Acts",
+            Book::Romans => "Romans",
+            Book::Corinthians1 => "1 Corinthians",
+            Book::Corinthians2 => "2 Corinthians",
+            Book::Galatians => "Galatians",
+            Book::Ephesians => "Ephesians",
+            Book::Philippians => "Philippians",
+            Book::Colossians => "Colossians",
+            Book::Thessalonians1 => "1 Thessalonians",
+            Book::Thessalonians2 => "2 Thessalonians",
+            Book::Timothy1 => "1 Timothy",
+            Book::Timothy2 => "2 Timothy",
+            Book::Titus => "Titus",
+            Book::Philemon => "Philemon",
+            Book::Hebrews => "Hebrews",
+            Book::James => "James",
+            Book::Peter1 => "1 Peter",
+            Book::Peter2 => "2 Peter",
+            Book::John1 => "1 John",
+            Book::John2 => "2 John",
+            Book::John3 => "3 John",
+            Book::Jude => "Jude",
+            Book::Revelation => "Revelation",
+        }
+    }
+}
+
+impl fmt::Display for Book {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl From<u8> for Book {
+    fn from(u: u8) -> Self {
+        Book::from_u8(u)
+    }
+}
+
+impl FromStr for Book {
+    type Err = ParseBookError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_book(s).map_err(|e| ParseBookError {
+            suggestions: suggest(s),
+            ..e
+        })
+    }
+}
+
+fn parse_book(s: &str) -> Result<Book, ParseBookError> {
+    // Abbreviations are frequently written with a trailing period, e.g. "Gen." or "1 Cor." The
+    // period has to go before splitting off the number, or it looks like one.
+    let (name, number) = book_name_in_parts(s.trim_end_matches('.'))?;
+    let name = name.to_ascii_uppercase();
+    let number = number.map(u8::from);
+
+    match canonical_name(&name).ok_or_else(|| ParseBookError::new(s))? {
+        "GENESIS" => Ok(Book::Genesis),
+        "EXODUS" => Ok(Book::Exodus),
+        "LEVITICUS" => Ok(Book::Leviticus),
+        "NUMBERS" => Ok(Book::Numbers),
+        "DEUTERONOMY" => Ok(Book::Deuteronomy),
+        "JOSHUA" => Ok(Book::Joshua),
+        "JUDGES" => Ok(Book::Judges),
+        "RUTH" => Ok(Book::Ruth),
+
+        "SAMUEL" => match number {
+            Some(1) => Ok(Book::Samuel1),
+            Some(2) => Ok(Book::Samuel2),
+            _ => Err(ParseBookError::new(s)),
+        },
+
+        "KINGS" => match number {
+            Some(1) => Ok(Book::Kings1),
+            Some(2) => Ok(Book::Kings2),
+            _ => Err(ParseBookError::new(s)),
+        },
+
+        "CHRONICLES" => match number {
+            Some(1) => Ok(Book::Chronicles1),
+            Some(2) => Ok(Book::Chronicles2),
+            _ => Err(ParseBookError::new(s)),
+        },
+
+        "EZRA" => Ok(Book::Ezra),
+        "NEHEMIAH" => Ok(Book::Nehemiah),
+        "ESTHER" => Ok(Book::Esther),
+        "JOB" => Ok(Book::Job),
+        "PSALMS" => Ok(Book::Psalms),
+        "PROVERBS" => Ok(Book::Proverbs),
+        "ECCLESIASTES" => Ok(Book::Ecclesiastes),
+
+        "SONGS" => Ok(Book::SongofSongs),
+
+        "ISAIAH" => Ok(Book::Isaiah),
+        "JEREMIAH" => Ok(Book::Jeremiah),
+        "LAMENTATIONS" => Ok(Book::Lamentations),
+        "EZEKIEL" => Ok(Book::Ezekiel),
+        "DANIEL" => Ok(Book::Daniel),
+        "HOSEA" => Ok(Book::Hosea),
+        "JOEL" => Ok(Book::Joel),
+        "AMOS" => Ok(Book::Amos),
+        "OBADIAH" => Ok(Book::Obadiah),
+        "JONAH" => Ok(Book::Jonah),
+        "MICAH" => Ok(Book::Micah),
+        "NAHUM" => Ok(Book::Nahum),
+        "HABAKKUK" => Ok(Book::Habakkuk),
+        "ZEPHANIAH" => Ok(Book::Zephaniah),
+        "HAGGAI" => Ok(Book::Haggai),
+        "ZECHARIAH" => Ok(Book::Zechariah),
+        "MALACHI" => Ok(Book::Malachi),
+        "MATTHEW" => Ok(Book::Matthew),
+        "MARK" => Ok(Book::Mark),
+        "LUKE" => Ok(Book::Luke),
+
+        "JOHN" => match number {
+            None => Ok(Book::John),
+            Some(1) => Ok(Book::John1),
+            Some(2) => Ok(Book::John2),
+            Some(3) => Ok(Book::John3),
+            _ => Err(ParseBookError::new(s)),
+        },
+
+        "ACTS" => Ok(Book::Acts),
+        "ROMANS" => Ok(Book::Romans),
+
+        "CORINTHIANS" => match number {
+            Some(1) => Ok(Book::Corinthians1),
+            Some(2) => Ok(Book::Corinthians2),
+            _ => Err(ParseBookError::new(s)),
+        },
+
+        "GALATIANS" => Ok(Book::Galatians),
+        "EPHESIANS" => Ok(Book::Ephesians),
+        "PHILIPPIANS" => Ok(Book::Philippians),
+        "COLOSSIANS" => Ok(Book::Colossians),
+
+        "THESSALONIANS" => match number {
+            Some(1) => Ok(Book::Thessalonians1),
+            Some(2) => Ok(Book::Thessalonians2),
+            _ => Err(ParseBookError::new(s)),
+        },
+
+        "TIMOTHY" => match number {
+            Some(1) => Ok(Book::Timothy1),
+            Some(2) => Ok(Book::Timothy2),
+            _ => Err(ParseBookError::new(s)),
+        },
+
+        "TITUS" => Ok(Book::Titus),
+        "PHILEMON" => Ok(Book::Philemon),
+        "HEBREWS" => Ok(Book::Hebrews),
+        "JAMES" => Ok(Book::James),
+
+        "PETER" => match number {
+            Some(1) => Ok(Book::Peter1),
+            Some(2) => Ok(Book::Peter2),
+            _ => Err(ParseBookError::new(s)),
+        },
+
+        "JUDE" => Ok(Book::Jude),
+        "REVELATION" => Ok(Book::Revelation),
+
+        _ => Err(ParseBookError::new(s)),
+    }
+}
+
+/// Accepted spellings of each book name, sans any number, keyed by the canonical spelling.
+///
+/// Ambiguous prefixes (Ph, Jud, Jo) are deliberately absent.
+static NAMES: &[(&str, &[&str])] = &[
+    ("GENESIS", &["GEN", "GE", "GN"]),
+    ("EXODUS", &["EXO", "EXOD", "EX"]),
+    ("LEVITICUS", &["LEV", "LE", "LV"]),
+    ("NUMBERS", &["NUM", "NU", "NM", "NB"]),
+    ("DEUTERONOMY", &["DEUT", "DEU", "DT"]),
+    ("JOSHUA", &["JOSH", "JOS", "JSH"]),
+    ("JUDGES", &["JUDG", "JDG", "JG", "JDGS"]),
+    ("RUTH", &["RTH", "RU"]),
+    ("SAMUEL", &["SAM", "SA", "SM"]),
+    ("KINGS", &["KGS", "KIN", "KI"]),
+    ("CHRONICLES", &["CHRON", "CHR", "CH"]),
+    ("EZRA", &["EZR"]),
+    ("NEHEMIAH", &["NEH", "NE"]),
+    ("ESTHER", &["ESTH", "EST", "ES"]),
+    ("JOB", &["JB"]),
+    ("PSALMS", &["PSALM", "PS", "PSA", "PSS", "PSLM", "PSM"]),
+    ("PROVERBS", &["PROV", "PRO", "PRV", "PR"]),
+    ("ECCLESIASTES", &["ECCL", "ECCLES", "ECC", "EC", "QOH"]),
+    (
+        "SONGS",
+        &[
+            "SONG OF SONGS",
+            "SONG OF SOLOMON",
+            "SONG",
+            "SOS",
+            "SOL",
+            "SS",
+        ],
+    ),
+    ("ISAIAH", &["ISA", "IS"]),
+    ("JEREMIAH", &["JER", "JE", "JR"]),
+    ("LAMENTATIONS", &["LAM", "LA"]),
+    ("EZEKIEL", &["EZEK", "EZE", "EZK"]),
+    ("DANIEL", &["DAN", "DA", "DN"]),
+    ("HOSEA", &["HOS", "HO"]),
+    ("JOEL", &["JL"]),
+    ("AMOS", &["AM"]),
+    ("OBADIAH", &["OBAD", "OB"]),
+    ("JONAH", &["JNH", "JON"]),
+    ("MICAH", &["MIC", "MC"]),
+    ("NAHUM", &["NAH", "NA"]),
+    ("HABAKKUK", &["HAB", "HB"]),
+    ("ZEPHANIAH", &["ZEPH", "ZEP", "ZP"]),
+    ("HAGGAI", &["HAG", "HG"]),
+    ("ZECHARIAH", &["ZECH", "ZEC", "ZC"]),
+    ("MALACHI", &["MAL", "ML"]),
+    ("MATTHEW", &["MATT", "MAT", "MT"]),
+    ("MARK", &["MRK", "MAR", "MK", "MR"]),
+    ("LUKE", &["LUK", "LK"]),
+    ("JOHN", &["JHN", "JN"]),
+    ("ACTS", &["ACT", "AC"]),
+    ("ROMANS", &["ROM", "RO", "RM"]),
+    ("CORINTHIANS", &["COR", "CO"]),
+    ("GALATIANS", &["GAL", "GA"]),
+    ("EPHESIANS", &["EPH", "EPHES"]),
+    ("PHILIPPIANS", &["PHIL", "PHP", "PP"]),
+    ("COLOSSIANS", &["COL"]),
+    ("THESSALONIANS", &["THESS", "THES", "TH"]),
+    ("TIMOTHY", &["TIM", "TI"]),
+    ("TITUS", &["TIT"]),
+    ("PHILEMON", &["PHILEM", "PHM", "PHLM"]),
+    ("HEBREWS", &["HEB"]),
+    ("JAMES", &["JAS", "JM"]),
+    ("PETER", &["PET", "PE", "PT"]),
+    ("JUDE", &["JDE"]),
+    ("REVELATION", &["REV", "RE", "RV"]),
+];
+
+fn canonical_name(name: &str) -> Option<&'static str> {
+    NAMES
+        .iter()
+        .find(|&&(canonical, aliases)| canonical == name || aliases.contains(&name))
+        .map(|&(canonical, _)| canonical)
+}
+
+fn book_name_in_parts(s: &str) -> Result<(&str, Option<NonZeroU8>), ParseBookError> {
+    // We want to split on the first transition between numeric and non-numeric characters. At
+    // this point in time, don't be passing us any damn books with Roman numerals. Romans killed
+    // Jesus, after all.
+
+    // That's a joke. Lighten up!
+
+    // If there IS no numeric/nonnumeric transition, that means the name is monolithic, and we'll
+    // just go ahead and return the entire string with no numeric identifier.
+
+    let idx = match first_numeric_nonnumeric_transition(s) {
+        Some(idx) => idx,
+        None => return Ok((s, None)),
+    };
+
+    // Once we've split our prospective name string, we want to determine which end of the string
+    // had a number in it. Of course, it's possible neither end had a number, in which case we
+    // will just forget about the second portion here (because it's a blank string, probably).
+
+    fn characterize<'a>(left: &'a str, right: &'a str) -> (&'a str, &'a str) {
+        let left = left.trim();
+        let right = right.trim();
+
+        if left.bytes().any(|u| u.is_ascii_digit()) {
+            (right, left)
+        } else {
+            (left, right)
+        }
+    }
+
+    let (left, right) = s.split_at(idx);
+    let (name, numeric) = characterize(left, right);
+    let n: u8 = numeric.parse().map_err(|_| ParseBookError::new(s))?;
+    let n = NonZeroU8::new(n).ok_or_else(|| ParseBookError::new(s))?;
+    Ok((name, Some(n)))
+}
+
+fn first_numeric_nonnumeric_transition(s: &str) -> Option<usize> {
+    if s.is_empty() {
+        return None;
+    }
+
+    let is_alphabetic = s.starts_with(|u: char| u.is_alphabetic());
+    s[1..]
+        .find(|u: char| !u.is_whitespace() && u.is_alphabetic() != is_alphabetic)
+        .map(|idx| idx + 1)
+}
+
+/// Find the book or books most likely intended by a name which failed to parse.
+fn suggest(s: &str) -> Vec<String> {
+    /// Past a certain point, this isn't going to be helpful.
+    const MAX_SUGGESTIONS: usize = 5;
+
+    let (name, number) = book_name_in_parts(s).unwrap_or((s, None));
+    let name = name.trim_end_matches('.').to_ascii_uppercase();
+
+    // Anything further away than this is probably a different word rather than a typo.
+    let threshold = (name.len() / 3).max(1);
+
+    let mut best = threshold;
+    let mut candidates = Vec::new();
+    for &(canonical, aliases) in NAMES {
+        let distance = iter::once(canonical)
+            .chain(aliases.iter().copied())
+            .map(|spelling| edit_distance(&name, spelling))
+            .min()
+            .unwrap_or(usize::MAX);
+
+        if distance < best {
+            best = distance;
+            candidates.clear();
+        }
+
+        if distance == best {
+            candidates.push(canonical);
+        }
+    }
+
+    // A name like "Corinthians" stands for more than one book, so we suggest whichever books
+    // the name produces with the number provided--or with any number, if none was provided.
+    let numbers = match number {
+        Some(n) => vec![Some(n.get())],
+        None => vec![None, Some(1), Some(2), Some(3)],
+    };
+
+    let mut suggestions = Vec::new();
+    for canonical in candidates {
+        for n in &numbers {
+            let candidate = match n {
+                Some(n) => format!("{n} {canonical}"),
+                None => canonical.to_string(),
+            };
+
+            if let Ok(book) = parse_book(&candidate) {
+                let book = book.to_string();
+                if !suggestions.contains(&book) {
+                    suggestions.push(book);
+                }
+            }
+        }
+    }
+
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+/// Levenshtein distance, in bytes. Book names are ASCII, so that's fine.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.as_bytes();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, &x) in a.as_bytes().iter().enumerate() {
+        current[0] = i + 1;
+        for (j, &y) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(x != y);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("could not parse '{text}' as book{}", DidYouMean(suggestions))]
+pub struct ParseBookError {
+    text: String,
+    suggestions: Vec<String>,
+}
+
+impl ParseBookError {
+    fn new(text: impl AbbrevStr) -> Self {
+        Self {
+            text: text.get(20),
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// Book names which were probably intended, if any.
+    pub fn suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+}
+
+struct DidYouMean<'a>(&'a [String]);
+
+impl fmt::Display for DidYouMean<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((last, rest)) = self.0.split_last() else {
+            return Ok(());
+        };
+
+        f.write_str(", did you mean ")?;
+        for suggestion in rest {
+            write!(f, "'{suggestion}', ")?;
+        }
+
+        if rest.is_empty() {
+            write!(f, "'{last}'?")
+        } else {
+            write!(f, "or '{last}'?")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn first_numeric_nonnumeric_transition() {
+        use super::first_numeric_nonnumeric_transition as test;
+        assert_eq!(Some(2), test("1 Kings"));
+        assert_eq!(Some(1), test("1Kings"));
+        assert_eq!(Some(5), test("Kings1"));
+        assert_eq!(Some(6), test("Kings 1"));
+        assert_eq!(None, test("Exodus"));
+    }
+
+    #[test]
+    fn abbreviations() {
+        use super::Book;
+        let test = |s: &str| s.parse::<Book>().ok();
+        assert_eq!(Some(Book::Genesis), test("Gen"));
+        assert_eq!(Some(Book::Genesis), test("Gen."));
+        assert_eq!(Some(Book::Exodus), test("ex"));
+        assert_eq!(Some(Book::Deuteronomy), test("Dt"));
+        assert_eq!(Some(Book::Psalms), test("ps"));
+        assert_eq!(Some(Book::Psalms), test("Psa"));
+        assert_eq!(Some(Book::SongofSongs), test("SoS"));
+        assert_eq!(Some(Book::Matthew), test("Mt"));
+        assert_eq!(Some(Book::Corinthians1), test("1Cor"));
+        assert_eq!(Some(Book::Corinthians1), test("1 Cor."));
+        assert_eq!(Some(Book::John1), test("1 Jn"));
+        assert_eq!(Some(Book::Philippians), test("Phil"));
+        assert_eq!(Some(Book::Philemon), test("Philem"));
+        assert_eq!(Some(Book::Revelation), test("Rev"));
+    }
+
+    #[test]
+    fn ambiguous_abbreviations() {
+        use super::Book;
+        let test = |s: &str| s.parse::<Book>().ok();
+
+        // Philippians or Philemon?
+        assert_eq!(None, test("Ph"));
+
+        // Judges or Jude?
+        assert_eq!(None, test("Jud"));
+
+        // Joshua, Job, Joel, Jonah or John?
+        assert_eq!(None, test("Jo"));
+
+        // A numbered book without its number.
+        assert_eq!(None, test("Cor"));
+    }
+
+    #[test]
+    fn genres() {
+        use super::{Book, Genre};
+        assert_eq!(Genre::Torah, Book::Deuteronomy.genre());
+        assert_eq!(Genre::History, Book::Esther.genre());
+        assert_eq!(Genre::Wisdom, Book::SongofSongs.genre());
+        assert_eq!(Genre::Prophets, Book::Malachi.genre());
+        assert_eq!(Genre::Gospels, Book::John.genre());
+        assert_eq!(Genre::History, Book::Acts.genre());
+        assert_eq!(Genre::Epistles, Book::Jude.genre());
+        assert_eq!(Genre::Prophets, Book::Revelation.genre());
+
+        assert!(Book::Psalms.is_poetry());
+        assert!(Book::Lamentations.is_poetry());
+        assert!(!Book::Isaiah.is_poetry());
+    }
+
+    #[test]
+    fn chapters() {
+        use super::Book;
+        use crate::{location::Location, parse_verses_with_id, translation::Translation};
+
+        let mut last = [0; 66];
+        for (id, _) in parse_verses_with_id(Translation::Kjv.text().unwrap_or_default()) {
+            let location = Location::from_id(id);
+            let idx = usize::from(location.book as u8) - 1;
+            last[idx] = last[idx].max(location.chapter);
+        }
+
+        for (idx, &chapters) in last.iter().enumerate() {
+            let book = Book::from_u8(idx as u8 + 1);
+            assert_eq!(chapters, book.chapters(), "{book}");
+        }
+    }
+
+    #[test]
+    fn suggestions() {
+        use super::Book;
+        let test = |s: &str| s.parse::<Book>().unwrap_err().suggestions().to_vec();
+        assert_eq!(vec!["Galatians"], test("Galations"));
+        assert_eq!(vec!["Deuteronomy"], test("Dueteronomy"));
+        assert_eq!(vec!["1 Corinthians"], test("1 Corinthans"));
+        assert_eq!(vec!["1 Corinthians", "2 Corinthians"], test("Corinthians"));
+        assert!(test("Hezekiah").is_empty());
+    }
+
+    #[test]
+    fn edit_distance() {
+        use super::edit_distance as test;
+        assert_eq!(0, test("JOHN", "JOHN"));
+        assert_eq!(1, test("JHON", "JHN"));
+        assert_eq!(1, test("GALATIONS", "GALATIANS"));
+        assert_eq!(3, test("KITTEN", "SITTING"));
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{book::Book, translation::Translation};
+
+/// Chapters are kept for a month by default...
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60 * 24 * 30);
+
+/// ...and the whole cache is kept under 50 megabytes, which is a few complete Bibles.
+pub const DEFAULT_MAX_BYTES: u64 = 50 * 0x100000;
+
+/// On-disk cache of remote chapters, one file per chapter, keyed by translation and location.
+pub(crate) struct ChapterCache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_bytes: u64,
+}
+
+/// A chapter from the cache. Stale chapters are still useful when the network isn't.
+pub(crate) struct Cached {
+    pub verses: Vec<(u16, String)>,
+    pub fresh: bool,
+}
+
+impl ChapterCache {
+    pub fn new(dir: PathBuf, ttl: Duration, max_bytes: u64) -> Self {
+        Self {
+            dir,
+            ttl,
+            max_bytes,
+        }
+    }
+
+    pub fn get(&self, translation: Translation, book: Book, chapter: u16) -> Option<Cached> {
+        let path = self.path(translation, book, chapter);
+        let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+        let text = fs::read_to_string(&path).ok()?;
+        let verses = text
+            .lines()
+            .map(|line| {
+                let (verse, content) = line.split_once('\t')?;
+                Some((verse.parse().ok()?, content.to_string()))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let age = SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or_default();
+
+        Some(Cached {
+            verses,
+            fresh: age <= self.ttl,
+        })
+    }
+
+    pub fn put(
+        &self,
+        translation: Translation,
+        book: Book,
+        chapter: u16,
+        verses: &[(u16, String)],
+    ) -> io::Result<()> {
+        let path = self.path(translation, book, chapter);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut buf = String::new();
+        for (verse, content) in verses {
+            buf += &format!("{verse}\t{content}\n");
+        }
+        fs::write(path, buf)?;
+        self.prune()
+    }
+
+    /// Delete the least recently fetched chapters until the cache fits within its size limit.
+    fn prune(&self) -> io::Result<()> {
+        let mut files = Vec::new();
+        collect_files(&self.dir, &mut files)?;
+
+        let mut total: u64 = files.iter().map(|&(_, len, _)| len).sum();
+        if total <= self.max_bytes {
+            return Ok(());
+        }
+
+        files.sort_by_key(|&(_, _, modified)| modified);
+        for (path, len, _) in files {
+            if total <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(path)?;
+            total -= len;
+        }
+
+        Ok(())
+    }
+
+    fn path(&self, translation: Translation, book: Book, chapter: u16) -> PathBuf {
+        self.dir
+            .join(translation.to_string())
+            .join((book as u8).to_string())
+            .join(format!("{chapter}.txt"))
+    }
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<(PathBuf, u64, SystemTime)>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let meta = entry.metadata()?;
+        if meta.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else {
+            files.push((entry.path(), meta.len(), meta.modified()?));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, time::Duration};
+
+    use super::ChapterCache;
+    use crate::{Book, Translation};
+
+    #[test]
+    fn round_trip_and_prune() {
+        let dir = std::env::temp_dir().join(format!("fiat-lux-cache-{}", std::process::id()));
+        let verses = vec![(1, String::from("In the beginning was the Word"))];
+
+        // Big enough for one chapter, but not two.
+        let cache = ChapterCache::new(dir.clone(), Duration::from_secs(60), 40);
+        cache.put(Translation::Esv, Book::John, 1, &verses).unwrap();
+        let cached = cache.get(Translation::Esv, Book::John, 1).unwrap();
+        assert!(cached.fresh);
+        assert_eq!(verses, cached.verses);
+
+        cache.put(Translation::Esv, Book::John, 2, &verses).unwrap();
+        let remaining = [1, 2]
+            .into_iter()
+            .filter(|&chapter| cache.get(Translation::Esv, Book::John, chapter).is_some())
+            .count();
+        assert_eq!(1, remaining);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,234 @@
+//! Dates of the major feasts for a given (Gregorian) year.
+//!
+//! The Jewish feasts follow the fixed Hebrew calendar as reckoned today, and the Christian ones the
+//! Western (Gregorian) reckoning. Jewish days begin at sundown, so each feast actually starts the
+//! evening before the date given here.
+
+use std::fmt;
+
+/// A day in the proleptic Gregorian calendar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u8, day: u8) -> Self {
+        Self { year, month, day }
+    }
+
+    /// Today, in UTC.
+    pub fn today() -> Self {
+        let seconds = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        Self::from_days(seconds as i64 / 86_400)
+    }
+
+    /// Days since 1970-01-01.
+    pub fn days(self) -> i64 {
+        // Howard Hinnant's days_from_civil.
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let month = i64::from(self.month);
+        let doy =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// The date some number of days after 1970-01-01.
+    pub fn from_days(days: i64) -> Self {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let doe = days - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        Self::new(year as i32, month as u8, day as u8)
+    }
+
+    fn add_days(self, days: i64) -> Self {
+        Self::from_days(self.days() + days)
+    }
+
+    /// Day of the week, with Sunday as 0.
+    fn weekday(self) -> u8 {
+        // 1970-01-01 was a Thursday.
+        (self.days() + 4).rem_euclid(7) as u8
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        static WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+        static MONTHS: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+
+        write!(
+            f,
+            "{} {} {:2}",
+            WEEKDAYS[usize::from(self.weekday())],
+            MONTHS[usize::from(self.month - 1)],
+            self.day
+        )
+    }
+}
+
+/// A feast, when it falls, and where it's instituted (or, for the Christian feasts, where the event
+/// it remembers is told).
+#[derive(Clone, Copy, Debug)]
+pub struct Feast {
+    pub name: &'static str,
+    pub date: Date,
+    pub passages: &'static [&'static str],
+}
+
+/// The feasts falling in a given year, in the order they occur.
+pub fn feasts(year: i32) -> Vec<Feast> {
+    let passover = passover(year);
+    let mut feasts = vec![
+        Feast {
+            name: "Passover",
+            date: passover,
+            passages: &["Exodus 12:1-28", "Leviticus 23:4-8", "Deuteronomy 16:1-8"],
+        },
+        Feast {
+            name: "Pentecost (Weeks)",
+            date: passover.add_days(50),
+            passages: &["Leviticus 23:15-22", "Deuteronomy 16:9-12", "Acts 2:1-4"],
+        },
+        Feast {
+            name: "Tabernacles",
+            date: rosh_hashanah(year + 3761).add_days(14),
+            passages: &["Leviticus 23:33-43", "Deuteronomy 16:13-15"],
+        },
+        Feast {
+            name: "Easter",
+            date: easter(year),
+            passages: &["Matthew 28:1-10", "1 Corinthians 15:3-8"],
+        },
+        Feast {
+            name: "Advent",
+            date: advent(year),
+            passages: &["Isaiah 9:2-7", "Luke 1:26-38"],
+        },
+    ];
+    feasts.sort_by_key(|feast| feast.date);
+    feasts
+}
+
+/// 15 Nisan, which falls a fixed 163 days before the following new year.
+fn passover(year: i32) -> Date {
+    rosh_hashanah(year + 3761).add_days(-163)
+}
+
+/// 1 Tishri of a Hebrew year, following Calendrical Calculations (Reingold & Dershowitz).
+fn rosh_hashanah(year: i32) -> Date {
+    // 1 Tishri AM 1, i.e. 7 October 3761 BC (Julian), as days since 1970-01-01.
+    const EPOCH: i64 = -2_092_590;
+
+    let year = i64::from(year);
+    Date::from_days(EPOCH + elapsed_days(year) + year_length_correction(year))
+}
+
+/// Days from the epoch to the new year, by the molad of Tishri and the postponement rules.
+fn elapsed_days(year: i64) -> i64 {
+    let months = (235 * year - 234).div_euclid(19);
+    let parts = 12_084 + 13_753 * months;
+    let day = 29 * months + parts.div_euclid(25_920);
+    if (3 * (day + 1)).rem_euclid(7) < 3 {
+        day + 1
+    } else {
+        day
+    }
+}
+
+/// Delay the new year to keep the years before and after it a legal length.
+fn year_length_correction(year: i64) -> i64 {
+    let (previous, current, next) = (
+        elapsed_days(year - 1),
+        elapsed_days(year),
+        elapsed_days(year + 1),
+    );
+
+    if next - current == 356 {
+        2
+    } else if current - previous == 382 {
+        1
+    } else {
+        0
+    }
+}
+
+/// Western Easter, by the anonymous Gregorian algorithm.
+fn easter(year: i32) -> Date {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    Date::new(year, month as u8, day as u8)
+}
+
+/// The first Sunday of Advent, which is the fourth Sunday before Christmas.
+fn advent(year: i32) -> Date {
+    let earliest = Date::new(year, 11, 27);
+    earliest.add_days(i64::from((7 - earliest.weekday()) % 7))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{feasts, Date};
+
+    #[test]
+    fn feasts_for_2025() {
+        let dates: Vec<_> = feasts(2025)
+            .iter()
+            .map(|feast| (feast.name, feast.date))
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("Passover", Date::new(2025, 4, 13)),
+                ("Easter", Date::new(2025, 4, 20)),
+                ("Pentecost (Weeks)", Date::new(2025, 6, 2)),
+                ("Tabernacles", Date::new(2025, 10, 7)),
+                ("Advent", Date::new(2025, 11, 30)),
+            ],
+            dates
+        );
+    }
+
+    #[test]
+    fn feasts_for_2024() {
+        let dates: Vec<_> = feasts(2024).iter().map(|feast| feast.date).collect();
+        assert_eq!(
+            vec![
+                Date::new(2024, 3, 31),
+                Date::new(2024, 4, 23),
+                Date::new(2024, 6, 12),
+                Date::new(2024, 10, 17),
+                Date::new(2024, 12, 1),
+            ],
+            dates
+        );
+    }
+}
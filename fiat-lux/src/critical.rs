@@ -0,0 +1,152 @@
+//! Verses which modern critical editions of the Greek New Testament omit or set in brackets.
+//!
+//! The KJV and ASV follow (mostly) the Textus Receptus and include these, so readers comparing
+//! against a modern translation tend to find them missing without explanation. The notes are
+//! deliberately short; they say which early witnesses lack the text, not which side is right.
+
+use std::fmt;
+
+use crate::book::Book;
+
+use self::Treatment::{Bracketed, Omitted};
+
+/// How critical editions treat a disputed passage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Treatment {
+    /// Dropped from the text (and usually relegated to a footnote).
+    Omitted,
+
+    /// Kept, but set in brackets to mark it as doubtful.
+    Bracketed,
+}
+
+impl fmt::Display for Treatment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Treatment::Omitted => f.write_str("omitted"),
+            Treatment::Bracketed => f.write_str("bracketed"),
+        }
+    }
+}
+
+/// A run of disputed verses within a single chapter.
+#[derive(Clone, Copy, Debug)]
+pub struct CriticalNote {
+    pub book: Book,
+    pub chapter: u16,
+    pub first: u16,
+    pub last: u16,
+    pub treatment: Treatment,
+    pub note: &'static str,
+}
+
+impl CriticalNote {
+    /// The note covering a verse, if the verse is disputed.
+    pub fn find(book: Book, chapter: u16, verse: u16) -> Option<&'static CriticalNote> {
+        NOTES.iter().find(|note| {
+            note.book == book
+                && note.chapter == chapter
+                && (note.first..=note.last).contains(&verse)
+        })
+    }
+}
+
+impl fmt::Display for CriticalNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let CriticalNote {
+            book,
+            chapter,
+            first,
+            last,
+            treatment,
+            note,
+        } = self;
+
+        if first == last {
+            write!(f, "{book} {chapter}:{first} ({treatment}): {note}")
+        } else {
+            write!(f, "{book} {chapter}:{first}-{last} ({treatment}): {note}")
+        }
+    }
+}
+
+const fn note(
+    book: Book,
+    chapter: u16,
+    first: u16,
+    last: u16,
+    treatment: Treatment,
+    note: &'static str,
+) -> CriticalNote {
+    CriticalNote {
+        book,
+        chapter,
+        first,
+        last,
+        treatment,
+        note,
+    }
+}
+
+#[rustfmt::skip]
+static NOTES: &[CriticalNote] = &[
+    note(Book::Matthew, 17, 21, 21, Omitted,
+        "absent from Sinaiticus, Vaticanus and others; probably borrowed from Mark 9:29"),
+    note(Book::Matthew, 18, 11, 11, Omitted,
+        "absent from Sinaiticus, Vaticanus and others; probably borrowed from Luke 19:10"),
+    note(Book::Matthew, 23, 14, 14, Omitted,
+        "absent from the earliest manuscripts; probably borrowed from Mark 12:40 or Luke 20:47"),
+    note(Book::Mark, 7, 16, 16, Omitted,
+        "absent from Sinaiticus, Vaticanus and others; echoes Mark 4:9 and 4:23"),
+    note(Book::Mark, 9, 44, 44, Omitted,
+        "absent from Sinaiticus, Vaticanus and others; repeats verse 48"),
+    note(Book::Mark, 9, 46, 46, Omitted,
+        "absent from Sinaiticus, Vaticanus and others; repeats verse 48"),
+    note(Book::Mark, 11, 26, 26, Omitted,
+        "absent from Sinaiticus, Vaticanus and others; probably borrowed from Matthew 6:15"),
+    note(Book::Mark, 15, 28, 28, Omitted,
+        "absent from Sinaiticus, Alexandrinus, Vaticanus and others; probably borrowed from Luke 22:37"),
+    note(Book::Mark, 16, 9, 20, Bracketed,
+        "the longer ending of Mark is absent from Sinaiticus and Vaticanus, and other manuscripts end the book differently"),
+    note(Book::Luke, 17, 36, 36, Omitted,
+        "absent from the earliest manuscripts; probably borrowed from Matthew 24:40"),
+    note(Book::Luke, 22, 43, 44, Bracketed,
+        "absent from P75, Alexandrinus, Vaticanus and others"),
+    note(Book::Luke, 23, 17, 17, Omitted,
+        "absent from P75, Alexandrinus, Vaticanus and others; probably borrowed from Matthew 27:15 or Mark 15:6"),
+    note(Book::Luke, 23, 34, 34, Bracketed,
+        "the prayer \"Father, forgive them\" is absent from P75, Vaticanus and others"),
+    note(Book::John, 5, 4, 4, Omitted,
+        "the angel stirring the water is absent from P66, P75, Sinaiticus, Vaticanus and others"),
+    note(Book::John, 7, 53, 53, Bracketed,
+        "the woman caught in adultery (7:53-8:11) is absent from the earliest manuscripts and found in different places in later ones"),
+    note(Book::John, 8, 1, 11, Bracketed,
+        "the woman caught in adultery (7:53-8:11) is absent from the earliest manuscripts and found in different places in later ones"),
+    note(Book::Acts, 8, 37, 37, Omitted,
+        "the eunuch's confession is absent from P45, P74, Sinaiticus, Alexandrinus, Vaticanus and others"),
+    note(Book::Acts, 15, 34, 34, Omitted,
+        "absent from P74, Sinaiticus, Alexandrinus, Vaticanus and others"),
+    note(Book::Acts, 24, 7, 7, Omitted,
+        "absent (with the end of verse 6 and the start of verse 8) from P74, Sinaiticus, Alexandrinus, Vaticanus and others"),
+    note(Book::Acts, 28, 29, 29, Omitted,
+        "absent from P74, Sinaiticus, Alexandrinus, Vaticanus and others"),
+    note(Book::Romans, 16, 24, 24, Omitted,
+        "absent from P46, P61, Sinaiticus, Alexandrinus, Vaticanus and others; repeats the benediction of verse 20"),
+    note(Book::John1, 5, 7, 8, Omitted,
+        "the Johannine Comma (\"in heaven, the Father, the Word...\") appears in no Greek manuscript before the late middle ages"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{CriticalNote, Treatment};
+    use crate::book::Book;
+
+    #[test]
+    fn find() {
+        let note = CriticalNote::find(Book::Mark, 16, 12).unwrap();
+        assert_eq!(note.treatment, Treatment::Bracketed);
+        assert_eq!(note.first, 9);
+        assert!(CriticalNote::find(Book::Mark, 16, 8).is_none());
+        assert!(CriticalNote::find(Book::Acts, 8, 37).is_some());
+    }
+}
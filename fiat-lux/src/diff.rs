@@ -0,0 +1,76 @@
+/// One step in transforming one text into another.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Word-by-word diff of two texts, split on whitespace.
+///
+/// This is the classic longest-common-subsequence diff. Verses are short, so the quadratic table
+/// is nothing to worry about.
+pub fn diff_words<'a>(left: &'a str, right: &'a str) -> Vec<Change<'a>> {
+    diff_words_by(left, right, |a, b| a == b)
+}
+
+/// As [`diff_words`], but using `eq` to decide whether two words are the same.
+pub fn diff_words_by<'a>(
+    left: &'a str,
+    right: &'a str,
+    eq: impl Fn(&str, &str) -> bool,
+) -> Vec<Change<'a>> {
+    let left: Vec<_> = left.split_whitespace().collect();
+    let right: Vec<_> = right.split_whitespace().collect();
+
+    // lengths[i][j] is the length of the longest common subsequence of left[i..] and right[j..]
+    let mut lengths = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            lengths[i][j] = if eq(left[i], right[j]) {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::with_capacity(left.len().max(right.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        if eq(left[i], right[j]) {
+            changes.push(Change::Same(right[j]));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            changes.push(Change::Removed(left[i]));
+            i += 1;
+        } else {
+            changes.push(Change::Added(right[j]));
+            j += 1;
+        }
+    }
+
+    changes.extend(left[i..].iter().map(|&word| Change::Removed(word)));
+    changes.extend(right[j..].iter().map(|&word| Change::Added(word)));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_words, Change};
+
+    #[test]
+    fn diff() {
+        let changes = diff_words("believeth in him", "believeth on him");
+        assert_eq!(
+            vec![
+                Change::Same("believeth"),
+                Change::Removed("in"),
+                Change::Added("on"),
+                Change::Same("him"),
+            ],
+            changes
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! The translations embedded in the crate, as the build script leaves them: deflated a book at a
+//! time, which takes megabytes off the binary, and inflated as each book is needed.
+
+use std::{io::Read, sync::OnceLock};
+
+use crate::{book::Book, translation::Translation};
+
+pub(crate) struct Embedded {
+    compressed: &'static [u8],
+    /// Three fields for each book: its number and where its stream starts and ends.
+    books: &'static [u8],
+    /// Two fields for each verse, by id: the id and where its line starts in its book's text.
+    verses: &'static [u8],
+    text: OnceLock<String>,
+    book_texts: [OnceLock<String>; 66],
+}
+
+macro_rules! embedded {
+    ($name:literal) => {
+        Embedded {
+            compressed: include_bytes!(concat!(env!("OUT_DIR"), "/", $name, ".dat.z")),
+            books: include_bytes!(concat!(env!("OUT_DIR"), "/", $name, ".books")),
+            verses: include_bytes!(concat!(env!("OUT_DIR"), "/", $name, ".verses")),
+            text: OnceLock::new(),
+            book_texts: [const { OnceLock::new() }; 66],
+        }
+    };
+}
+
+static ASV: Embedded = embedded!("asv");
+static KJV: Embedded = embedded!("kjv");
+
+impl Embedded {
+    pub(crate) fn for_translation(translation: Translation) -> Option<&'static Self> {
+        match translation {
+            Translation::Asv => Some(&ASV),
+            Translation::Kjv => Some(&KJV),
+            Translation::Esv | Translation::Niv | Translation::User(_) => None,
+        }
+    }
+
+    /// The text as it's embedded, for telling whether it's changed without inflating it. An
+    /// empty dat file leaves this empty.
+    pub(crate) fn compressed(&self) -> &'static [u8] {
+        self.compressed
+    }
+
+    /// The whole dat file.
+    pub(crate) fn text(&'static self) -> &'static str {
+        self.text.get_or_init(|| {
+            (0..field_count(self.books, 3))
+                .map(|idx| inflate(self.stream(idx)))
+                .collect()
+        })
+    }
+
+    /// Just the lines of the dat file for one book, which is empty if it has none.
+    pub(crate) fn book(&'static self, book: Book) -> &'static str {
+        let number = book as u8;
+        self.book_texts[usize::from(number) - 1].get_or_init(|| {
+            (0..field_count(self.books, 3))
+                .find(|&idx| field(self.books, 3, idx, 0) == u32::from(number))
+                .map(|idx| inflate(self.stream(idx)))
+                .unwrap_or_default()
+        })
+    }
+
+    pub(crate) fn verse_count(&self) -> usize {
+        field_count(self.verses, 2)
+    }
+
+    /// The id of the verse at `idx` and where its line starts in its book.
+    pub(crate) fn verse(&self, idx: usize) -> (u32, usize) {
+        (
+            field(self.verses, 2, idx, 0),
+            field(self.verses, 2, idx, 1) as usize,
+        )
+    }
+
+    fn stream(&self, idx: usize) -> &'static [u8] {
+        let start = field(self.books, 3, idx, 1) as usize;
+        let end = field(self.books, 3, idx, 2) as usize;
+        &self.compressed[start..end]
+    }
+}
+
+fn field_count(table: &[u8], fields: usize) -> usize {
+    table.len() / (4 * fields)
+}
+
+/// The `field`th of the `fields` u32s in row `idx` of a table.
+fn field(table: &[u8], fields: usize, idx: usize, field: usize) -> u32 {
+    let at = (idx * fields + field) * 4;
+    u32::from_le_bytes(table[at..at + 4].try_into().unwrap())
+}
+
+/// The build script wrote these, so they're known to be good.
+fn inflate(compressed: &[u8]) -> String {
+    let mut text = String::new();
+    flate2::read::DeflateDecoder::new(compressed)
+        .read_to_string(&mut text)
+        .expect("embedded text is corrupt");
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ASV, KJV};
+    use crate::Book;
+
+    #[test]
+    fn round_trip() {
+        assert_eq!(include_str!("../resource/kjv.dat"), KJV.text());
+        assert_eq!(include_str!("../resource/asv.dat"), ASV.text());
+
+        let john = KJV.book(Book::John);
+        assert!(john.starts_with("43001001 In the beginning was the Word"));
+        assert!(john.lines().all(|line| line.starts_with("43")));
+    }
+}
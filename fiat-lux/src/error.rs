@@ -0,0 +1,125 @@
+use std::io;
+
+use crate::translation::Translation;
+
+pub trait AbbrevStr: AsRef<str> + Into<String> {
+    fn get(self, limit: usize) -> String {
+        let full = self.as_ref();
+
+        if full.len() > limit {
+            full[..limit].to_string() + "..."
+        } else {
+            self.into()
+        }
+    }
+}
+
+impl<T: AsRef<str> + Into<String>> AbbrevStr for T {}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    IO(#[from] io::Error),
+
+    #[cfg(feature = "index")]
+    #[error(transparent)]
+    Tantivy(#[from] tantivy::error::TantivyError),
+
+    #[cfg(feature = "index")]
+    #[error(transparent)]
+    TantivyDir(#[from] tantivy::directory::error::OpenDirectoryError),
+
+    #[cfg(feature = "index")]
+    #[error(transparent)]
+    TantivyRead(#[from] tantivy::directory::error::OpenReadError),
+
+    #[cfg(feature = "index")]
+    #[error(transparent)]
+    TantivyQuery(#[from] tantivy::query::QueryParserError),
+
+    #[cfg(feature = "sqlite")]
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("can't search for '{query}': {message}")]
+    BadQuery { query: String, message: String },
+
+    #[error("only a tantivy index can explain a search")]
+    NoExplain,
+
+    #[error("there's nowhere to keep added translations with the index in memory")]
+    NoDataDir,
+
+    #[cfg(not(feature = "index"))]
+    #[error("this build has no search index; it was built without the `index` feature")]
+    NoIndex,
+
+    #[cfg(feature = "sword")]
+    #[error("'{0}' isn't a Strong's number, like G25 or H430")]
+    BadStrongs(String),
+
+    #[cfg(feature = "sword")]
+    #[error("no SWORD module called '{0}'")]
+    UnknownModule(String),
+
+    #[error("unknown translation '{0}'; add it with `flux translation add`")]
+    UnknownTranslation(Translation),
+
+    #[error("{translation}: {message}")]
+    BadTranslation {
+        translation: Translation,
+        message: String,
+    },
+
+    #[error("{translation}: {message}")]
+    Remote {
+        translation: Translation,
+        message: String,
+    },
+}
+
+impl Error {
+    pub(crate) fn remote(translation: Translation, message: impl Into<String>) -> Self {
+        Error::Remote {
+            translation,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn bad_query(query: &str, message: impl Into<String>) -> Self {
+        Error::BadQuery {
+            query: query.into(),
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn bad_translation(translation: Translation, message: impl Into<String>) -> Self {
+        Error::BadTranslation {
+            translation,
+            message: message.into(),
+        }
+    }
+
+    #[cfg(feature = "remote")]
+    pub(crate) fn http(translation: Translation, e: ureq::Error) -> Self {
+        let message = match e {
+            ureq::Error::Status(401 | 403, _) => String::from("the api key was rejected"),
+            ureq::Error::Status(404, _) => String::from("the passage was not found"),
+            ureq::Error::Status(429, _) => {
+                String::from("too many requests; the api's rate limit has been reached")
+            }
+            ureq::Error::Status(code, response) => {
+                format!("server responded {code} {}", response.status_text())
+            }
+            ureq::Error::Transport(transport) => match transport.url() {
+                Some(url) => format!(
+                    "unable to reach {}: {}",
+                    url.host_str().unwrap_or("server"),
+                    transport.kind()
+                ),
+                None => transport.to_string(),
+            },
+        };
+        Error::remote(translation, message)
+    }
+}
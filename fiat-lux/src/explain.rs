@@ -0,0 +1,20 @@
+use crate::text::Text;
+
+/// How a search was understood and why each of its best hits scored as it did, for working out
+/// why a verse did or didn't match.
+#[derive(Clone, Debug, Default)]
+pub struct Explanation {
+    /// The query as the index runs it, one clause per line, indented beneath the clause it's in.
+    pub query: String,
+    /// The facets the search is confined to: the translation, and any books searched in.
+    pub facets: Vec<String>,
+    pub hits: Vec<ExplainedHit>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExplainedHit {
+    pub text: Text,
+    pub score: f32,
+    /// How the score was arrived at, as an indented tree of the values that went into it.
+    pub breakdown: String,
+}
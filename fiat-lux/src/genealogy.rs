@@ -0,0 +1,94 @@
+use std::fmt;
+
+/// People, one per line, as tab-separated fields: id, name, other names (comma-separated), the
+/// father's id, and the reference which names the father. Names repeat (there are two Josephs and
+/// two Jacobs in the line from Adam to Jesus), so fathers are given by id.
+static GENEALOGY_DAT: &str = include_str!("../resource/genealogy.dat");
+
+/// Someone in the line from Adam through Noah, Abraham and David to Jesus, or a close relative.
+#[derive(Clone, Copy, Debug)]
+pub struct Person {
+    id: u16,
+    pub name: &'static str,
+    aliases: &'static str,
+    father: Option<u16>,
+    pub reference: &'static str,
+}
+
+impl Person {
+    /// Everyone going by a name or one of their other names, ignoring case.
+    pub fn find(name: &str) -> Vec<Person> {
+        let name = name.trim();
+        people()
+            .filter(|person| person.names().any(|n| n.eq_ignore_ascii_case(name)))
+            .collect()
+    }
+
+    /// The person's name followed by any other names they go by, e.g. Abraham and Abram.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> {
+        let aliases = self.aliases;
+        std::iter::once(self.name).chain(
+            aliases
+                .split(',')
+                .map(str::trim)
+                .filter(|alias| !alias.is_empty()),
+        )
+    }
+
+    pub fn father(&self) -> Option<Person> {
+        let father = self.father?;
+        people().find(|person| person.id == father)
+    }
+
+    /// Ancestors from the earliest on record down to the father.
+    pub fn ancestors(&self) -> Vec<Person> {
+        let mut ancestors: Vec<_> = std::iter::successors(self.father(), Person::father).collect();
+        ancestors.reverse();
+        ancestors
+    }
+
+    /// Children in birth order, as far as the text gives it.
+    pub fn children(&self) -> Vec<Person> {
+        people()
+            .filter(|person| person.father == Some(self.id))
+            .collect()
+    }
+}
+
+impl fmt::Display for Person {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+fn people() -> impl Iterator<Item = Person> {
+    GENEALOGY_DAT.lines().filter_map(|line| {
+        let mut fields = line.split('\t');
+        Some(Person {
+            id: fields.next()?.parse().ok()?,
+            name: fields.next()?,
+            aliases: fields.next()?,
+            father: fields.next()?.parse().ok(),
+            reference: fields.next()?,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Person;
+
+    #[test]
+    fn lineage() {
+        let david = &Person::find("david")[0];
+        let ancestors: Vec<_> = david.ancestors().iter().map(|p| p.name).collect();
+        assert_eq!(Some(&"Adam"), ancestors.first());
+        assert_eq!(Some(&"Jesse"), ancestors.last());
+        assert!(david.children().iter().any(|child| child.name == "Solomon"));
+
+        assert_eq!(2, Person::find("Joseph").len());
+        let jesus = &Person::find("Jesus")[0];
+        assert_eq!("Joseph", jesus.father().unwrap().name);
+        assert!(jesus.ancestors().iter().any(|p| p.name == "David"));
+    }
+}
@@ -0,0 +1,105 @@
+//! Words the KJV uses which have dropped out of English, or out of the sense the KJV uses them in,
+//! with short modern equivalents.
+
+use std::{fmt, ops::Range};
+
+/// Archaic words, one per line, as tab-separated fields: the word, its other forms in the text
+/// (comma-separated) and what it means. Units of measure are left to [`crate::annotate_units`].
+static GLOSSARY_DAT: &str = include_str!("../resource/glossary.dat");
+
+/// An archaic word and its meaning, e.g. besom, "a broom".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gloss {
+    pub word: &'static str,
+    forms: &'static str,
+    pub meaning: &'static str,
+}
+
+impl Gloss {
+    /// Look up a word, in any of its forms, ignoring case.
+    pub fn find(word: &str) -> Option<Gloss> {
+        let word = word.trim();
+        glossary().find(|gloss| gloss.forms().any(|form| form.eq_ignore_ascii_case(word)))
+    }
+
+    /// The word followed by its other forms, e.g. bewray and bewrayeth.
+    pub fn forms(&self) -> impl Iterator<Item = &'static str> {
+        let forms = self.forms;
+        std::iter::once(self.word).chain(
+            forms
+                .split(',')
+                .map(str::trim)
+                .filter(|form| !form.is_empty()),
+        )
+    }
+
+    /// Where each archaic word in `text` is, with its gloss.
+    pub fn find_in(text: &str) -> Vec<(Range<usize>, Gloss)> {
+        let mut found = Vec::new();
+        let mut start = None;
+        // A trailing space closes the last word.
+        for (idx, c) in text.char_indices().chain([(text.len(), ' ')]) {
+            match (start, c.is_alphabetic()) {
+                (None, true) => start = Some(idx),
+                (Some(from), false) => {
+                    if let Some(gloss) = Gloss::find(&text[from..idx]) {
+                        found.push((from..idx, gloss));
+                    }
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        found
+    }
+}
+
+impl fmt::Display for Gloss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.word, self.meaning)
+    }
+}
+
+/// Every word in the glossary, alphabetically.
+pub fn glossary() -> impl Iterator<Item = Gloss> {
+    GLOSSARY_DAT.lines().filter_map(|line| {
+        let mut fields = line.split('\t');
+        Some(Gloss {
+            word: fields.next()?,
+            forms: fields.next()?,
+            meaning: fields.next()?,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Gloss;
+
+    #[test]
+    fn find() {
+        assert_eq!(
+            super::GLOSSARY_DAT.lines().count(),
+            super::glossary().count()
+        );
+
+        let besom = Gloss::find("Besom").unwrap();
+        assert_eq!("besom: a broom", besom.to_string());
+        assert_eq!("bewray", Gloss::find("bewrayeth").unwrap().word);
+        assert!(Gloss::find("broom").is_none());
+
+        let text = "And they were astonied, and wist not what to say; for the fuller's wife...";
+        let found: Vec<_> = Gloss::find_in(text)
+            .into_iter()
+            .map(|(range, gloss)| (&text[range], gloss.word))
+            .collect();
+        assert_eq!(
+            vec![
+                ("astonied", "astonied"),
+                ("wist", "wist"),
+                ("fuller", "fuller")
+            ],
+            found
+        );
+    }
+}
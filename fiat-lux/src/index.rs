@@ -0,0 +1,299 @@
+//! Looking after the search index itself: what's in it, how big it is, and throwing it away.
+
+// Without the index there's nothing to stamp, but clear_index still tidies up after a full build.
+#![cfg_attr(not(feature = "index"), allow(dead_code))]
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "index")]
+use tantivy::{
+    collector::{Count, FacetCollector},
+    directory::MmapDirectory,
+    merge_policy::NoMergePolicy,
+    query::AllQuery,
+    Index, IndexWriter,
+};
+
+use crate::{embedded::Embedded, Result, Translation};
+
+/// Where the index is kept under the data directory.
+pub(crate) fn index_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("bible_idx")
+}
+
+/// Bumped whenever the index's schema changes, so an index built by an older version can be told
+/// apart from a current one.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// How the index is built and written to. The defaults suit a desktop; a Raspberry Pi wants a
+/// smaller heap, and a big machine can build faster with more threads.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IndexOptions {
+    /// Leave the embedded translations' text out of the index; see
+    /// [`Bible::open_lean`](crate::Bible::open_lean).
+    pub lean: bool,
+    /// Memory for the writer, in bytes, shared among its threads, each of which needs at least
+    /// 15 MB. Otherwise 500 MB to build the whole index and 50 MB to index a single translation.
+    pub heap_bytes: Option<usize>,
+    /// Threads to index with; otherwise one per core, up to eight.
+    pub threads: Option<usize>,
+    pub merge: MergePolicy,
+}
+
+/// Whether the writer merges the index's segments as it goes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// tantivy's usual policy, merging segments of similar size in the background
+    #[default]
+    Log,
+    /// never merge, which spares memory and disk traffic while building at the cost of a few
+    /// more files to search
+    None,
+}
+
+#[cfg(feature = "index")]
+impl IndexOptions {
+    /// A writer for `index` set up as asked, with `default_heap` bytes unless told otherwise.
+    pub(crate) fn writer(
+        &self,
+        index: &Index,
+        default_heap: usize,
+    ) -> tantivy::Result<IndexWriter> {
+        let heap = self.heap_bytes.unwrap_or(default_heap);
+        let writer = match self.threads {
+            Some(threads) => index.writer_with_num_threads(threads, heap)?,
+            None => index.writer(heap)?,
+        };
+        if self.merge == MergePolicy::None {
+            writer.set_merge_policy(Box::new(NoMergePolicy));
+        }
+        Ok(writer)
+    }
+}
+
+/// What's in the search index, for `flux index status`.
+#[derive(Clone, Debug)]
+pub struct IndexStatus {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub documents: usize,
+    /// Each indexed translation and how many verses it has, in the order the index lists them.
+    pub translations: Vec<(Translation, u64)>,
+    /// The schema the index was built to, if it was built by a version which kept track.
+    pub schema_version: Option<u32>,
+    /// Whether the index leaves the embedded translations' text out, reading it from the binary.
+    pub lean: bool,
+    /// Whether it was built from this build's text and schema. If not, it's built again the next
+    /// time it's opened.
+    pub up_to_date: bool,
+}
+
+#[cfg(feature = "index")]
+impl IndexStatus {
+    /// What's in the index kept in `data_dir`, or `None` if there isn't one yet. Unlike
+    /// [`Bible::open`](crate::Bible::open), this never builds one.
+    pub fn read(data_dir: &Path) -> Result<Option<Self>> {
+        let path = index_path(data_dir);
+        if !path.is_dir() || !Index::exists(&MmapDirectory::open(&path)?)? {
+            return Ok(None);
+        }
+
+        let index = Index::open_in_dir(&path)?;
+        let searcher = index.reader()?.searcher();
+        let documents = searcher.search(&AllQuery, &Count)?;
+
+        let mut collector = FacetCollector::for_field("translation");
+        collector.add_facet("/");
+        let counts = searcher.search(&AllQuery, &collector)?;
+        let translations = counts
+            .get("/")
+            .filter_map(|(facet, count)| {
+                let name = facet.to_path().first()?.parse().ok()?;
+                Some((name, count))
+            })
+            .collect();
+
+        let stamp = Stamp::read(data_dir);
+        Ok(Some(Self {
+            bytes: dir_size(&path)?,
+            path,
+            documents,
+            translations,
+            schema_version: stamp.map(|stamp| stamp.schema),
+            lean: stamp.is_some_and(|stamp| stamp.lean),
+            up_to_date: stamp.is_some_and(|stamp| stamp == Stamp::current().with_lean(stamp.lean)),
+        }))
+    }
+}
+
+/// Delete the index kept in `data_dir`, and the SQLite file if there is one, returning whether
+/// there was either. They're built again the next time the Bible is opened, user translations and
+/// all.
+pub fn clear_index(data_dir: &Path) -> Result<bool> {
+    Stamp::remove(data_dir)?;
+    let index = match fs::remove_dir_all(index_path(data_dir)) {
+        Ok(()) => true,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => false,
+        Err(e) => return Err(e.into()),
+    };
+    let sqlite = match fs::remove_file(data_dir.join("bible.sqlite")) {
+        Ok(()) => true,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => false,
+        Err(e) => return Err(e.into()),
+    };
+    Ok(index || sqlite)
+}
+
+/// What an index was built from: the schema version and a hash of the embedded text, and whether
+/// it's lean. It's kept beside the index, tab-separated, so that after an upgrade which changes
+/// either of the first two the index is built again rather than answering from old text or failing
+/// to open. Asking for the other kind of index builds it again too.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Stamp {
+    schema: u32,
+    content: u64,
+    lean: bool,
+}
+
+impl Stamp {
+    pub(crate) fn current() -> Self {
+        let mut hasher = Fnv::default();
+        for translation in Translation::ALL {
+            hasher.write(translation.to_string().as_bytes());
+            let embedded = Embedded::for_translation(translation);
+            hasher.write(embedded.map_or(&[], |embedded| embedded.compressed()));
+        }
+
+        Self {
+            schema: SCHEMA_VERSION,
+            content: hasher.0,
+            lean: false,
+        }
+    }
+
+    pub(crate) fn with_lean(self, lean: bool) -> Self {
+        Self { lean, ..self }
+    }
+
+    fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join("bible_idx.meta")
+    }
+
+    pub(crate) fn read(data_dir: &Path) -> Option<Self> {
+        Self::decode(&fs::read_to_string(Self::path(data_dir)).ok()?)
+    }
+
+    pub(crate) fn write(self, data_dir: &Path) -> io::Result<()> {
+        fs::write(Self::path(data_dir), self.encode() + "\n")
+    }
+
+    pub(crate) fn encode(self) -> String {
+        let Self {
+            schema,
+            content,
+            lean,
+        } = self;
+        let lean = if lean { "\tlean" } else { "" };
+        format!("{schema}\t{content:016x}{lean}")
+    }
+
+    pub(crate) fn decode(text: &str) -> Option<Self> {
+        let mut fields = text.trim().split('\t');
+        let schema = fields.next()?.parse().ok()?;
+        let content = u64::from_str_radix(fields.next()?, 16).ok()?;
+        let lean = match fields.next() {
+            None => false,
+            Some("lean") => true,
+            Some(_) => return None,
+        };
+        Some(Self {
+            schema,
+            content,
+            lean,
+        })
+    }
+
+    pub(crate) fn remove(data_dir: &Path) -> io::Result<()> {
+        match fs::remove_file(Self::path(data_dir)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 64-bit FNV-1a, taken a word at a time, which is plenty to notice a changed text and, unlike
+/// the standard library's hasher, gives the same answer from one build to the next.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv {
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    fn write(&mut self, bytes: &[u8]) {
+        let mut words = bytes.chunks_exact(8);
+        for word in words.by_ref() {
+            let word = u64::from_le_bytes(word.try_into().unwrap());
+            self.0 = (self.0 ^ word).wrapping_mul(Self::PRIME);
+        }
+        for &byte in words.remainder() {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(Self::PRIME);
+        }
+        self.0 = (self.0 ^ bytes.len() as u64).wrapping_mul(Self::PRIME);
+    }
+}
+
+#[cfg(feature = "index")]
+fn dir_size(path: &Path) -> io::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stamp;
+
+    #[test]
+    fn stamps() {
+        let dir = std::env::temp_dir().join(format!("fiat-lux-stamp-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(None, Stamp::read(&dir));
+
+        let current = Stamp::current();
+        assert_eq!(current, Stamp::current());
+        current.write(&dir).unwrap();
+        assert_eq!(Some(current), Stamp::read(&dir));
+
+        let older = Stamp {
+            schema: current.schema - 1,
+            ..current
+        };
+        older.write(&dir).unwrap();
+        assert_ne!(Some(current), Stamp::read(&dir));
+
+        let lean = current.with_lean(true);
+        lean.write(&dir).unwrap();
+        assert_eq!(Some(lean), Stamp::read(&dir));
+        assert_ne!(Some(current), Stamp::read(&dir));
+
+        Stamp::remove(&dir).unwrap();
+        assert_eq!(None, Stamp::read(&dir));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,830 @@
+//! Offline Bible text, verse lookup and full-text search.
+//!
+//! The [`Bible`] type owns the search index and is the main entry point:
+//!
+//! ```no_run
+//! use fiat_lux::{Bible, Book, PartialLocation, Translation};
+//!
+//! let bible = Bible::open()?;
+//! let location = PartialLocation { chapter: 3, verse: Some(16), end: None };
+//! for text in bible.lookup(Book::John, Some(location), Translation::Kjv)? {
+//!     println!("{} {}:{} {}", text.book, text.chapter, text.verse, text.content);
+//! }
+//! # Ok::<(), fiat_lux::Error>(())
+//! ```
+
+mod book;
+#[cfg(feature = "remote")]
+mod cache;
+mod calendar;
+mod critical;
+mod diff;
+mod embedded;
+mod error;
+mod explain;
+mod genealogy;
+mod glossary;
+mod index;
+mod location;
+mod offsets;
+#[cfg(feature = "sword")]
+mod original;
+mod outline;
+mod parallels;
+mod place;
+mod plan;
+mod quotation;
+mod reference;
+#[cfg(feature = "remote")]
+mod remote;
+#[cfg(not(feature = "index"))]
+mod scan;
+mod scope;
+#[cfg(feature = "index")]
+mod search;
+mod similar;
+mod spelling;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod stats;
+#[cfg(feature = "sword")]
+mod sword;
+mod text;
+mod topic;
+mod translation;
+mod units;
+mod user;
+mod usfm;
+mod votd;
+
+use std::{collections::HashSet, io, ops::Range, path::PathBuf};
+
+use directories::ProjectDirs;
+#[cfg(feature = "index")]
+use tantivy::{Index, IndexWriter, Term};
+
+pub use book::{Book, Genre, ParseBookError};
+pub use calendar::{feasts, Date, Feast};
+pub use critical::{CriticalNote, Treatment};
+pub use diff::{diff_words, diff_words_by, Change};
+pub use error::{AbbrevStr, Error};
+pub use explain::{ExplainedHit, Explanation};
+pub use genealogy::Person;
+pub use glossary::{glossary, Gloss};
+pub use index::{clear_index, IndexOptions, IndexStatus, MergePolicy, SCHEMA_VERSION};
+pub use location::{Location, ParseLocationError, ParsePassageError, PartialLocation, Passage};
+pub use offsets::lookup_embedded;
+#[cfg(feature = "sword")]
+pub use original::original_word;
+pub use outline::{outline, outlined_books};
+pub use place::{places, Place};
+pub use plan::{Plan, Reading};
+pub use quotation::{quotations, Quotation, QuotationKind};
+pub use reference::{ParseProviderError, Reference, ReferenceLocator, ReferenceProvider};
+#[cfg(feature = "remote")]
+pub use remote::{RemoteConfig, RetryPolicy};
+#[cfg(not(feature = "index"))]
+pub use scan::search_texts;
+pub use scope::Scope;
+#[cfg(feature = "index")]
+pub use search::search_texts;
+pub use spelling::modernize;
+pub use stats::{Collocate, Stats, Study, TermCount};
+#[cfg(feature = "sword")]
+pub use sword::{Strongs, Tag};
+pub use text::{parse_verses_with_id, Text};
+pub use topic::{topic, topical, topics};
+pub use translation::{Edition, ParseTranslationError, Translation, UserTranslation};
+pub use units::{annotate_units, Measure};
+pub use user::added_translations;
+pub use usfm::{usfm_to_dat, ParseUsfmError};
+pub use votd::verse_of_the_day;
+
+#[cfg(feature = "index")]
+use search::SearchFields;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A searchable corpus of every embedded translation, plus any the user has added.
+///
+/// Opening the corpus will build the search index on first use, which takes a little while.
+/// Without the `index` feature there's no index at all: lookups read the text, searches scan it
+/// for words within a small edit distance of the query's, and the [`IndexOptions`] go unused.
+pub struct Bible {
+    backend: Backend,
+    /// Where added translations are kept, which there mightn't be with an index in memory.
+    data_dir: Option<PathBuf>,
+    #[cfg(feature = "remote")]
+    remote: Option<remote::Remote>,
+    #[cfg(feature = "sword")]
+    sword: Option<sword::Sword>,
+    /// How the index was built, which adding or reindexing a translation follows too.
+    #[cfg(feature = "index")]
+    options: IndexOptions,
+}
+
+impl Bible {
+    /// Open (or create) the search index in the user's data directory.
+    pub fn open() -> Result<Self> {
+        Self::open_in(data_dir()?)
+    }
+
+    /// Like [`Bible::open`], but keeping the index (and any translations the user adds) in
+    /// `data_dir` instead.
+    pub fn open_in(data_dir: PathBuf) -> Result<Self> {
+        Self::open_with(data_dir, IndexOptions::default())
+    }
+
+    /// Like [`Bible::open_in`], but with an index which only says where each verse of the
+    /// embedded translations is, and reads the verse itself from the text built into the binary.
+    /// That takes about half off the index on disk. Opening an index built the other way builds it
+    /// again.
+    pub fn open_lean(data_dir: PathBuf) -> Result<Self> {
+        let options = IndexOptions {
+            lean: true,
+            ..Default::default()
+        };
+        Self::open_with(data_dir, options)
+    }
+
+    /// Like [`Bible::open_in`], building and writing to the index as `options` say.
+    #[cfg(feature = "index")]
+    pub fn open_with(data_dir: PathBuf, options: IndexOptions) -> Result<Self> {
+        let (index, fields) = search::initialize_search(&data_dir, &options)?;
+        let bible = Self::with_backend(Backend::Tantivy { index, fields }, Some(data_dir));
+        Ok(Self { options, ..bible })
+    }
+
+    /// Like [`Bible::open_in`]. There's no index to build in this build, so `options` make no
+    /// difference.
+    #[cfg(not(feature = "index"))]
+    pub fn open_with(data_dir: PathBuf, _options: IndexOptions) -> Result<Self> {
+        let scan = scan::Scan::new(Some(data_dir.clone()));
+        Ok(Self::with_backend(Backend::Scan(scan), Some(data_dir)))
+    }
+
+    /// Build the index in memory rather than on disk, for when there's nowhere to keep it, e.g. a
+    /// read-only container. That means building it every time, which takes a little while.
+    /// Translations the user has added are read from `data_dir`, if given.
+    pub fn open_in_memory(data_dir: Option<PathBuf>) -> Result<Self> {
+        Self::open_in_memory_with(data_dir, IndexOptions::default())
+    }
+
+    /// Like [`Bible::open_in_memory`], building the index as `options` say. It's never lean, since
+    /// it's never kept.
+    #[cfg(feature = "index")]
+    pub fn open_in_memory_with(data_dir: Option<PathBuf>, options: IndexOptions) -> Result<Self> {
+        let options = IndexOptions {
+            lean: false,
+            ..options
+        };
+        let (index, fields) = search::initialize_search_in_ram(data_dir.as_deref(), &options)?;
+        let bible = Self::with_backend(Backend::Tantivy { index, fields }, data_dir);
+        Ok(Self { options, ..bible })
+    }
+
+    /// Like [`Bible::open_in_memory`]; without an index there's nothing to build in memory either.
+    #[cfg(not(feature = "index"))]
+    pub fn open_in_memory_with(data_dir: Option<PathBuf>, _options: IndexOptions) -> Result<Self> {
+        let scan = scan::Scan::new(data_dir.clone());
+        Ok(Self::with_backend(Backend::Scan(scan), data_dir))
+    }
+
+    /// Like [`Bible::open_in`], but keeping the verses and their index in a single SQLite file,
+    /// `bible.sqlite`, rather than a tantivy directory. Searches take the same syntax.
+    #[cfg(feature = "sqlite")]
+    pub fn open_sqlite(data_dir: PathBuf) -> Result<Self> {
+        let store = sqlite::Store::open(&data_dir)?;
+        Ok(Self::with_backend(Backend::Sqlite(store), Some(data_dir)))
+    }
+
+    /// Like [`Bible::open_in_memory`], with an SQLite database in memory.
+    #[cfg(feature = "sqlite")]
+    pub fn open_sqlite_in_memory(data_dir: Option<PathBuf>) -> Result<Self> {
+        let store = sqlite::Store::open_in_memory(data_dir.as_deref())?;
+        Ok(Self::with_backend(Backend::Sqlite(store), data_dir))
+    }
+
+    fn with_backend(backend: Backend, data_dir: Option<PathBuf>) -> Self {
+        Self {
+            backend,
+            data_dir,
+            #[cfg(feature = "remote")]
+            remote: None,
+            #[cfg(feature = "sword")]
+            sword: None,
+            #[cfg(feature = "index")]
+            options: IndexOptions::default(),
+        }
+    }
+
+    /// Enable lookups against remote translations (see [`Translation::is_remote`]).
+    #[cfg(feature = "remote")]
+    pub fn with_remote(mut self, config: RemoteConfig) -> Self {
+        self.remote = Some(remote::Remote::new(config));
+        self
+    }
+
+    /// Enable lookups against the Bible modules installed in SWORD libraries such as ~/.sword.
+    ///
+    /// Modules are addressed by name, like user translations, which take priority if the names
+    /// collide. They can't be searched, since they aren't in the index.
+    #[cfg(feature = "sword")]
+    pub fn with_sword(mut self, libraries: &[PathBuf]) -> Self {
+        self.sword = Some(sword::Sword::load(libraries));
+        self
+    }
+
+    /// The names of every readable SWORD module.
+    #[cfg(feature = "sword")]
+    pub fn sword_modules(&self) -> Vec<String> {
+        self.sword
+            .iter()
+            .flat_map(|sword| sword.names())
+            .map(String::from)
+            .collect()
+    }
+
+    /// Each English word or phrase a SWORD module tagged with Strong's numbers, such as
+    /// CrossWire's KJV, uses for the given one, with how often, most used first.
+    #[cfg(feature = "sword")]
+    pub fn renderings(&self, module: &str, strongs: Strongs) -> Result<Vec<(String, usize)>> {
+        self.sword
+            .as_ref()
+            .map(|sword| sword.renderings(module, strongs))
+            .transpose()?
+            .flatten()
+            .ok_or_else(|| Error::UnknownModule(module.to_string()))
+    }
+
+    /// The words in a passage which a SWORD module tagged with Strong's numbers, such as "loved"
+    /// for G25 in John 3:16.
+    #[cfg(feature = "sword")]
+    pub fn tags(
+        &self,
+        module: &str,
+        book: Book,
+        location: Option<PartialLocation>,
+    ) -> Result<Vec<Tag>> {
+        self.sword
+            .as_ref()
+            .map(|sword| sword.tags(module, book, location))
+            .transpose()?
+            .flatten()
+            .ok_or_else(|| Error::UnknownModule(module.to_string()))
+    }
+
+    /// Retrieve a whole book, a chapter, or a single verse.
+    pub fn lookup(
+        &self,
+        book: Book,
+        location: Option<PartialLocation>,
+        translation: Translation,
+    ) -> Result<Vec<Text>> {
+        if translation.is_remote() {
+            return self.lookup_remote(book, location, translation);
+        }
+
+        #[cfg(feature = "sword")]
+        if let Some(sword) = self.sword_for(translation) {
+            return Ok(sword.lookup(book, location, translation)?);
+        }
+
+        self.check_available(translation)?;
+
+        let texts = match &self.backend {
+            #[cfg(feature = "index")]
+            Backend::Tantivy { index, fields } => {
+                search::search_by_book_and_location(index, fields, book, location, translation)?
+            }
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite(store) => store.lookup(book, location, translation)?,
+            #[cfg(not(feature = "index"))]
+            Backend::Scan(scan) => scan.lookup(book, location, translation)?,
+        };
+
+        // An embedded translation taken out of the index is still there to read.
+        if texts.is_empty() {
+            if let Some(texts) = offsets::lookup_embedded(book, location, translation) {
+                return Ok(texts);
+            }
+        }
+        Ok(texts)
+    }
+
+    /// Full-text search against a single translation, returning at most `limit` verses in
+    /// canonical order.
+    pub fn search(&self, query: &str, translation: Translation, limit: usize) -> Result<Vec<Text>> {
+        self.search_in(query, translation, &[], limit)
+    }
+
+    /// Like [`Bible::search`], but only within the given books or testaments. No scopes means the
+    /// whole Bible.
+    pub fn search_in(
+        &self,
+        query: &str,
+        translation: Translation,
+        scopes: &[Scope],
+        limit: usize,
+    ) -> Result<Vec<Text>> {
+        self.check_searchable(translation)?;
+        match &self.backend {
+            #[cfg(feature = "index")]
+            Backend::Tantivy { index, fields } => {
+                search::search(index, fields, query, translation, scopes, limit)
+            }
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite(store) => {
+                let mut texts: Vec<_> = store
+                    .search(query, translation, scopes, Some(limit))?
+                    .into_iter()
+                    .map(|(_, text)| text)
+                    .collect();
+                texts.sort();
+                Ok(texts)
+            }
+            #[cfg(not(feature = "index"))]
+            Backend::Scan(scan) => {
+                let mut texts: Vec<_> = scan
+                    .search(query, translation, scopes, Some(limit))?
+                    .into_iter()
+                    .map(|(_, text)| text)
+                    .collect();
+                texts.sort();
+                Ok(texts)
+            }
+        }
+    }
+
+    /// Every verse matching a query, however many there are, with its relevance score, best
+    /// first. Scores only mean anything relative to others from the same query.
+    pub fn search_scored(
+        &self,
+        query: &str,
+        translation: Translation,
+        scopes: &[Scope],
+    ) -> Result<Vec<(f32, Text)>> {
+        self.check_searchable(translation)?;
+        match &self.backend {
+            #[cfg(feature = "index")]
+            Backend::Tantivy { index, fields } => {
+                search::search_scored(index, fields, query, translation, scopes)
+            }
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite(store) => store.search(query, translation, scopes, None),
+            #[cfg(not(feature = "index"))]
+            Backend::Scan(scan) => scan.search(query, translation, scopes, None),
+        }
+    }
+
+    /// How a query is understood, and the scores of its best `limit` hits broken down, best
+    /// first. Only the tantivy index can say; the other backends score differently.
+    pub fn explain(
+        &self,
+        query: &str,
+        translation: Translation,
+        scopes: &[Scope],
+        limit: usize,
+    ) -> Result<Explanation> {
+        self.check_searchable(translation)?;
+        match &self.backend {
+            #[cfg(feature = "index")]
+            Backend::Tantivy { index, fields } => {
+                search::explain(index, fields, query, translation, scopes, limit)
+            }
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite(_) => Err(Error::NoExplain),
+            // Without the index, nothing else reads the query.
+            #[cfg(not(feature = "index"))]
+            Backend::Scan(_) => {
+                let _ = (query, scopes, limit);
+                Err(Error::NoExplain)
+            }
+        }
+    }
+
+    /// How many verses in each book match a search query, in canonical order.
+    pub fn count_by_book(
+        &self,
+        query: &str,
+        translation: Translation,
+        scopes: &[Scope],
+    ) -> Result<Vec<(Book, u64)>> {
+        self.check_searchable(translation)?;
+        let mut books: Vec<(Book, u64)> = Vec::new();
+        for (book, _, count) in self.count_by_chapter(query, translation, scopes)? {
+            match books.last_mut() {
+                Some((last, total)) if *last == book => *total += count,
+                _ => books.push((book, count)),
+            }
+        }
+        Ok(books)
+    }
+
+    /// How many verses in each chapter match a search query, in canonical order. Chapters without
+    /// a match are left out.
+    pub fn count_by_chapter(
+        &self,
+        query: &str,
+        translation: Translation,
+        scopes: &[Scope],
+    ) -> Result<Vec<(Book, u16, u64)>> {
+        self.check_searchable(translation)?;
+        match &self.backend {
+            #[cfg(feature = "index")]
+            Backend::Tantivy { index, fields } => {
+                search::count_by_chapter(index, fields, query, translation, scopes)
+            }
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite(store) => store.count_by_chapter(query, translation, scopes),
+            #[cfg(not(feature = "index"))]
+            Backend::Scan(scan) => scan.count_by_chapter(query, translation, scopes),
+        }
+    }
+
+    /// Byte ranges of the words in `content` matching a search query, for highlighting results.
+    pub fn highlights(&self, query: &str, content: &str) -> Result<Vec<Range<usize>>> {
+        #[cfg(not(feature = "index"))]
+        use scan::highlights;
+        #[cfg(feature = "index")]
+        use search::highlights;
+        highlights(query, content)
+    }
+
+    /// How many times a word or phrase occurs in a translation, in how many verses and books.
+    pub fn count(&self, phrase: &str, translation: Translation) -> Result<TermCount> {
+        let found = self.concordance(phrase, translation, &[])?;
+        let books: HashSet<_> = found.iter().map(|(text, _)| text.book).collect();
+        Ok(TermCount {
+            occurrences: found.iter().map(|(_, ranges)| ranges.len()).sum(),
+            verses: found.len(),
+            books: books.len(),
+        })
+    }
+
+    /// Every verse where a word or phrase occurs, within the given books or testaments, with the
+    /// byte range of each occurrence. No scopes means the whole Bible.
+    pub fn concordance(
+        &self,
+        phrase: &str,
+        translation: Translation,
+        scopes: &[Scope],
+    ) -> Result<Vec<(Text, Vec<Range<usize>>)>> {
+        self.check_searchable(translation)?;
+        match &self.backend {
+            #[cfg(feature = "index")]
+            Backend::Tantivy { index, fields } => {
+                search::find_term(index, fields, phrase, translation, scopes)
+            }
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite(store) => store.find_term(phrase, translation, scopes),
+            #[cfg(not(feature = "index"))]
+            Backend::Scan(scan) => scan.find_term(phrase, translation, scopes),
+        }
+    }
+
+    /// The words found within `window` words of a word or phrase, most frequent first, with how
+    /// strongly each is associated with it compared to the rest of the given books or testaments.
+    pub fn collocates(
+        &self,
+        phrase: &str,
+        translation: Translation,
+        scopes: &[Scope],
+        window: usize,
+    ) -> Result<Vec<Collocate>> {
+        let concordance = self.concordance(phrase, translation, scopes)?;
+        let occurrences = concordance.iter().map(|(_, ranges)| ranges.len()).sum();
+        let near = stats::near_words(&concordance, window);
+
+        let words: Vec<_> = near.iter().map(|(word, _)| word.as_str()).collect();
+        let (with_word, verses) = match &self.backend {
+            #[cfg(feature = "index")]
+            Backend::Tantivy { index, fields } => (
+                search::verse_counts(index, fields, &words, translation, scopes)?,
+                search::verse_total(index, fields, translation, scopes)?,
+            ),
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite(store) => (
+                store.verse_counts(&words, translation, scopes)?,
+                store.verse_total(translation, scopes)?,
+            ),
+            #[cfg(not(feature = "index"))]
+            Backend::Scan(scan) => (
+                scan.verse_counts(&words, translation, scopes)?,
+                scan.verse_total(translation, scopes)?,
+            ),
+        };
+
+        Ok(near
+            .into_iter()
+            .zip(with_word)
+            .map(|((word, count), with_word)| {
+                Collocate::new(word, count, occurrences, with_word, verses)
+            })
+            .collect())
+    }
+
+    /// The chapters which read most like a given one, by TF-IDF cosine similarity, most similar
+    /// first. Only indexed translations can be compared this way.
+    pub fn similar_chapters(
+        &self,
+        book: Book,
+        chapter: u16,
+        translation: Translation,
+        limit: usize,
+    ) -> Result<Vec<(Book, u16, f64)>> {
+        let texts = self.whole_text(translation)?;
+        let mut similar = similar::similar_chapters(&texts, book, chapter);
+        similar.truncate(limit);
+        Ok(similar)
+    }
+
+    /// Groups of near-identical verses in a translation, in canonical order, each with the lowest
+    /// similarity between two of its verses. Similarity is the overlap between the pairs of
+    /// adjacent words in two verses, from 0 to 1. Verses shorter than `min_words` are skipped,
+    /// since two-word verses match far too easily.
+    pub fn parallels(
+        &self,
+        translation: Translation,
+        threshold: f64,
+        min_words: usize,
+    ) -> Result<Vec<(Vec<Text>, f64)>> {
+        let texts = self.whole_text(translation)?;
+        Ok(parallels::parallels(&texts, threshold, min_words)
+            .into_iter()
+            .map(|(members, similarity)| {
+                let verses = members.into_iter().map(|idx| texts[idx].clone()).collect();
+                (verses, similarity)
+            })
+            .collect())
+    }
+
+    /// The Old Testament quotations in a New Testament verse, each with the byte range of the
+    /// quoting words, found by comparing the verse against the quoted passage in the same
+    /// translation.
+    pub fn quotations_in(
+        &self,
+        text: &Text,
+        translation: Translation,
+    ) -> Result<Vec<(Range<usize>, Quotation)>> {
+        let verse = Passage {
+            book: text.book,
+            location: Some(PartialLocation {
+                chapter: text.chapter,
+                verse: Some(text.verse),
+                end: None,
+            }),
+        };
+
+        let mut quotations = Vec::new();
+        for quotation in Quotation::find(&verse) {
+            if !quotation.new.overlaps(&verse) {
+                continue;
+            }
+
+            let old: Vec<_> = self
+                .lookup(quotation.old.book, quotation.old.location, translation)?
+                .into_iter()
+                .map(|text| text.content)
+                .collect();
+            let span = quotation::quoted_span(&text.content, &old.join(" "));
+            quotations.push((span, quotation));
+        }
+
+        Ok(quotations)
+    }
+
+    /// Register a translation from the text of a dat file and add it to the search index.
+    ///
+    /// Adding a translation under a name that's already registered replaces it. Returns the
+    /// number of verses indexed.
+    pub fn add_translation(&self, translation: Translation, text: &str) -> Result<usize> {
+        if !matches!(translation, Translation::User(_)) {
+            return Err(Error::bad_translation(
+                translation,
+                "built-in translations can't be replaced",
+            ));
+        }
+
+        let count = parse_verses_with_id(text).count();
+        if count == 0 {
+            return Err(Error::bad_translation(translation, "no verses found"));
+        }
+
+        match &self.backend {
+            #[cfg(feature = "index")]
+            Backend::Tantivy { index, fields } => {
+                let mut writer = self.writer(index)?;
+                writer.delete_term(translation_term(fields, translation));
+                search::write_index(translation, text, true, fields, &mut writer)?;
+            }
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite(store) => store.add_translation(translation, text)?,
+            // Scans read the file saved below.
+            #[cfg(not(feature = "index"))]
+            Backend::Scan(_) => {}
+        }
+        let data_dir = self.data_dir.as_deref().ok_or(Error::NoDataDir)?;
+        user::save(data_dir, translation, text)?;
+        Ok(count)
+    }
+
+    /// Remove a user-supplied translation from the registry and the search index.
+    pub fn remove_translation(&self, translation: Translation) -> Result<()> {
+        let Some(data_dir) = self
+            .data_dir
+            .as_deref()
+            .filter(|_| self.is_registered(translation))
+        else {
+            return Err(Error::UnknownTranslation(translation));
+        };
+
+        match &self.backend {
+            #[cfg(feature = "index")]
+            Backend::Tantivy { index, fields } => {
+                let mut writer = self.writer(index)?;
+                writer.delete_term(translation_term(fields, translation));
+                writer.commit()?;
+            }
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite(store) => store.remove_translation(translation)?,
+            #[cfg(not(feature = "index"))]
+            Backend::Scan(_) => {}
+        }
+        user::remove(data_dir, translation)?;
+        Ok(())
+    }
+
+    /// Take a translation out of the search index, leaving its text (and, for one the user added,
+    /// its registration) alone. Searches in it find nothing until [`Bible::reindex_translation`]
+    /// puts it back or the index is rebuilt. Lookups in an embedded translation still work, read
+    /// from the embedded text; with the sqlite backend, lookups in any translation do.
+    #[cfg(feature = "index")]
+    pub fn unindex_translation(&self, translation: Translation) -> Result<()> {
+        self.check_searchable(translation)?;
+        match &self.backend {
+            Backend::Tantivy { index, fields } => {
+                let mut writer = self.writer(index)?;
+                writer.delete_term(translation_term(fields, translation));
+                writer.commit()?;
+            }
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite(store) => store.unindex_translation(translation)?,
+        }
+        Ok(())
+    }
+
+    /// Index one translation again from its text, the embedded text or the dat file it was added
+    /// from, without rebuilding the rest. Returns the number of verses indexed.
+    #[cfg(feature = "index")]
+    pub fn reindex_translation(&self, translation: Translation) -> Result<usize> {
+        self.check_searchable(translation)?;
+        let text = match (translation.text(), self.data_dir.as_deref()) {
+            (Some(text), _) => text.into(),
+            (None, Some(data_dir)) => std::fs::read_to_string(user::path(data_dir, translation))?,
+            (None, None) => return Err(Error::UnknownTranslation(translation)),
+        };
+
+        match &self.backend {
+            Backend::Tantivy { index, fields } => {
+                // A lean index only keeps the text of translations which aren't embedded.
+                let store = translation.text().is_none() || !self.options.lean;
+
+                let mut writer = self.writer(index)?;
+                writer.delete_term(translation_term(fields, translation));
+                search::write_index(translation, &text, store, fields, &mut writer)?;
+            }
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite(store) => store.add_translation(translation, &text)?,
+        }
+        Ok(parse_verses_with_id(&text).count())
+    }
+
+    /// There's no index to take a translation out of in this build.
+    #[cfg(not(feature = "index"))]
+    pub fn unindex_translation(&self, _translation: Translation) -> Result<()> {
+        Err(Error::NoIndex)
+    }
+
+    /// There's no index to put a translation back into in this build.
+    #[cfg(not(feature = "index"))]
+    pub fn reindex_translation(&self, _translation: Translation) -> Result<usize> {
+        Err(Error::NoIndex)
+    }
+
+    /// Every user-supplied translation, by name.
+    pub fn user_translations(&self) -> Result<Vec<Translation>> {
+        match &self.data_dir {
+            Some(data_dir) => Ok(user::registered(data_dir)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Every verse of an indexed translation, in canonical order.
+    fn whole_text(&self, translation: Translation) -> Result<Vec<Text>> {
+        self.check_searchable(translation)?;
+        let mut texts = Vec::new();
+        for book in (1..=66).map(Book::from_u8) {
+            texts.extend(self.lookup(book, None, translation)?);
+        }
+        Ok(texts)
+    }
+
+    #[cfg(feature = "index")]
+    fn writer(&self, index: &Index) -> Result<IndexWriter> {
+        /// 50 megabytes; a single translation is much smaller than the whole corpus
+        const ARENA_SIZE: usize = 0x100000 * 50;
+        Ok(self.options.writer(index, ARENA_SIZE)?)
+    }
+
+    fn is_registered(&self, translation: Translation) -> bool {
+        self.data_dir
+            .as_deref()
+            .is_some_and(|data_dir| user::is_registered(data_dir, translation))
+    }
+
+    fn check_searchable(&self, translation: Translation) -> Result<()> {
+        if translation.is_remote() {
+            return Err(Error::remote(translation, "search is not available"));
+        }
+
+        #[cfg(feature = "sword")]
+        if self.sword_for(translation).is_some() {
+            return Err(Error::bad_translation(
+                translation,
+                "search is not available for sword modules",
+            ));
+        }
+
+        self.check_available(translation)
+    }
+
+    fn check_available(&self, translation: Translation) -> Result<()> {
+        if matches!(translation, Translation::User(_)) && !self.is_registered(translation) {
+            return Err(Error::UnknownTranslation(translation));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "sword")]
+    fn sword_for(&self, translation: Translation) -> Option<&sword::Sword> {
+        if !matches!(translation, Translation::User(_)) || self.is_registered(translation) {
+            return None;
+        }
+
+        self.sword
+            .as_ref()
+            .filter(|sword| sword.contains(translation))
+    }
+
+    #[cfg(feature = "remote")]
+    fn lookup_remote(
+        &self,
+        book: Book,
+        location: Option<PartialLocation>,
+        translation: Translation,
+    ) -> Result<Vec<Text>> {
+        self.remote
+            .as_ref()
+            .ok_or_else(|| Error::remote(translation, "remote translations are not configured"))?
+            .lookup(book, location, translation)
+    }
+
+    #[cfg(not(feature = "remote"))]
+    fn lookup_remote(
+        &self,
+        _book: Book,
+        _location: Option<PartialLocation>,
+        translation: Translation,
+    ) -> Result<Vec<Text>> {
+        Err(Error::remote(
+            translation,
+            "remote translations require the `remote` feature",
+        ))
+    }
+}
+
+/// Where the verses and their full-text index are kept.
+enum Backend {
+    #[cfg(feature = "index")]
+    Tantivy { index: Index, fields: SearchFields },
+    #[cfg(feature = "sqlite")]
+    Sqlite(sqlite::Store),
+    /// No index at all; see [`scan`].
+    #[cfg(not(feature = "index"))]
+    Scan(scan::Scan),
+}
+
+/// We want to store our data someplace sane, so we're gonna use the directories library to
+/// decide where all this data goes.
+fn data_dir() -> io::Result<PathBuf> {
+    let dirs = ProjectDirs::from("org", "Hack Commons", "Bible-App")
+        .ok_or_else(|| io::Error::other("unable to initialize project directory"))?;
+    Ok(dirs.data_dir().into())
+}
+
+#[cfg(feature = "index")]
+fn translation_term(fields: &SearchFields, translation: Translation) -> Term {
+    Term::from_facet(fields.translation, &translation.facet())
+}
@@ -0,0 +1,404 @@
+use std::{fmt, num::ParseIntError, ops::Range, str::FromStr};
+
+use crate::{
+    book::{Book, ParseBookError},
+    error::AbbrevStr,
+};
+
+/// Book, chapter and verse
+///
+/// A location such as this can be used to search translations for a specific verse.
+#[derive(Clone, Copy, Debug)]
+pub struct Location {
+    pub book: Book,
+    pub chapter: u16,
+    pub verse: u16,
+}
+
+impl Location {
+    pub fn from_id<T: Into<u64>>(id: T) -> Self {
+        let id = id.into();
+        Self {
+            book: ((id / 1_000_000) as u8).into(),
+            chapter: (id % 1_000_000 / 1000) as u16,
+            verse: (id % 1000) as u16,
+        }
+    }
+
+    /// The id used for this location in dat files, e.g. 43003016 for John 3:16.
+    pub fn id(&self) -> u64 {
+        u64::from(self.book as u8) * 1_000_000
+            + u64::from(self.chapter) * 1000
+            + u64::from(self.verse)
+    }
+}
+
+/// Chapter and verse
+///
+/// A location may also describe a range of verses within a chapter, e.g. 3:16-18, in which case
+/// `end` is the last verse of the range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PartialLocation {
+    pub chapter: u16,
+    pub verse: Option<u16>,
+    pub end: Option<u16>,
+}
+
+impl PartialLocation {
+    /// Returns true if the given verse of this chapter falls within the location.
+    pub fn contains(&self, verse: u16) -> bool {
+        match (self.verse, self.end) {
+            (Some(start), Some(end)) => (start..=end).contains(&verse),
+            (Some(start), None) => start == verse,
+            (None, _) => true,
+        }
+    }
+}
+
+impl fmt::Display for PartialLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chapter = self.chapter;
+        match (self.verse, self.end) {
+            (Some(verse), Some(end)) => write!(f, "[{chapter}:{verse}-{end}]"),
+            (Some(verse), None) => write!(f, "[{chapter}:{verse}]"),
+            (None, _) => write!(f, "[{chapter}]"),
+        }
+    }
+}
+
+impl FromStr for PartialLocation {
+    type Err = ParseLocationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // I have just now decided that the way this has to be written is in the following format,
+        // at least until I start to refine the cli...
+
+        // psalms.23
+        // Romans.3:23
+        // john.3:16 -- see also Austin.3:16
+        // john.3:16-18
+
+        let (chapter, verse) = s.split_once(':').unwrap_or((s, ""));
+
+        // For right now, we're not going to check the book's name, because... well, whatever. We
+        // are gonna implement that later.
+
+        let chapter = chapter
+            .parse()
+            .map_err(|e| ParseLocationError::chapter(chapter, e))?;
+
+        if verse.is_empty() {
+            return Ok(PartialLocation {
+                chapter,
+                verse: None,
+                end: None,
+            });
+        }
+
+        let (verse, end) = match verse.split_once('-') {
+            Some((verse, end)) => (verse, Some(end)),
+            None => (verse, None),
+        };
+
+        let verse: u16 = verse
+            .parse()
+            .map_err(|e| ParseLocationError::verse(verse, e))?;
+        let end: Option<u16> = end
+            .map(|end| end.parse().map_err(|e| ParseLocationError::verse(end, e)))
+            .transpose()?;
+
+        // A backwards range is almost certainly a typo, but there's no telling which number was
+        // meant, so we simply swap them.
+        let (verse, end) = match end {
+            Some(end) if end < verse => (end, Some(verse)),
+            Some(end) if end == verse => (verse, None),
+            end => (verse, end),
+        };
+
+        Ok(PartialLocation {
+            chapter,
+            verse: Some(verse),
+            end,
+        })
+    }
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum ParseLocationError {
+    #[error("unable to parse chapter: {text}")]
+    Chapter { text: String, cause: ParseIntError },
+
+    #[error("unable to parse verse: {text}")]
+    Verse { text: String, cause: ParseIntError },
+}
+
+impl ParseLocationError {
+    fn chapter(text: impl AbbrevStr, cause: ParseIntError) -> Self {
+        ParseLocationError::Chapter {
+            text: text.get(10),
+            cause,
+        }
+    }
+
+    fn verse(text: impl AbbrevStr, cause: ParseIntError) -> Self {
+        ParseLocationError::Verse {
+            text: text.get(10),
+            cause,
+        }
+    }
+}
+
+/// A book and, optionally, a chapter/verse within it, e.g. "John 3:16" or "Psalms 23"
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Passage {
+    pub book: Book,
+    pub location: Option<PartialLocation>,
+}
+
+impl Passage {
+    /// Whether two passages share any verse. A passage without a chapter (or verse) covers every
+    /// chapter (or verse).
+    pub fn overlaps(&self, other: &Passage) -> bool {
+        if self.book != other.book {
+            return false;
+        }
+
+        let (Some(a), Some(b)) = (self.location, other.location) else {
+            return true;
+        };
+
+        if a.chapter != b.chapter {
+            return false;
+        }
+
+        let verses = |location: PartialLocation| {
+            let verse = location.verse?;
+            Some((verse, location.end.unwrap_or(verse)))
+        };
+        match (verses(a), verses(b)) {
+            (Some((a_start, a_end)), Some((b_start, b_end))) => {
+                a_start <= b_end && b_start <= a_end
+            }
+            _ => true,
+        }
+    }
+
+    /// Every reference with at least a chapter in some text, e.g. the "John 3:16" in "see John
+    /// 3:16" or the "1 Cor. 13" in "(1 Cor. 13)", with its byte range. Bare book names are left
+    /// out, since too many of them (Job, Acts, Numbers) are ordinary words.
+    ///
+    /// Prose is full of things that parse as references, so the book's name has to be
+    /// capitalized, an abbreviation of a letter or two (Am, Is) or one which is an ordinary word
+    /// (Act, Col) needs its period or a verse, and the chapter has to be one the book has: "I am
+    /// 40" and "Act 2 of the play" aren't references.
+    pub fn find_all(text: &str) -> Vec<(Range<usize>, Passage)> {
+        let words: Vec<Range<usize>> = text
+            .split_whitespace()
+            .map(|word| {
+                let start = word.as_ptr() as usize - text.as_ptr() as usize;
+                start..start + word.len()
+            })
+            .collect();
+
+        let mut found: Vec<(Range<usize>, Passage)> = Vec::new();
+        for (idx, word) in words.iter().enumerate() {
+            // A chapter, and maybe verses, ending at the first character which can't be part of
+            // one, e.g. the comma in "3:16,".
+            let location = &text[word.clone()];
+            if !location.starts_with(|c: char| c.is_ascii_digit()) {
+                continue;
+            }
+            let len = location
+                .find(|c: char| !c.is_ascii_digit() && c != ':' && c != '-')
+                .unwrap_or(location.len());
+            let end = word.start + location[..len].trim_end_matches([':', '-']).len();
+
+            // The book's name is the three words before at most ("Song of Songs"), and the
+            // longest name that parses wins, so "1 John 3:16" isn't taken for "John 3:16".
+            let earliest = found.last().map_or(0, |(range, _)| range.end);
+            let passage = (idx.saturating_sub(3)..idx).find_map(|first| {
+                let name = &text[words[first].start..end];
+                let start = end
+                    - name
+                        .trim_start_matches(|c: char| !c.is_alphanumeric())
+                        .len();
+                if start < earliest {
+                    return None;
+                }
+                let passage: Passage = text[start..end].parse().ok()?;
+                let name = text[start..word.start].trim_end();
+                looks_like_reference(name, passage).then_some((start..end, passage))
+            });
+            found.extend(passage);
+        }
+
+        found
+    }
+}
+
+/// Abbreviations which are also everyday words, and so only count as a book's name with a period
+/// or a verse after them.
+const ORDINARY_WORDS: &[&str] = &[
+    "ACT", "COL", "DAN", "LAM", "MAR", "MIC", "NUM", "PET", "PRO", "SOL", "SONG",
+];
+
+/// Whether `name`, as written before the chapter, and the passage it was parsed into are likely
+/// to be a reference rather than words which happen to parse as one.
+fn looks_like_reference(name: &str, passage: Passage) -> bool {
+    let Some(location) = passage.location else {
+        return false;
+    };
+    if location.chapter == 0 || location.chapter > passage.book.chapters() {
+        return false;
+    }
+    if !name
+        .chars()
+        .find(|c| c.is_alphabetic())
+        .is_some_and(char::is_uppercase)
+    {
+        return false;
+    }
+
+    if name.ends_with('.') || name.contains(char::is_whitespace) {
+        return true;
+    }
+    let letters = name.chars().filter(|c| c.is_alphabetic()).count();
+    let ordinary = ORDINARY_WORDS
+        .iter()
+        .any(|word| word.eq_ignore_ascii_case(name));
+    letters > 2 && !(ordinary && location.verse.is_none())
+}
+
+impl fmt::Display for Passage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let book = self.book;
+        match self.location {
+            None => write!(f, "{book}"),
+            Some(PartialLocation {
+                chapter,
+                verse: None,
+                ..
+            }) => write!(f, "{book} {chapter}"),
+            Some(PartialLocation {
+                chapter,
+                verse: Some(verse),
+                end: None,
+            }) => write!(f, "{book} {chapter}:{verse}"),
+            Some(PartialLocation {
+                chapter,
+                verse: Some(verse),
+                end: Some(end),
+            }) => write!(f, "{book} {chapter}:{verse}-{end}"),
+        }
+    }
+}
+
+impl FromStr for Passage {
+    type Err = ParsePassageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        // The location, if any, is whatever follows the last space. Unfortunately, "3 John" also
+        // ends in something following a space, so if that something isn't a location, we try
+        // the whole thing as a book name instead.
+        let mut book_error = None;
+        if let Some((book, location)) = s.rsplit_once(char::is_whitespace) {
+            if location.starts_with(|u: char| u.is_ascii_digit()) {
+                match book.parse() {
+                    Ok(book) => {
+                        return Ok(Passage {
+                            book,
+                            location: Some(location.parse()?),
+                        })
+                    }
+                    Err(e) => book_error = Some(e),
+                }
+            }
+        }
+
+        // If neither attempt works, the complaint about the shorter name is more useful; its
+        // suggestions won't be thrown off by the chapter and verse.
+        match s.parse() {
+            Ok(book) => Ok(Passage {
+                book,
+                location: None,
+            }),
+            Err(e) => Err(book_error.unwrap_or(e).into()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum ParsePassageError {
+    #[error(transparent)]
+    Book(#[from] ParseBookError),
+
+    #[error(transparent)]
+    Location(#[from] ParseLocationError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PartialLocation, Passage};
+    use crate::Book;
+
+    #[test]
+    fn parse_passage() {
+        let test = |s: &str| s.parse::<Passage>().unwrap().to_string();
+        assert_eq!("John 3:16", test("john 3:16"));
+        assert_eq!("3 John", test("3 John"));
+        assert_eq!("John 3", test("John 3"));
+        assert_eq!("1 Corinthians 13:4-7", test("1 Cor 13:4-7"));
+        assert_eq!("Song of Songs 2", test("song of songs 2"));
+        assert_eq!(Book::Psalms, "Ps 23".parse::<Passage>().unwrap().book);
+    }
+
+    #[test]
+    fn find_all() {
+        let text = "See 1 John 3:16, (Song of Songs 2) and Ps. 23:1-3; not Job, or page 12.";
+        let found: Vec<_> = Passage::find_all(text)
+            .into_iter()
+            .map(|(range, passage)| (&text[range], passage.to_string()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("1 John 3:16", String::from("1 John 3:16")),
+                ("Song of Songs 2", String::from("Song of Songs 2")),
+                ("Ps. 23:1-3", String::from("Psalms 23:1-3")),
+            ],
+            found
+        );
+
+        for prose in [
+            "I am 40 years old and it is 3 miles away",
+            "Act 2 of the play",
+            "the temperature is 3 degrees",
+            "Amos 40 and Obadiah 2",
+            "see john 3:16",
+        ] {
+            assert!(Passage::find_all(prose).is_empty(), "{prose}");
+        }
+        let found: Vec<_> = Passage::find_all("Is. 53:5, Am 5:24 and Act 2:4")
+            .into_iter()
+            .map(|(_, passage)| passage.to_string())
+            .collect();
+        assert_eq!(vec!["Isaiah 53:5", "Acts 2:4"], found);
+    }
+
+    #[test]
+    fn parse_range() {
+        let location: PartialLocation = "3:16-18".parse().unwrap();
+        assert_eq!(Some(16), location.verse);
+        assert_eq!(Some(18), location.end);
+        assert!(location.contains(17));
+        assert!(!location.contains(19));
+    }
+
+    #[test]
+    fn parse_backward_range() {
+        let location: PartialLocation = "3:18-16".parse().unwrap();
+        assert_eq!(Some(16), location.verse);
+        assert_eq!(Some(18), location.end);
+    }
+}
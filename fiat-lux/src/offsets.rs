@@ -0,0 +1,117 @@
+//! Looking up verses in the embedded translations straight from their text, using the table of
+//! verses made by the build script, so a plain reference needn't wait on the search index.
+
+use crate::{
+    book::Book, embedded::Embedded, location::PartialLocation, text::Text, translation::Translation,
+};
+
+/// The position of the first verse with an id of at least `id`.
+fn find(embedded: &Embedded, id: u32) -> usize {
+    let (mut low, mut high) = (0, embedded.verse_count());
+    while low < high {
+        let mid = (low + high) / 2;
+        if embedded.verse(mid).0 < id {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+fn verse(embedded: &'static Embedded, idx: usize) -> Text {
+    let (id, offset) = embedded.verse(idx);
+    let book = Book::from((id / 1_000_000) as u8);
+    let line = embedded.book(book)[offset..]
+        .lines()
+        .next()
+        .unwrap_or_default();
+    Text {
+        book,
+        chapter: (id % 1_000_000 / 1000) as u16,
+        verse: (id % 1000) as u16,
+        content: line.get(9..).unwrap_or_default().into(),
+    }
+}
+
+/// The text of a single verse of an embedded translation, by its id, e.g. 43003016 for John 3:16.
+/// This is where a lean index gets its verses from, since it doesn't keep them itself.
+#[cfg_attr(not(feature = "index"), allow(dead_code))]
+pub(crate) fn embedded_verse(translation: Translation, id: u32) -> Option<String> {
+    let embedded = Embedded::for_translation(translation)?;
+    let idx = find(embedded, id);
+    (idx < embedded.verse_count() && embedded.verse(idx).0 == id)
+        .then(|| verse(embedded, idx).content)
+}
+
+/// Look up a book, chapter or verses in one of the embedded translations without opening the
+/// search index, giving the same verses [`Bible::lookup`](crate::Bible::lookup) would. `None`
+/// means the translation isn't embedded (or was built without its text) and the index is needed
+/// after all.
+pub fn lookup_embedded(
+    book: Book,
+    location: Option<PartialLocation>,
+    translation: Translation,
+) -> Option<Vec<Text>> {
+    let embedded = Embedded::for_translation(translation)?;
+
+    let (first, last) = id_range(book, location);
+    Some(
+        (find(embedded, first)..find(embedded, last + 1))
+            .map(|idx| verse(embedded, idx))
+            .collect(),
+    )
+}
+
+/// The first and last ids a lookup could cover, e.g. 43003016 to 43003018 for John 3:16-18, or
+/// 43003000 to 43003999 for the whole chapter.
+pub(crate) fn id_range(book: Book, location: Option<PartialLocation>) -> (u32, u32) {
+    let book = u32::from(book as u8) * 1_000_000;
+    match location {
+        None => (book, book + 999_999),
+        Some(PartialLocation {
+            chapter,
+            verse,
+            end,
+        }) => {
+            let chapter = book + u32::from(chapter) * 1000;
+            match (verse, end) {
+                (Some(verse), end) => (
+                    chapter + u32::from(verse),
+                    chapter + u32::from(end.unwrap_or(verse)),
+                ),
+                (None, _) => (chapter, chapter + 999),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lookup_embedded;
+    use crate::{Book, Translation};
+
+    #[test]
+    fn lookups() {
+        let lookup = |book, location: &str| {
+            lookup_embedded(book, Some(location.parse().unwrap()), Translation::Kjv).unwrap()
+        };
+
+        let texts = lookup(Book::John, "3:16-17");
+        assert_eq!(2, texts.len());
+        assert_eq!(16, texts[0].verse);
+        assert!(texts[0].content.starts_with("For God so loved the world"));
+
+        assert_eq!("Jesus wept.", lookup(Book::John, "11:35")[0].content);
+        assert_eq!(176, lookup(Book::Psalms, "119").len());
+        assert!(lookup(Book::Jude, "2").is_empty());
+
+        let jude = lookup_embedded(Book::Jude, None, Translation::Kjv).unwrap();
+        assert_eq!(25, jude.len());
+        assert!(lookup_embedded(Book::John, None, Translation::Esv).is_none());
+
+        let wept = super::embedded_verse(Translation::Kjv, 43011035);
+        assert_eq!(Some("Jesus wept."), wept.as_deref());
+        assert_eq!(None, super::embedded_verse(Translation::Kjv, 43011999));
+    }
+}
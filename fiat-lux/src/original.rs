@@ -0,0 +1,27 @@
+//! Hebrew and Greek words English can only approximate, like hesed and agape, for following their
+//! translations with the original.
+
+use crate::sword::Strongs;
+
+/// Words, one per line, as tab-separated fields: the Strong's number and a transliteration.
+static ORIGINAL_DAT: &str = include_str!("../resource/original.dat");
+
+/// The transliteration of a word on the list, e.g. hesed for H2617, or `None` for any other.
+pub fn original_word(strongs: Strongs) -> Option<&'static str> {
+    ORIGINAL_DAT.lines().find_map(|line| {
+        let (number, word) = line.split_once('\t')?;
+        (number.parse::<Strongs>().ok()? == strongs).then_some(word)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::original_word;
+
+    #[test]
+    fn words() {
+        assert_eq!(Some("hesed"), original_word("H2617".parse().unwrap()));
+        assert_eq!(Some("agape"), original_word("G0026".parse().unwrap()));
+        assert_eq!(None, original_word("G2316".parse().unwrap()));
+    }
+}
@@ -0,0 +1,48 @@
+use crate::book::Book;
+
+/// One-line chapter summaries, written for flux and so far only for a few books. Each line is a
+/// five-digit id (two digits of book, three of chapter), a space, and the summary.
+static OUTLINE_DAT: &str = include_str!("../resource/outline.dat");
+
+/// A one-line summary of each chapter of a book, in order. Books without summaries yet come back
+/// empty.
+pub fn outline(book: Book) -> Vec<(u16, &'static str)> {
+    let prefix = format!("{:02}", book as u8);
+    OUTLINE_DAT
+        .lines()
+        .filter(|line| line.starts_with(&prefix))
+        .filter_map(|line| {
+            let chapter = line.get(2..5)?.parse().ok()?;
+            Some((chapter, line.get(6..)?))
+        })
+        .collect()
+}
+
+/// The books with an outline, in order; so far only a few have one.
+pub fn outlined_books() -> Vec<Book> {
+    let mut books: Vec<Book> = OUTLINE_DAT
+        .lines()
+        .filter_map(|line| line.get(..2)?.parse::<u8>().ok())
+        .map(Book::from_u8)
+        .collect();
+    books.dedup();
+    books
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::book::Book;
+
+    #[test]
+    fn outline() {
+        let acts = super::outline(Book::Acts);
+        assert_eq!(28, acts.len());
+        assert!(acts[26].1.contains("shipwreck"));
+        assert!(super::outline(Book::Obadiah).is_empty());
+
+        let books = super::outlined_books();
+        assert!(books.contains(&Book::Acts));
+        assert!(!books.contains(&Book::Obadiah));
+        assert!(books.iter().all(|&book| !super::outline(book).is_empty()));
+    }
+}
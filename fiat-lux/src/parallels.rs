@@ -0,0 +1,185 @@
+//! Near-identical verses: synoptic parallels, refrains, and the formulas the narrative repeats.
+//!
+//! Verses are compared as sets of word pairs (bigrams), so two verses score 1 when every pair of
+//! adjacent words in one turns up in the other, and a single changed word in a twenty-word verse
+//! still scores about 0.8. Comparing every verse with every other would take hundreds of millions
+//! of comparisons, so candidates are found with MinHash: each verse gets a short signature, and
+//! only verses whose signatures agree on some band of it are compared properly.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use crate::{stats::words_in, text::Text};
+
+/// Bands of the MinHash signature, and hashes in each band. Five bands of four put the chance of
+/// missing a pair at 0.8 similarity under one in ten, while pairs at 0.5 rarely even get compared.
+const BANDS: usize = 5;
+const ROWS: usize = 4;
+
+/// Groups of near-identical verses among `texts`, as indices into it, each in canonical order
+/// along with the lowest similarity between verses paired in it. Verses shorter than `min_words`
+/// are left out, as are groups of one.
+pub(crate) fn parallels(
+    texts: &[Text],
+    threshold: f64,
+    min_words: usize,
+) -> Vec<(Vec<usize>, f64)> {
+    let bigrams: Vec<HashSet<(String, String)>> = texts
+        .iter()
+        .map(|text| {
+            let words: Vec<_> = words_in(&text.content).collect();
+            if words.len() < min_words.max(2) {
+                return HashSet::new();
+            }
+            words
+                .windows(2)
+                .map(|pair| (pair[0].clone(), pair[1].clone()))
+                .collect()
+        })
+        .collect();
+
+    let mut buckets: HashMap<(usize, Vec<u64>), Vec<usize>> = HashMap::new();
+    for (idx, set) in bigrams.iter().enumerate() {
+        if set.is_empty() {
+            continue;
+        }
+
+        let signature: Vec<u64> = (0..BANDS * ROWS)
+            .map(|seed| set.iter().map(|bigram| hash(seed, bigram)).min().unwrap())
+            .collect();
+        for (band, rows) in signature.chunks(ROWS).enumerate() {
+            buckets.entry((band, rows.to_vec())).or_default().push(idx);
+        }
+    }
+
+    let mut pairs: HashMap<(usize, usize), f64> = HashMap::new();
+    for members in buckets.values() {
+        for (i, &a) in members.iter().enumerate() {
+            for &b in &members[i + 1..] {
+                if pairs.contains_key(&(a, b)) {
+                    continue;
+                }
+                let shared = bigrams[a].intersection(&bigrams[b]).count();
+                let similarity =
+                    shared as f64 / (bigrams[a].len() + bigrams[b].len() - shared) as f64;
+                pairs.insert((a, b), similarity);
+            }
+        }
+    }
+
+    // Union-find, so a refrain repeated a dozen times comes out as one group rather than every
+    // pair of its verses.
+    let mut parent: Vec<usize> = (0..texts.len()).collect();
+    fn root(parent: &mut [usize], mut idx: usize) -> usize {
+        while parent[idx] != idx {
+            parent[idx] = parent[parent[idx]];
+            idx = parent[idx];
+        }
+        idx
+    }
+
+    let mut lowest: HashMap<usize, f64> = HashMap::new();
+    let mut matched: Vec<_> = pairs
+        .into_iter()
+        .filter(|&(_, similarity)| similarity >= threshold)
+        .collect();
+    matched.sort_by_key(|&(pair, _)| pair);
+    for &((a, b), _) in &matched {
+        let (a, b) = (root(&mut parent, a), root(&mut parent, b));
+        if a != b {
+            parent[b.max(a)] = a.min(b);
+        }
+    }
+    for ((a, _), similarity) in matched {
+        let group = root(&mut parent, a);
+        let entry = lowest.entry(group).or_insert(similarity);
+        *entry = entry.min(similarity);
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for idx in 0..texts.len() {
+        let group = root(&mut parent, idx);
+        if lowest.contains_key(&group) {
+            groups.entry(group).or_default().push(idx);
+        }
+    }
+
+    let mut groups: Vec<_> = groups
+        .into_iter()
+        .map(|(group, members)| (members, lowest[&group]))
+        .collect();
+    groups.sort_by_key(|(members, _)| members[0]);
+    groups
+}
+
+fn hash(seed: usize, bigram: &(String, String)) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    bigram.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parallels;
+    use crate::{book::Book, text::Text};
+
+    #[test]
+    fn groups() {
+        let text = |book, chapter, verse, content: &str| Text {
+            book,
+            chapter,
+            verse,
+            content: content.to_string(),
+        };
+
+        let texts = [
+            text(
+                Book::Leviticus,
+                4,
+                1,
+                "And the LORD spake unto Moses, saying,",
+            ),
+            text(
+                Book::Leviticus,
+                5,
+                14,
+                "And the LORD spake unto Moses, saying,",
+            ),
+            text(
+                Book::Leviticus,
+                6,
+                1,
+                "And the LORD spake unto Moses, saying,",
+            ),
+            text(
+                Book::Psalms,
+                136,
+                1,
+                "O give thanks unto the LORD; for he is good: for his mercy endureth for ever.",
+            ),
+            text(
+                Book::Psalms,
+                107,
+                1,
+                "O give thanks unto the LORD, for he is good: for his mercy endureth for ever.",
+            ),
+            text(Book::John, 11, 35, "Jesus wept."),
+            text(
+                Book::Luke,
+                19,
+                41,
+                "And when he was come near, he beheld the city, and wept over it,",
+            ),
+        ];
+
+        let groups = parallels(&texts, 0.8, 4);
+        assert_eq!(2, groups.len());
+        assert_eq!(vec![0, 1, 2], groups[0].0);
+        assert_eq!(1.0, groups[0].1);
+        assert_eq!(vec![3, 4], groups[1].0);
+        assert!(groups[1].1 >= 0.8);
+    }
+}
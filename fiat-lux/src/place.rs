@@ -0,0 +1,85 @@
+use std::fmt;
+
+/// Places, one per line, as tab-separated fields: name, aliases (comma-separated), latitude,
+/// longitude, modern identification, description.
+static PLACES_DAT: &str = include_str!("../resource/places.dat");
+
+/// A place named in the Bible, along with where it's thought to be today.
+#[derive(Clone, Copy, Debug)]
+pub struct Place {
+    pub name: &'static str,
+    aliases: &'static str,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub modern: &'static str,
+    pub description: &'static str,
+}
+
+impl Place {
+    /// Look up a place by its name or one of its other names, ignoring case.
+    pub fn find(name: &str) -> Option<Place> {
+        let name = name.trim();
+        places().find(|place| place.names().any(|n| n.eq_ignore_ascii_case(name)))
+    }
+
+    /// The place's name followed by any other names it goes by, e.g. Malta and Melita.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> {
+        let aliases = self.aliases;
+        std::iter::once(self.name).chain(
+            aliases
+                .split(',')
+                .map(str::trim)
+                .filter(|alias| !alias.is_empty()),
+        )
+    }
+
+    /// An RFC 5870 geo URI, which most map apps will open.
+    pub fn geo_uri(&self) -> String {
+        format!("geo:{},{}", self.latitude, self.longitude)
+    }
+
+    pub fn osm_url(&self) -> String {
+        let Place {
+            latitude,
+            longitude,
+            ..
+        } = self;
+        format!("https://www.openstreetmap.org/?mlat={latitude}&mlon={longitude}#map=13/{latitude}/{longitude}")
+    }
+}
+
+impl fmt::Display for Place {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+/// Every place in the gazetteer, alphabetically.
+pub fn places() -> impl Iterator<Item = Place> {
+    PLACES_DAT.lines().filter_map(|line| {
+        let mut fields = line.split('\t');
+        Some(Place {
+            name: fields.next()?,
+            aliases: fields.next()?,
+            latitude: fields.next()?.parse().ok()?,
+            longitude: fields.next()?.parse().ok()?,
+            modern: fields.next()?,
+            description: fields.next()?,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Place;
+
+    #[test]
+    fn find() {
+        let malta = Place::find("melita").unwrap();
+        assert_eq!("Malta", malta.name);
+        assert_eq!(vec!["Malta", "Melita"], malta.names().collect::<Vec<_>>());
+        assert!(Place::find("capernaum").is_some());
+        assert!(Place::find("Atlantis").is_none());
+        assert_eq!(46, super::places().count());
+    }
+}
@@ -0,0 +1,140 @@
+//! Reading plans, which take you through some part of the Bible a few chapters a day.
+//!
+//! A plan is made of one or more streams of books, each read straight through and spread as
+//! evenly as whole chapters allow over the length of the plan. Each day's reading takes a piece
+//! of every stream.
+
+use std::{fmt, ops::RangeInclusive};
+
+use crate::book::Book;
+
+/// A stream is a run of books, by number; listing a run twice reads it twice.
+type Stream = &'static [RangeInclusive<u8>];
+
+#[derive(Clone, Copy, Debug)]
+pub struct Plan {
+    /// Short name used on the command line, e.g. "year".
+    pub name: &'static str,
+    pub description: &'static str,
+    pub days: u16,
+    streams: &'static [Stream],
+}
+
+static PLANS: &[Plan] = &[
+    Plan {
+        name: "year",
+        description: "the whole Bible, Genesis to Revelation, in a year",
+        days: 365,
+        streams: &[&[1..=66]],
+    },
+    Plan {
+        name: "parallel",
+        description: "the Old Testament once and the New Testament twice in a year, side by side",
+        days: 365,
+        streams: &[&[1..=39], &[40..=66, 40..=66]],
+    },
+    Plan {
+        name: "nt",
+        description: "the New Testament in ninety days",
+        days: 90,
+        streams: &[&[40..=66]],
+    },
+];
+
+impl Plan {
+    /// Every built-in plan.
+    pub fn all() -> &'static [Plan] {
+        PLANS
+    }
+
+    pub fn find(name: &str) -> Option<&'static Plan> {
+        PLANS
+            .iter()
+            .find(|plan| plan.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The reading for a day of the plan, counting from 1. Days past the end have none.
+    pub fn day(&self, day: u16) -> Vec<Reading> {
+        if day == 0 || day > self.days {
+            return Vec::new();
+        }
+
+        let mut readings = Vec::new();
+        for stream in self.streams {
+            let chapters: Vec<(Book, u16)> = stream
+                .iter()
+                .flat_map(|books| books.clone().map(Book::from_u8))
+                .flat_map(|book| (1..=book.chapters()).map(move |chapter| (book, chapter)))
+                .collect();
+
+            // Day n of d gets the chapters from (n - 1) / d to n / d of the way through.
+            let share = |day: u16| chapters.len() * usize::from(day) / usize::from(self.days);
+            for &(book, chapter) in &chapters[share(day - 1)..share(day)] {
+                match readings.last_mut() {
+                    Some(Reading {
+                        book: last_book,
+                        chapters,
+                    }) if *last_book == book && *chapters.end() + 1 == chapter => {
+                        *chapters = *chapters.start()..=chapter;
+                    }
+                    _ => readings.push(Reading {
+                        book,
+                        chapters: chapter..=chapter,
+                    }),
+                }
+            }
+        }
+
+        readings
+    }
+}
+
+/// A run of whole chapters from one book.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reading {
+    pub book: Book,
+    pub chapters: RangeInclusive<u16>,
+}
+
+impl fmt::Display for Reading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (first, last) = (self.chapters.start(), self.chapters.end());
+        if first == last {
+            write!(f, "{} {first}", self.book)
+        } else {
+            write!(f, "{} {first}-{last}", self.book)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Plan;
+    use crate::book::Book;
+
+    #[test]
+    fn every_chapter_once() {
+        let plan = Plan::find("year").unwrap();
+        let chapters: usize = (1..=plan.days)
+            .flat_map(|day| plan.day(day))
+            .map(|reading| reading.chapters.count())
+            .sum();
+        assert_eq!(1189, chapters);
+
+        let first: Vec<_> = plan.day(1).iter().map(ToString::to_string).collect();
+        assert_eq!(vec!["Genesis 1-3"], first);
+        assert!(plan.day(366).is_empty());
+    }
+
+    #[test]
+    fn parallel_streams() {
+        let plan = Plan::find("parallel").unwrap();
+        let first = plan.day(1);
+        assert_eq!(Book::Genesis, first[0].book);
+        assert_eq!(Book::Matthew, first.last().unwrap().book);
+
+        let last = plan.day(plan.days);
+        assert_eq!(Book::Malachi, last[0].book);
+        assert_eq!(Book::Revelation, last.last().unwrap().book);
+    }
+}
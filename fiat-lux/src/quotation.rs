@@ -0,0 +1,192 @@
+//! New Testament passages which quote the Old, or which are traditionally read as fulfilling it.
+//!
+//! The list is a teaching aid rather than a complete index: it covers the citations most often
+//! discussed, not every allusion.
+
+use std::{fmt, ops::Range};
+
+use crate::location::Passage;
+
+/// Quotations, one per line, as tab-separated fields: the New Testament passage, the Old
+/// Testament passage, and either "quotation" or "fulfillment".
+static QUOTATIONS_DAT: &str = include_str!("../resource/quotations.dat");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuotationKind {
+    /// The Old Testament text is simply quoted, e.g. in an argument.
+    Quotation,
+
+    /// The New Testament presents the event as the fulfillment of the Old Testament text.
+    Fulfillment,
+}
+
+impl fmt::Display for QuotationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotationKind::Quotation => f.write_str("quotation"),
+            QuotationKind::Fulfillment => f.write_str("fulfillment"),
+        }
+    }
+}
+
+/// A New Testament passage and the Old Testament passage it cites.
+#[derive(Clone, Copy, Debug)]
+pub struct Quotation {
+    pub new: Passage,
+    pub old: Passage,
+    pub kind: QuotationKind,
+}
+
+impl Quotation {
+    /// Quotations whose Old Testament or New Testament side overlaps a passage, whichever
+    /// testament the passage is in.
+    pub fn find(passage: &Passage) -> Vec<Quotation> {
+        quotations()
+            .filter(|quotation| quotation.old.overlaps(passage) || quotation.new.overlaps(passage))
+            .collect()
+    }
+}
+
+/// Every quotation in the index, in New Testament order.
+pub fn quotations() -> impl Iterator<Item = Quotation> {
+    QUOTATIONS_DAT.lines().filter_map(|line| {
+        let mut fields = line.split('\t');
+        let new = fields.next()?.parse().ok()?;
+        let old = fields.next()?.parse().ok()?;
+        let kind = match fields.next()? {
+            "quotation" => QuotationKind::Quotation,
+            "fulfillment" => QuotationKind::Fulfillment,
+            _ => return None,
+        };
+        Some(Quotation { new, old, kind })
+    })
+}
+
+/// The byte range of the words in `new` which quote `old`.
+///
+/// Translations rarely render both sides the same way (the New Testament often follows the
+/// Septuagint), so this starts from the longest run of words the two have in common and widens it
+/// over nearby words which also appear in `old`, allowing a few misses in between. If no run of
+/// three words turns up, the whole verse is taken as the quotation.
+pub(crate) fn quoted_span(new: &str, old: &str) -> Range<usize> {
+    const MAX_MISSES: usize = 3;
+    const TRIVIAL: &[&str] = &[
+        "a", "an", "and", "be", "for", "in", "is", "it", "of", "the", "that", "to", "unto", "with",
+    ];
+
+    let new_words = words(new);
+    let old_words = words(old);
+
+    // Longest common substring, by words. Verses are short, so the quadratic table is fine.
+    let mut best = (0, 0);
+    let mut previous = vec![0; old_words.len() + 1];
+    for (i, (new_word, _)) in new_words.iter().enumerate() {
+        let mut current = vec![0; old_words.len() + 1];
+        for (j, (old_word, _)) in old_words.iter().enumerate() {
+            if new_word == old_word {
+                current[j + 1] = previous[j] + 1;
+                if current[j + 1] > best.0 {
+                    best = (current[j + 1], i + 1);
+                }
+            }
+        }
+        previous = current;
+    }
+
+    let (len, end) = best;
+    if len < 3 {
+        let start = new.len() - new.trim_start().len();
+        return start..new.trim_end().len();
+    }
+
+    // Widen the run while shared words keep turning up, but only as far as the last one that
+    // means something; "and the" in common doesn't make a quotation.
+    let shared = |word: &String| old_words.iter().any(|(old_word, _)| old_word == word);
+    let widen = |indices: &mut dyn Iterator<Item = usize>, mut edge: usize| {
+        let mut misses = 0;
+        for idx in indices {
+            let word = &new_words[idx].0;
+            if !shared(word) {
+                misses += 1;
+                if misses > MAX_MISSES {
+                    break;
+                }
+            } else {
+                misses = 0;
+                if !TRIVIAL.contains(&word.as_str()) {
+                    edge = idx;
+                }
+            }
+        }
+        edge
+    };
+
+    let first = widen(&mut (0..end - len).rev(), end - len);
+    let last = widen(&mut (end..new_words.len()), end - 1);
+    new_words[first].1.start..new_words[last].1.end
+}
+
+fn words(s: &str) -> Vec<(String, Range<usize>)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (idx, c) in s.char_indices().chain(std::iter::once((s.len(), ' '))) {
+        let is_word = c.is_alphanumeric() || c == '\'';
+        match (is_word, start) {
+            (true, None) => start = Some(idx),
+            (false, Some(first)) => {
+                words.push((s[first..idx].to_lowercase(), first..idx));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{quoted_span, Quotation};
+
+    #[test]
+    fn every_line_parses() {
+        let lines = super::QUOTATIONS_DAT.lines().count();
+        assert_eq!(lines, super::quotations().count());
+    }
+
+    #[test]
+    fn find() {
+        let isaiah_53 = Quotation::find(&"Isaiah 53".parse().unwrap());
+        assert!(isaiah_53
+            .iter()
+            .any(|quotation| quotation.new.to_string() == "Acts 8:32-33"));
+        assert!(isaiah_53
+            .iter()
+            .all(|quotation| quotation.old.book == crate::Book::Isaiah));
+
+        let isaiah_53_5 = Quotation::find(&"Isaiah 53:5".parse().unwrap());
+        assert_eq!(1, isaiah_53_5.len());
+
+        let john_19 = Quotation::find(&"John 19".parse().unwrap());
+        assert_eq!(3, john_19.len());
+    }
+
+    #[test]
+    fn span() {
+        let new =
+            "Behold, a virgin shall be with child, and shall bring forth a son, and they shall \
+            call his name Emmanuel, which being interpreted is, God with us.";
+        let old = "Therefore the Lord himself shall give you a sign; Behold, a virgin shall \
+            conceive, and bear a son, and shall call his name Immanuel.";
+        assert_eq!(
+            "Behold, a virgin shall be with child, and shall bring forth a son, and they shall \
+            call his name",
+            &new[quoted_span(new, old)]
+        );
+
+        let new = "Saying, Hosanna.";
+        assert_eq!(
+            "Saying, Hosanna.",
+            &new[quoted_span(new, "Save now, I beseech thee")]
+        );
+    }
+}
@@ -0,0 +1,281 @@
+use std::{fmt, str::FromStr};
+
+use crate::{book::Book, error::AbbrevStr, location::PartialLocation, translation::Translation};
+
+/// Everything a provider needs to know in order to link to a passage. A range of verses, like
+/// Psalm 127:4-5, is linked to as a range where the provider has a way to, and otherwise by its
+/// first verse.
+#[derive(Clone, Copy, Debug)]
+pub struct ReferenceLocator {
+    pub book: Book,
+    pub location: Option<PartialLocation>,
+    pub translation: Translation,
+}
+
+/// An online Bible which can be linked to.
+pub trait Reference {
+    /// Build a link to the passage described by `locator`.
+    fn url(&self, locator: &ReferenceLocator) -> String;
+
+    /// Whether a link can cover a range of verses, rather than just the first of them.
+    fn links_ranges(&self) -> bool {
+        false
+    }
+}
+
+/// The set of online Bibles we know how to link to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReferenceProvider {
+    #[default]
+    Biblia,
+    BibleGateway,
+    BlueLetterBible,
+}
+
+impl Reference for ReferenceProvider {
+    fn url(&self, locator: &ReferenceLocator) -> String {
+        match self {
+            ReferenceProvider::Biblia => Biblia.url(locator),
+            ReferenceProvider::BibleGateway => BibleGateway.url(locator),
+            ReferenceProvider::BlueLetterBible => BlueLetterBible.url(locator),
+        }
+    }
+
+    fn links_ranges(&self) -> bool {
+        match self {
+            ReferenceProvider::Biblia => Biblia.links_ranges(),
+            ReferenceProvider::BibleGateway => BibleGateway.links_ranges(),
+            ReferenceProvider::BlueLetterBible => BlueLetterBible.links_ranges(),
+        }
+    }
+}
+
+impl FromStr for ReferenceProvider {
+    type Err = ParseProviderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "BIBLIA" => Ok(ReferenceProvider::Biblia),
+            "BIBLEGATEWAY" | "BIBLE-GATEWAY" | "BG" => Ok(ReferenceProvider::BibleGateway),
+            "BLUELETTERBIBLE" | "BLUE-LETTER-BIBLE" | "BLB" => {
+                Ok(ReferenceProvider::BlueLetterBible)
+            }
+            _ => Err(ParseProviderError::new(s)),
+        }
+    }
+}
+
+impl fmt::Display for ReferenceProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReferenceProvider::Biblia => f.write_str("biblia"),
+            ReferenceProvider::BibleGateway => f.write_str("biblegateway"),
+            ReferenceProvider::BlueLetterBible => f.write_str("blueletterbible"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("unknown reference provider '{text}'")]
+pub struct ParseProviderError {
+    text: String,
+}
+
+impl ParseProviderError {
+    fn new(text: impl AbbrevStr) -> Self {
+        Self { text: text.get(20) }
+    }
+}
+
+/// https://biblia.com
+///
+/// Biblia links look like `https://biblia.com/bible/kjv1900/1-corinthians/13/4`, or
+/// `.../13/4-7` for a range.
+struct Biblia;
+
+impl Biblia {
+    fn translation_code(translation: Translation) -> String {
+        match translation {
+            Translation::Kjv => "kjv1900".into(),
+            Translation::Asv => "asv".into(),
+            Translation::Esv => "esv".into(),
+            Translation::Niv => "niv2011".into(),
+            // Best guess: Biblia mostly uses the plain abbreviation.
+            Translation::User(user) => user.as_str().to_ascii_lowercase(),
+        }
+    }
+}
+
+impl Reference for Biblia {
+    fn url(&self, locator: &ReferenceLocator) -> String {
+        let translation = Biblia::translation_code(locator.translation);
+        let book = slug(locator.book);
+        let mut url = format!("https://biblia.com/bible/{translation}/{book}");
+
+        if let Some(location) = locator.location {
+            url += &format!("/{}", location.chapter);
+            if let Some(verse) = location.verse {
+                url += &format!("/{verse}");
+                if let Some(end) = location.end.filter(|&end| end > verse) {
+                    url += &format!("-{end}");
+                }
+            }
+        }
+
+        url
+    }
+
+    fn links_ranges(&self) -> bool {
+        true
+    }
+}
+
+/// https://www.biblegateway.com
+///
+/// BibleGateway takes the reference as a search, like
+/// `https://www.biblegateway.com/passage/?search=1+Corinthians+13%3A4-7&version=KJV`, so a range
+/// of verses can be linked to as well.
+struct BibleGateway;
+
+impl BibleGateway {
+    fn translation_code(translation: Translation) -> String {
+        match translation {
+            Translation::Kjv => "KJV".into(),
+            Translation::Asv => "ASV".into(),
+            Translation::Esv => "ESV".into(),
+            Translation::Niv => "NIV".into(),
+            Translation::User(user) => user.as_str().to_ascii_uppercase(),
+        }
+    }
+}
+
+impl Reference for BibleGateway {
+    fn url(&self, locator: &ReferenceLocator) -> String {
+        let mut search = locator.book.to_string().replace(' ', "+");
+        if let Some(location) = locator.location {
+            search += &format!("+{}", location.chapter);
+            if let Some(verse) = location.verse {
+                search += &format!("%3A{verse}");
+                if let Some(end) = location.end.filter(|&end| end > verse) {
+                    search += &format!("-{end}");
+                }
+            }
+        }
+
+        let version = BibleGateway::translation_code(locator.translation);
+        format!("https://www.biblegateway.com/passage/?search={search}&version={version}")
+    }
+
+    fn links_ranges(&self) -> bool {
+        true
+    }
+}
+
+/// https://www.blueletterbible.org
+///
+/// Blue Letter Bible links look like `https://www.blueletterbible.org/kjv/1co/13/4/`, with its
+/// own three-character book codes. There's no link to a whole book, so that goes to its first
+/// chapter.
+struct BlueLetterBible;
+
+impl BlueLetterBible {
+    /// Each book's code, in canonical order.
+    const BOOKS: [&'static str; 66] = [
+        "gen", "exo", "lev", "num", "deu", "jos", "jdg", "rth", "1sa", "2sa", "1ki", "2ki", "1ch",
+        "2ch", "ezr", "neh", "est", "job", "psa", "pro", "ecc", "sng", "isa", "jer", "lam", "eze",
+        "dan", "hos", "joe", "amo", "oba", "jon", "mic", "nah", "hab", "zep", "hag", "zec", "mal",
+        "mat", "mar", "luk", "jhn", "act", "rom", "1co", "2co", "gal", "eph", "phl", "col", "1th",
+        "2th", "1ti", "2ti", "tit", "phm", "heb", "jas", "1pe", "2pe", "1jo", "2jo", "3jo", "jde",
+        "rev",
+    ];
+
+    fn translation_code(translation: Translation) -> String {
+        match translation {
+            Translation::Kjv => "kjv".into(),
+            Translation::Asv => "asv".into(),
+            Translation::Esv => "esv".into(),
+            Translation::Niv => "niv".into(),
+            Translation::User(user) => user.as_str().to_ascii_lowercase(),
+        }
+    }
+}
+
+impl Reference for BlueLetterBible {
+    fn url(&self, locator: &ReferenceLocator) -> String {
+        let translation = BlueLetterBible::translation_code(locator.translation);
+        let book = BlueLetterBible::BOOKS[usize::from(locator.book as u8) - 1];
+        let (chapter, verse) = match locator.location {
+            Some(location) => (location.chapter, location.verse.unwrap_or(1)),
+            None => (1, 1),
+        };
+        format!("https://www.blueletterbible.org/{translation}/{book}/{chapter}/{verse}/")
+    }
+}
+
+/// Lowercase book name with spaces replaced by dashes, e.g. "song-of-songs".
+fn slug(book: Book) -> String {
+    book.to_string().to_ascii_lowercase().replace(' ', "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Reference, ReferenceLocator, ReferenceProvider};
+    use crate::{Book, PartialLocation, Translation};
+
+    #[test]
+    fn biblia_url() {
+        let locator = ReferenceLocator {
+            book: Book::Corinthians1,
+            location: Some(PartialLocation {
+                chapter: 13,
+                verse: Some(4),
+                end: None,
+            }),
+            translation: Translation::Kjv,
+        };
+        assert_eq!(
+            "https://biblia.com/bible/kjv1900/1-corinthians/13/4",
+            ReferenceProvider::Biblia.url(&locator)
+        );
+    }
+
+    #[test]
+    fn other_providers() {
+        let locator = ReferenceLocator {
+            book: Book::Corinthians1,
+            location: Some(PartialLocation {
+                chapter: 13,
+                verse: Some(4),
+                end: Some(7),
+            }),
+            translation: Translation::Esv,
+        };
+        assert_eq!(
+            "https://www.biblegateway.com/passage/?search=1+Corinthians+13%3A4-7&version=ESV",
+            ReferenceProvider::BibleGateway.url(&locator)
+        );
+        assert_eq!(
+            "https://www.blueletterbible.org/esv/1co/13/4/",
+            ReferenceProvider::BlueLetterBible.url(&locator)
+        );
+        assert_eq!(
+            "https://biblia.com/bible/esv/1-corinthians/13/4-7",
+            ReferenceProvider::Biblia.url(&locator)
+        );
+        assert!(!ReferenceProvider::BlueLetterBible.links_ranges());
+
+        let jude = ReferenceLocator {
+            book: Book::Jude,
+            location: None,
+            translation: Translation::Kjv,
+        };
+        assert_eq!(
+            "https://www.blueletterbible.org/kjv/jde/1/1/",
+            ReferenceProvider::BlueLetterBible.url(&jude)
+        );
+        assert_eq!(
+            Ok(ReferenceProvider::BlueLetterBible),
+            "blb".parse::<ReferenceProvider>().map_err(|_| ())
+        );
+    }
+}
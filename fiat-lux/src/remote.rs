@@ -0,0 +1,365 @@
+use std::{
+    path::PathBuf,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde_json::Value;
+
+use crate::{
+    book::Book,
+    cache::{self, ChapterCache},
+    location::PartialLocation,
+    text::Text,
+    translation::Translation,
+    Error, Result,
+};
+
+/// Credentials and settings for fetching remote translations.
+#[derive(Clone, Debug, Default)]
+pub struct RemoteConfig {
+    /// Token for https://api.esv.org
+    pub esv_key: Option<String>,
+
+    /// Key for https://scripture.api.bible
+    pub api_bible_key: Option<String>,
+
+    /// API.Bible's identifier for the NIV. Which ids are available depends on what a given key is
+    /// licensed for, so there's no sensible default.
+    pub niv_bible_id: Option<String>,
+
+    /// Fetched chapters are kept here. Without a cache, every lookup hits the network.
+    pub cache_dir: Option<PathBuf>,
+
+    /// How long a cached chapter is used before it's fetched again (default: 30 days). Expired
+    /// chapters are still used if the network is unavailable.
+    pub cache_ttl: Option<Duration>,
+
+    /// Upper bound on the size of the cache (default: 50 MB).
+    pub cache_max_bytes: Option<u64>,
+
+    /// What to do when a request fails in a way that might succeed on a second try.
+    pub retry: RetryPolicy,
+
+    /// The least amount of time to leave between requests, to stay within an API's rate limits.
+    pub min_interval: Duration,
+}
+
+/// How hard to try before giving up on a request.
+///
+/// Connection failures, timeouts, rate limiting (429) and server errors (5xx) are retried, waiting
+/// twice as long after each failure. Anything else (a rejected key, say) won't get better by
+/// asking again.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+}
+
+pub(crate) struct Remote {
+    config: RemoteConfig,
+    agent: ureq::Agent,
+    cache: Option<ChapterCache>,
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl Remote {
+    pub fn new(config: RemoteConfig) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(15))
+            .build();
+        let cache = config.cache_dir.clone().map(|dir| {
+            ChapterCache::new(
+                dir,
+                config.cache_ttl.unwrap_or(cache::DEFAULT_TTL),
+                config.cache_max_bytes.unwrap_or(cache::DEFAULT_MAX_BYTES),
+            )
+        });
+
+        Self {
+            config,
+            agent,
+            cache,
+            last_request: Mutex::new(None),
+        }
+    }
+
+    /// Send a request, retrying per the retry policy, and parse the response as json.
+    fn send(&self, translation: Translation, request: ureq::Request) -> Result<Value> {
+        let policy = self.config.retry;
+        let mut attempt = 0;
+
+        loop {
+            self.throttle();
+            let error = match request.clone().call() {
+                Ok(response) => return Ok(response.into_json()?),
+                Err(e) => e,
+            };
+
+            if attempt >= policy.max_retries || !is_transient(&error) {
+                return Err(Error::http(translation, error));
+            }
+
+            // If the server told us how long to wait, we'll take its word for it.
+            let delay = retry_after(&error).unwrap_or_else(|| policy.backoff(attempt));
+            thread::sleep(delay.min(policy.max_backoff));
+            attempt += 1;
+        }
+    }
+
+    /// Wait out whatever remains of the minimum interval since the last request.
+    fn throttle(&self) {
+        let mut last_request = self.last_request.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(last) = *last_request {
+            let elapsed = last.elapsed();
+            if elapsed < self.config.min_interval {
+                thread::sleep(self.config.min_interval - elapsed);
+            }
+        }
+        *last_request = Some(Instant::now());
+    }
+
+    pub fn lookup(
+        &self,
+        book: Book,
+        location: Option<PartialLocation>,
+        translation: Translation,
+    ) -> Result<Vec<Text>> {
+        // Fetching a whole book would mean dozens of requests, which is a good way to get a key
+        // revoked.
+        let location =
+            location.ok_or_else(|| Error::remote(translation, "please specify a chapter"))?;
+
+        let chapter = location.chapter;
+        let cached = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get(translation, book, chapter));
+
+        let verses = match cached {
+            Some(cached) if cached.fresh => cached.verses,
+            cached => match self.fetch(translation, book, chapter) {
+                Ok(verses) => {
+                    // Failing to write the cache isn't worth failing the lookup over.
+                    if let Some(cache) = &self.cache {
+                        let _ = cache.put(translation, book, chapter, &verses);
+                    }
+                    verses
+                }
+
+                // Old text beats no text.
+                Err(e) => cached.map(|cached| cached.verses).ok_or(e)?,
+            },
+        };
+
+        Ok(verses
+            .into_iter()
+            .filter(|&(verse, _)| location.contains(verse))
+            .map(|(verse, content)| Text {
+                book,
+                chapter,
+                verse,
+                content,
+            })
+            .collect())
+    }
+
+    fn fetch(
+        &self,
+        translation: Translation,
+        book: Book,
+        chapter: u16,
+    ) -> Result<Vec<(u16, String)>> {
+        let content = match translation {
+            Translation::Esv => self.fetch_esv(book, chapter)?,
+            Translation::Niv => {
+                let id = self.config.niv_bible_id.as_deref().ok_or_else(|| {
+                    Error::remote(
+                        translation,
+                        "no API.Bible id configured for this translation",
+                    )
+                })?;
+                self.fetch_api_bible(translation, id, book, chapter)?
+            }
+            _ => return Err(Error::remote(translation, "not a remote translation")),
+        };
+
+        let verses = parse_numbered_verses(&content);
+        if verses.is_empty() {
+            return Err(Error::remote(translation, "no verses in response"));
+        }
+        Ok(verses)
+    }
+
+    fn fetch_esv(&self, book: Book, chapter: u16) -> Result<String> {
+        let translation = Translation::Esv;
+        let key = self
+            .config
+            .esv_key
+            .as_deref()
+            .ok_or_else(|| Error::remote(translation, "no api key configured"))?;
+
+        let query = format!("{book} {chapter}");
+        let request = self
+            .agent
+            .get("https://api.esv.org/v3/passage/text/")
+            .set("Authorization", &format!("Token {key}"))
+            .query("q", &query)
+            .query("include-passage-references", "false")
+            .query("include-verse-numbers", "true")
+            .query("include-first-verse-numbers", "true")
+            .query("include-footnotes", "false")
+            .query("include-headings", "false")
+            .query("include-short-copyright", "false");
+        let response = self.send(translation, request)?;
+
+        response["passages"][0]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| Error::remote(translation, "unexpected response"))
+    }
+
+    fn fetch_api_bible(
+        &self,
+        translation: Translation,
+        bible_id: &str,
+        book: Book,
+        chapter: u16,
+    ) -> Result<String> {
+        let key = self
+            .config
+            .api_bible_key
+            .as_deref()
+            .ok_or_else(|| Error::remote(translation, "no api key configured"))?;
+
+        let url = format!(
+            "https://api.scripture.api.bible/v1/bibles/{bible_id}/chapters/{}.{chapter}",
+            book.usfm_code()
+        );
+        let request = self
+            .agent
+            .get(&url)
+            .set("api-key", key)
+            .query("content-type", "text")
+            .query("include-notes", "false")
+            .query("include-titles", "false")
+            .query("include-chapter-numbers", "false")
+            .query("include-verse-numbers", "true")
+            .query("include-verse-spans", "false");
+        let response = self.send(translation, request)?;
+
+        response["data"]["content"]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| Error::remote(translation, "unexpected response"))
+    }
+}
+
+fn is_transient(error: &ureq::Error) -> bool {
+    match error {
+        ureq::Error::Status(code, _) => *code == 429 || *code >= 500,
+        ureq::Error::Transport(_) => true,
+    }
+}
+
+fn retry_after(error: &ureq::Error) -> Option<Duration> {
+    match error {
+        ureq::Error::Status(_, response) => response
+            .header("Retry-After")
+            .and_then(|seconds| seconds.trim().parse().ok())
+            .map(Duration::from_secs),
+        ureq::Error::Transport(_) => None,
+    }
+}
+
+/// Both APIs deliver plain text with verse numbers in square brackets, like
+/// `[16] For God so loved the world... [17] For God sent not...`
+fn parse_numbered_verses(content: &str) -> Vec<(u16, String)> {
+    let mut verses = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find('[') {
+        let after = &rest[start + 1..];
+        let Some(end) = after.find(']') else {
+            break;
+        };
+
+        let Ok(verse) = after[..end].trim().parse::<u16>() else {
+            rest = after;
+            continue;
+        };
+
+        let body = &after[end + 1..];
+        let next = next_marker(body).unwrap_or(body.len());
+        let text = body[..next]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        verses.push((verse, text));
+        rest = &body[next..];
+    }
+
+    verses
+}
+
+/// Find the next verse marker, skipping any brackets which don't contain a number.
+fn next_marker(s: &str) -> Option<usize> {
+    let mut offset = 0;
+    while let Some(idx) = s[offset..].find('[') {
+        let start = offset + idx;
+        let after = &s[start + 1..];
+        if let Some(end) = after.find(']') {
+            if after[..end].trim().parse::<u16>().is_ok() {
+                return Some(start);
+            }
+        }
+        offset = start + 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    #[test]
+    fn backoff() {
+        let policy = super::RetryPolicy::default();
+        assert_eq!(Duration::from_millis(500), policy.backoff(0));
+        assert_eq!(Duration::from_secs(2), policy.backoff(2));
+        assert_eq!(Duration::from_secs(8), policy.backoff(10));
+    }
+
+    #[test]
+    fn parse_numbered_verses() {
+        let content = "  [16] For God so loved\n the world. [17] For God [sent] not his Son.\n";
+        let verses = super::parse_numbered_verses(content);
+        assert_eq!(
+            vec![
+                (16, String::from("For God so loved the world.")),
+                (17, String::from("For God [sent] not his Son.")),
+            ],
+            verses
+        );
+    }
+}
@@ -0,0 +1,520 @@
+//! Searching without an index, for builds without the `index` feature: every verse in scope is
+//! read from the text and checked against the query, and words within a small edit distance of a
+//! query word count as matches, so a typo or an archaic spelling still finds something.
+//!
+//! That's slower than asking tantivy, but a translation is only about 31,000 verses, and it leaves
+//! tantivy out of the binary altogether.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    ops::Range,
+    path::PathBuf,
+};
+
+use crate::{
+    book::Book,
+    location::{Location, PartialLocation},
+    offsets::{self, id_range},
+    scope::Scope,
+    text::{parse_verses_with_id, Text},
+    translation::Translation,
+    user, Error, Result,
+};
+
+/// Tokens longer than this are dropped, as tantivy's default tokenizer drops them.
+const MAX_TOKEN_LEN: usize = 40;
+
+/// Verses read straight from the embedded text or the dat files of translations the user added.
+pub(crate) struct Scan {
+    data_dir: Option<PathBuf>,
+}
+
+impl Scan {
+    pub(crate) fn new(data_dir: Option<PathBuf>) -> Self {
+        Self { data_dir }
+    }
+
+    pub(crate) fn lookup(
+        &self,
+        book: Book,
+        location: Option<PartialLocation>,
+        translation: Translation,
+    ) -> Result<Vec<Text>> {
+        if let Some(texts) = offsets::lookup_embedded(book, location, translation) {
+            return Ok(texts);
+        }
+
+        let (first, last) = id_range(book, location);
+        self.user_verses(translation, |id| (first..=last).contains(&id))
+    }
+
+    /// Every verse matching a query with its score, best first, then in canonical order.
+    pub(crate) fn search(
+        &self,
+        query: &str,
+        translation: Translation,
+        scopes: &[Scope],
+        limit: Option<usize>,
+    ) -> Result<Vec<(f32, Text)>> {
+        let query = Query::parse(query)?;
+        let mut matcher = Matcher::default();
+        let mut hits: Vec<_> = self
+            .verses(translation, scopes)?
+            .into_iter()
+            .filter_map(|text| {
+                let score = matcher.score(&query, &tokens(&text.content))?;
+                Some((score, text))
+            })
+            .collect();
+
+        hits.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        if let Some(limit) = limit {
+            hits.truncate(limit);
+        }
+        Ok(hits)
+    }
+
+    pub(crate) fn count_by_chapter(
+        &self,
+        query: &str,
+        translation: Translation,
+        scopes: &[Scope],
+    ) -> Result<Vec<(Book, u16, u64)>> {
+        let mut chapters: Vec<(Book, u16, u64)> = Vec::new();
+        let mut hits = self.search(query, translation, scopes, None)?;
+        hits.sort_by(|(_, a), (_, b)| a.cmp(b));
+        for (_, text) in hits {
+            match chapters.last_mut() {
+                Some((book, chapter, count)) if (*book, *chapter) == (text.book, text.chapter) => {
+                    *count += 1
+                }
+                _ => chapters.push((text.book, text.chapter, 1)),
+            }
+        }
+        Ok(chapters)
+    }
+
+    /// Every verse where a word or phrase occurs, exactly, in canonical order, with the byte range
+    /// of each occurrence.
+    pub(crate) fn find_term(
+        &self,
+        phrase: &str,
+        translation: Translation,
+        scopes: &[Scope],
+    ) -> Result<Vec<(Text, Vec<Range<usize>>)>> {
+        let terms = terms(phrase);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Ok(self
+            .verses(translation, scopes)?
+            .into_iter()
+            .filter_map(|text| {
+                let ranges = occurrences(&terms, &tokens(&text.content));
+                (!ranges.is_empty()).then_some((text, ranges))
+            })
+            .collect())
+    }
+
+    /// How many verses contain each of some words or phrases.
+    pub(crate) fn verse_counts(
+        &self,
+        phrases: &[&str],
+        translation: Translation,
+        scopes: &[Scope],
+    ) -> Result<Vec<usize>> {
+        let phrases: Vec<_> = phrases.iter().map(|phrase| terms(phrase)).collect();
+        let mut counts = vec![0; phrases.len()];
+        for text in self.verses(translation, scopes)? {
+            let tokens = tokens(&text.content);
+            for (count, terms) in counts.iter_mut().zip(&phrases) {
+                if !terms.is_empty() && !occurrences(terms, &tokens).is_empty() {
+                    *count += 1;
+                }
+            }
+        }
+        Ok(counts)
+    }
+
+    pub(crate) fn verse_total(&self, translation: Translation, scopes: &[Scope]) -> Result<usize> {
+        Ok(self.verses(translation, scopes)?.len())
+    }
+
+    /// Every verse in the given books or testaments, in canonical order. No scopes means the whole
+    /// Bible.
+    fn verses(&self, translation: Translation, scopes: &[Scope]) -> Result<Vec<Text>> {
+        let books: HashSet<Book> = scopes.iter().flat_map(|scope| scope.books()).collect();
+        let in_scope = |book: Book| books.is_empty() || books.contains(&book);
+
+        if translation.text().is_some() {
+            return Ok((1..=66)
+                .map(Book::from_u8)
+                .filter(|&book| in_scope(book))
+                .flat_map(|book| offsets::lookup_embedded(book, None, translation))
+                .flatten()
+                .collect());
+        }
+
+        self.user_verses(translation, |id| {
+            in_scope(Book::from_u8((id / 1_000_000) as u8))
+        })
+    }
+
+    /// The verses of a translation the user added whose ids pass `filter`, read from its dat file.
+    fn user_verses(
+        &self,
+        translation: Translation,
+        filter: impl Fn(u32) -> bool,
+    ) -> Result<Vec<Text>> {
+        let data_dir = self
+            .data_dir
+            .as_deref()
+            .ok_or(Error::UnknownTranslation(translation))?;
+        let text = fs::read_to_string(user::path(data_dir, translation))?;
+
+        let mut texts: Vec<_> = parse_verses_with_id(&text)
+            .filter(|&(id, _)| filter(id as u32))
+            .map(|(id, content)| {
+                let Location {
+                    book,
+                    chapter,
+                    verse,
+                } = Location::from_id(id);
+                Text {
+                    book,
+                    chapter,
+                    verse,
+                    content: content.into(),
+                }
+            })
+            .collect();
+        texts.sort();
+        Ok(texts)
+    }
+}
+
+/// Rank some pieces of text, such as your notes, against a search query in the same syntax as
+/// [`Bible::search`](crate::Bible::search). Each match is its position in `texts`, with its score,
+/// best first.
+pub fn search_texts(texts: &[&str], query: &str, limit: usize) -> Result<Vec<(f32, usize)>> {
+    let query = Query::parse(query)?;
+    let mut matcher = Matcher::default();
+    let mut hits: Vec<_> = texts
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, text)| Some((matcher.score(&query, &tokens(text))?, idx)))
+        .collect();
+
+    hits.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    hits.truncate(limit);
+    Ok(hits)
+}
+
+/// Byte ranges of the words in `content` matching a search query, near misses included.
+pub(crate) fn highlights(query: &str, content: &str) -> Result<Vec<Range<usize>>> {
+    let query = Query::parse(query)?;
+    let tokens = tokens(content);
+    let mut matcher = Matcher::default();
+
+    let mut ranges = Vec::new();
+    for clause in query
+        .clauses
+        .iter()
+        .filter(|clause| clause.occur != Occur::MustNot)
+    {
+        match clause.terms.as_slice() {
+            [word] => ranges.extend(
+                tokens
+                    .iter()
+                    .filter(|(token, _)| matcher.distance(word, token).is_some())
+                    .map(|(_, range)| range.clone()),
+            ),
+            terms => {
+                for run in occurrences(terms, &tokens) {
+                    ranges.extend(
+                        tokens
+                            .iter()
+                            .map(|(_, range)| range.clone())
+                            .filter(|range| run.start <= range.start && range.end <= run.end),
+                    );
+                }
+            }
+        }
+    }
+
+    ranges.sort_by_key(|range| range.start);
+    ranges.dedup();
+    Ok(ranges)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Occur {
+    Should,
+    Must,
+    MustNot,
+}
+
+/// A word, or a quoted phrase, and whether it has to appear.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Clause {
+    occur: Occur,
+    terms: Vec<String>,
+}
+
+/// The part of the query language a scan can answer: plain words, which rank a verse higher the
+/// more of them it has, `+word` and `-word`, and quoted phrases. AND, OR and parentheses need the
+/// index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Query {
+    clauses: Vec<Clause>,
+}
+
+impl Query {
+    fn parse(query: &str) -> Result<Self> {
+        if !query.matches('"').count().is_multiple_of(2) {
+            return Err(Error::bad_query(
+                query,
+                "a quoted phrase is missing its closing quote",
+            ));
+        }
+
+        let mut clauses = Vec::new();
+        // Outside quotes on the even pieces, inside them on the odd ones.
+        let pieces: Vec<_> = query.split('"').collect();
+        let mut occur = Occur::Should;
+        for (idx, piece) in pieces.iter().enumerate() {
+            if idx % 2 == 1 {
+                let terms = terms(piece);
+                if !terms.is_empty() {
+                    clauses.push(Clause { occur, terms });
+                }
+                occur = Occur::Should;
+                continue;
+            }
+
+            for word in piece.split_whitespace() {
+                if word == "AND" || word == "OR" || word.contains(['(', ')']) {
+                    return Err(Error::bad_query(
+                        query,
+                        "AND, OR and parentheses need the search index, which this build leaves out",
+                    ));
+                }
+
+                let (prefix, rest) = if let Some(rest) = word.strip_prefix('+') {
+                    (Occur::Must, rest)
+                } else if let Some(rest) = word.strip_prefix('-') {
+                    (Occur::MustNot, rest)
+                } else {
+                    (Occur::Should, word)
+                };
+                if rest.is_empty() {
+                    // Only +"a phrase" or -"a phrase" leaves a sign on its own.
+                    if !piece.ends_with(word) || idx + 1 == pieces.len() {
+                        return Err(Error::bad_query(
+                            query,
+                            format!("'{word}' goes directly before a word, as in {word}faith"),
+                        ));
+                    }
+                    occur = prefix;
+                    continue;
+                }
+                for term in terms(rest) {
+                    clauses.push(Clause {
+                        occur: prefix,
+                        terms: vec![term],
+                    });
+                }
+            }
+        }
+
+        if clauses.iter().all(|clause| clause.occur == Occur::MustNot) {
+            return Err(Error::bad_query(
+                query,
+                "at least one word has to be something other than -excluded",
+            ));
+        }
+        Ok(Self { clauses })
+    }
+}
+
+/// Matches query words against the words of a verse, remembering the distance between each pair
+/// it's seen, since the same few thousand words come up over and over.
+#[derive(Default)]
+struct Matcher {
+    distances: HashMap<String, HashMap<String, Option<usize>>>,
+}
+
+impl Matcher {
+    /// A verse's score for a query, or `None` if it doesn't match. Each clause found adds up to
+    /// one, less for a word only found misspelled, and long verses lose a little, as they do with
+    /// bm25, so that of two verses with the same words the one more about them comes first.
+    fn score(&mut self, query: &Query, tokens: &[(String, Range<usize>)]) -> Option<f32> {
+        let mut score = 0.0;
+        let mut required = false;
+        for clause in &query.clauses {
+            let found = match clause.terms.as_slice() {
+                [word] => tokens
+                    .iter()
+                    .filter_map(|(token, _)| self.distance(word, token))
+                    .min()
+                    .map(|distance| 1.0 / (1 + distance) as f32),
+                terms => (!occurrences(terms, tokens).is_empty()).then_some(1.0),
+            };
+
+            match (clause.occur, found) {
+                (Occur::MustNot, Some(_)) | (Occur::Must, None) => return None,
+                (Occur::MustNot, None) => {}
+                (occur, Some(found)) => {
+                    required |= occur == Occur::Must;
+                    score += found;
+                }
+                (Occur::Should, None) => {}
+            }
+        }
+
+        let length = 1.0 + tokens.len() as f32 / 100.0;
+        (score > 0.0 || required).then_some(score / length)
+    }
+
+    /// How far a word of the verse is from a query word, if it's close enough to count: exact for
+    /// words of four letters or fewer, which are a letter away from too many others (love, live,
+    /// lose), one edit for up to eight, and two for anything longer.
+    fn distance(&mut self, word: &str, token: &str) -> Option<usize> {
+        if word == token {
+            return Some(0);
+        }
+
+        let limit = match word.chars().count() {
+            0..=4 => return None,
+            5..=8 => 1,
+            _ => 2,
+        };
+        if let Some(&distance) = self.distances.get(word).and_then(|known| known.get(token)) {
+            return distance;
+        }
+
+        let distance = Some(levenshtein(word, token)).filter(|&distance| distance <= limit);
+        self.distances
+            .entry(word.into())
+            .or_default()
+            .insert(token.into(), distance);
+        distance
+    }
+}
+
+/// The number of single-character insertions, deletions and substitutions between two words.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// The words of some text, split at anything but letters and digits and lowercased, the way
+/// tantivy's default tokenizer does it, with their byte ranges.
+fn tokens(text: &str) -> Vec<(String, Range<usize>)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    // A trailing space closes the last word.
+    for (idx, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (start, c.is_alphanumeric()) {
+            (None, true) => start = Some(idx),
+            (Some(from), false) => {
+                if idx - from <= MAX_TOKEN_LEN {
+                    tokens.push((text[from..idx].to_lowercase(), from..idx));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+fn terms(phrase: &str) -> Vec<String> {
+    tokens(phrase).into_iter().map(|(term, _)| term).collect()
+}
+
+/// The byte range of each run of `terms` in some tokens.
+fn occurrences(terms: &[String], tokens: &[(String, Range<usize>)]) -> Vec<Range<usize>> {
+    tokens
+        .windows(terms.len())
+        .filter(|window| window.iter().map(|(word, _)| word).eq(terms))
+        .map(|window| window[0].1.start..window[window.len() - 1].1.end)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Occur, Query, Scan};
+    use crate::{Book, Scope, Translation};
+
+    #[test]
+    fn queries() {
+        let query = Query::parse(r#"+faith -works "by grace" hope"#).unwrap();
+        let clauses: Vec<_> = query
+            .clauses
+            .iter()
+            .map(|clause| (clause.occur, clause.terms.join(" ")))
+            .collect();
+        assert_eq!(
+            vec![
+                (Occur::Must, String::from("faith")),
+                (Occur::MustNot, String::from("works")),
+                (Occur::Should, String::from("by grace")),
+                (Occur::Should, String::from("hope")),
+            ],
+            clauses
+        );
+
+        assert!(Query::parse("sheep OR goats").is_err());
+        assert!(Query::parse(r#""in the beginning"#).is_err());
+        assert!(Query::parse("-works").is_err());
+        assert!(Query::parse("faith - works").is_err());
+        assert!(Query::parse(r#"-"by grace" faith"#).is_ok());
+    }
+
+    #[test]
+    fn scan() {
+        let scan = Scan::new(None);
+        let hits = scan
+            .search("+jesus +wept", Translation::Kjv, &[], None)
+            .unwrap();
+        assert_eq!(
+            (Book::John, 11, 35),
+            (hits[0].1.book, hits[0].1.chapter, hits[0].1.verse)
+        );
+
+        // One letter out still finds it, a little further down.
+        let hits = scan
+            .search(
+                "beginnig",
+                Translation::Kjv,
+                &[Scope::Book(Book::Genesis)],
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            (Book::Genesis, 1, 1),
+            (hits[0].1.book, hits[0].1.chapter, hits[0].1.verse)
+        );
+
+        let found = scan
+            .find_term("jesus wept", Translation::Kjv, &[Scope::Gospels])
+            .unwrap();
+        assert_eq!(vec![0..10], found[0].1);
+
+        let highlights = super::highlights("wept", "Jesus wept.").unwrap();
+        assert_eq!(vec![6..10], highlights);
+    }
+}
@@ -0,0 +1,49 @@
+use std::{fmt, str::FromStr};
+
+use crate::book::{Book, ParseBookError};
+
+/// A part of the Bible to restrict a search to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    Book(Book),
+    OldTestament,
+    NewTestament,
+    Gospels,
+}
+
+impl Scope {
+    /// Every book in the scope.
+    pub fn books(self) -> impl Iterator<Item = Book> {
+        let range = match self {
+            Scope::Book(book) => book as u8..=book as u8,
+            Scope::OldTestament => 1..=39,
+            Scope::NewTestament => 40..=66,
+            Scope::Gospels => 40..=43,
+        };
+        range.map(Book::from_u8)
+    }
+}
+
+impl FromStr for Scope {
+    type Err = ParseBookError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "ot" | "old testament" => Ok(Scope::OldTestament),
+            "nt" | "new testament" => Ok(Scope::NewTestament),
+            "gospels" => Ok(Scope::Gospels),
+            _ => s.parse().map(Scope::Book),
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scope::Book(book) => book.fmt(f),
+            Scope::OldTestament => f.write_str("OT"),
+            Scope::NewTestament => f.write_str("NT"),
+            Scope::Gospels => f.write_str("Gospels"),
+        }
+    }
+}
@@ -0,0 +1,843 @@
+use std::{collections::HashSet, fmt::Write, ops::Range, path::Path};
+
+use tantivy::{
+    collector::{Count, DocSetCollector, FacetCollector, TopDocs},
+    directory::MmapDirectory,
+    query::{BooleanQuery, Occur, PhraseQuery, Query, QueryParser, QueryParserError, TermQuery},
+    schema::{Facet, Field, IndexRecordOption, Schema},
+    tokenizer::{TextAnalyzer, TokenStream, TokenizerManager},
+    Index, IndexWriter, ReloadPolicy, Term,
+};
+
+use crate::{
+    book::Book,
+    index::{index_path, IndexOptions, Stamp},
+    location::{Location, PartialLocation},
+    scope::Scope,
+    text::{parse_verses_with_id, Text},
+    translation::Translation,
+    user, Error, ExplainedHit, Explanation, Result,
+};
+
+pub(crate) struct SearchFields {
+    pub translation: Field,
+    pub location: Field,
+    /// The words of each verse, indexed but not kept.
+    pub content: Field,
+    /// The verse as it's shown, kept but not indexed. A lean index leaves it out for the embedded
+    /// translations.
+    pub text: Field,
+}
+
+impl SearchFields {
+    pub(crate) fn from_schema(schema: &Schema) -> Self {
+        Self {
+            translation: schema.get_field("translation").unwrap(),
+            location: schema.get_field("location").unwrap(),
+            content: schema.get_field("content").unwrap(),
+            text: schema.get_field("text").unwrap(),
+        }
+    }
+}
+
+pub(crate) fn search_by_book_and_location(
+    index: &Index,
+    fields: &SearchFields,
+    book: Book,
+    location: Option<PartialLocation>,
+    translation: Translation,
+) -> tantivy::Result<Vec<Text>> {
+    let range = location;
+    let mut buf = format!("/{}", book as u8);
+    if let Some(location) = &location {
+        let chapter = location.chapter;
+        write!(buf, "/{chapter}").unwrap();
+
+        // For a range of verses, we query the whole chapter and filter afterward.
+        if let (Some(verse), None) = (location.verse, location.end) {
+            write!(buf, "/{verse}").unwrap()
+        }
+    }
+
+    let location = TermQuery::new(
+        Term::from_facet(fields.location, &Facet::from(&buf)),
+        IndexRecordOption::Basic,
+    );
+    let translation = TermQuery::new(
+        Term::from_facet(fields.translation, &translation.facet()),
+        IndexRecordOption::Basic,
+    );
+    let query = BooleanQuery::intersection(vec![Box::new(location), Box::new(translation)]);
+
+    let reader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()?;
+    let searcher = reader.searcher();
+    // In this case, we don't actually want to limit the docs returned, and the number will be
+    // small in most cases, but I have no idea what collector to use or how, so...
+    let documents = searcher
+        .search(&query, &TopDocs::with_limit(10_000))?
+        .into_iter()
+        .map(|(_, candidate)| searcher.doc(candidate));
+
+    let mut texts = Vec::new();
+    for document in documents {
+        let text = Text::from_document(document?, fields);
+        if range.is_none_or(|range| range.contains(text.verse)) {
+            texts.push(text);
+        }
+    }
+    texts.sort();
+    Ok(texts)
+}
+
+pub(crate) fn search(
+    index: &Index,
+    fields: &SearchFields,
+    query: &str,
+    translation: Translation,
+    scopes: &[Scope],
+    limit: usize,
+) -> Result<Vec<Text>> {
+    let reader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()?;
+    let searcher = reader.searcher();
+
+    let combined_query = build_query(fields, query, translation, scopes)?;
+    let mut texts: Vec<_> = searcher
+        .search(&combined_query, &TopDocs::with_limit(limit))?
+        .into_iter()
+        .filter_map(|(_, address)| searcher.doc(address).ok())
+        .map(|document| Text::from_document(document, fields))
+        .collect();
+
+    texts.sort();
+    Ok(texts)
+}
+
+/// Every hit for a query along with its score, best first.
+pub(crate) fn search_scored(
+    index: &Index,
+    fields: &SearchFields,
+    query: &str,
+    translation: Translation,
+    scopes: &[Scope],
+) -> Result<Vec<(f32, Text)>> {
+    let reader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()?;
+    let searcher = reader.searcher();
+
+    let combined_query = build_query(fields, query, translation, scopes)?;
+    let total = searcher.search(&combined_query, &Count)?;
+    if total == 0 {
+        return Ok(Vec::new());
+    }
+
+    Ok(searcher
+        .search(&combined_query, &TopDocs::with_limit(total))?
+        .into_iter()
+        .filter_map(|(score, address)| {
+            let document = searcher.doc(address).ok()?;
+            Some((score, Text::from_document(document, fields)))
+        })
+        .collect())
+}
+
+/// The query as it's run, the facets it's confined to, and the best hits' scores broken down.
+pub(crate) fn explain(
+    index: &Index,
+    fields: &SearchFields,
+    query: &str,
+    translation: Translation,
+    scopes: &[Scope],
+    limit: usize,
+) -> Result<Explanation> {
+    let reader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()?;
+    let searcher = reader.searcher();
+
+    let combined_query = build_query(fields, query, translation, scopes)?;
+    let schema = index.schema();
+    let mut tree = String::new();
+    describe(&combined_query, &schema, "", 0, &mut tree);
+
+    let mut facets = vec![format!("translation {}", translation.facet())];
+    facets.extend(
+        scopes
+            .iter()
+            .flat_map(|scope| scope.books())
+            .map(|book| format!("location /{} ({book})", book as u8)),
+    );
+
+    let mut hits = Vec::new();
+    for (score, address) in searcher.search(&combined_query, &TopDocs::with_limit(limit))? {
+        let text = Text::from_document(searcher.doc(address)?, fields);
+        let explanation = combined_query.explain(&searcher, address)?;
+        let mut breakdown = String::new();
+        if let Ok(value) = serde_json::to_value(&explanation) {
+            describe_score(&value, 0, &mut breakdown);
+        }
+        hits.push(ExplainedHit {
+            text,
+            score,
+            breakdown,
+        });
+    }
+
+    Ok(Explanation {
+        query: tree,
+        facets,
+        hits,
+    })
+}
+
+/// Write a query out a line per clause, each indented under the one it belongs to and labelled
+/// with how it has to occur.
+fn describe(query: &dyn Query, schema: &Schema, occur: &str, depth: usize, buf: &mut String) {
+    let indent = "  ".repeat(depth);
+    if let Some(query) = query.downcast_ref::<BooleanQuery>() {
+        writeln!(buf, "{indent}{occur:<9}boolean").unwrap();
+        for (occur, clause) in query.clauses() {
+            let occur = match occur {
+                Occur::Must => "must",
+                Occur::Should => "should",
+                Occur::MustNot => "must not",
+            };
+            describe(clause.as_ref(), schema, occur, depth + 1, buf);
+        }
+    } else if let Some(query) = query.downcast_ref::<TermQuery>() {
+        writeln!(
+            buf,
+            "{indent}{occur:<9}{}",
+            describe_term(query.term(), schema)
+        )
+        .unwrap();
+    } else if let Some(query) = query.downcast_ref::<PhraseQuery>() {
+        let words: Vec<_> = query
+            .phrase_terms()
+            .iter()
+            .filter_map(|term| term.value().as_str().map(str::to_string))
+            .collect();
+        let field = schema.get_field_name(query.field());
+        writeln!(buf, "{indent}{occur:<9}{field} \"{}\"", words.join(" ")).unwrap();
+    } else {
+        writeln!(buf, "{indent}{occur:<9}{query:?}").unwrap();
+    }
+}
+
+/// A term as its field and value, naming the book for a location.
+fn describe_term(term: &Term, schema: &Schema) -> String {
+    let field = schema.get_field_name(term.field());
+    if let Some(facet) = term.value().as_facet() {
+        let book = facet
+            .to_path()
+            .first()
+            .and_then(|number| number.parse().ok())
+            .filter(|number| (1..=66).contains(number))
+            .map(Book::from_u8);
+        return match book {
+            Some(book) if field == "location" => format!("{field} {facet} ({book})"),
+            _ => format!("{field} {facet}"),
+        };
+    }
+
+    match term.value().as_str() {
+        Some(text) => format!("{field} \"{text}\""),
+        None => format!("{field} {term:?}"),
+    }
+}
+
+/// Write out tantivy's explanation of a score, each value above the ones it was worked out from.
+fn describe_score(explanation: &serde_json::Value, depth: usize, buf: &mut String) {
+    let indent = "  ".repeat(depth);
+    let value = explanation["value"].as_f64().unwrap_or_default();
+    let description = explanation["description"].as_str().unwrap_or_default();
+    writeln!(buf, "{indent}{value:>8.4}  {description}").unwrap();
+    for context in explanation["context"].as_array().into_iter().flatten() {
+        writeln!(
+            buf,
+            "{indent}          {}",
+            context.as_str().unwrap_or_default()
+        )
+        .unwrap();
+    }
+    for detail in explanation["details"].as_array().into_iter().flatten() {
+        describe_score(detail, depth + 1, buf);
+    }
+}
+
+pub(crate) fn count_by_chapter(
+    index: &Index,
+    fields: &SearchFields,
+    query: &str,
+    translation: Translation,
+    scopes: &[Scope],
+) -> Result<Vec<(Book, u16, u64)>> {
+    let reader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()?;
+    let searcher = reader.searcher();
+
+    // Each book is a top-level facet of the location field. Counting at the root ought to give us
+    // books directly, but tantivy loses hits that way (Genesis came back with one hit for "love"
+    // rather than three), so we count chapters within each book.
+    let combined_query = build_query(fields, query, translation, scopes)?;
+    let mut collector = FacetCollector::for_field("location");
+    let books: Vec<_> = (1..=66).map(Book::from_u8).collect();
+    for &book in &books {
+        collector.add_facet(Facet::from(&format!("/{}", book as u8)));
+    }
+    let counts = searcher.search(&combined_query, &collector)?;
+
+    let mut chapters: Vec<_> = books
+        .into_iter()
+        .flat_map(|book| {
+            counts
+                .get(&format!("/{}", book as u8))
+                .filter_map(move |(facet, count)| {
+                    let chapter = facet.to_path().last()?.parse().ok()?;
+                    Some((book, chapter, count))
+                })
+        })
+        .filter(|&(_, _, count)| count > 0)
+        .collect();
+
+    chapters.sort();
+    Ok(chapters)
+}
+
+/// Every verse where a word or phrase occurs, in canonical order, along with the byte range of
+/// each occurrence. The index finds the verses; the occurrences within each verse are found by
+/// tokenizing it again, the same way it was indexed.
+pub(crate) fn find_term(
+    index: &Index,
+    fields: &SearchFields,
+    phrase: &str,
+    translation: Translation,
+    scopes: &[Scope],
+) -> Result<Vec<(Text, Vec<Range<usize>>)>> {
+    let mut tokenizer = index.tokenizer_for_field(fields.content)?;
+    let terms = terms(&mut tokenizer, phrase);
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let query = term_query(fields, &terms, translation, scopes);
+
+    let reader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()?;
+    let searcher = reader.searcher();
+
+    let mut found = Vec::new();
+    for address in searcher.search(&query, &DocSetCollector)? {
+        let text = Text::from_document(searcher.doc(address)?, fields);
+        let ranges = occurrences(&mut tokenizer, &terms, &text.content);
+        if !ranges.is_empty() {
+            found.push((text, ranges));
+        }
+    }
+
+    found.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(found)
+}
+
+/// How many verses contain each of some words or phrases, within the given books or
+/// testaments.
+pub(crate) fn verse_counts(
+    index: &Index,
+    fields: &SearchFields,
+    phrases: &[&str],
+    translation: Translation,
+    scopes: &[Scope],
+) -> Result<Vec<usize>> {
+    let mut tokenizer = index.tokenizer_for_field(fields.content)?;
+    let reader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()?;
+    let searcher = reader.searcher();
+
+    let mut counts = Vec::with_capacity(phrases.len());
+    for phrase in phrases {
+        let terms = terms(&mut tokenizer, phrase);
+        let count = if terms.is_empty() {
+            0
+        } else {
+            searcher.search(&term_query(fields, &terms, translation, scopes), &Count)?
+        };
+        counts.push(count);
+    }
+
+    Ok(counts)
+}
+
+/// How many verses there are in the given books or testaments.
+pub(crate) fn verse_total(
+    index: &Index,
+    fields: &SearchFields,
+    translation: Translation,
+    scopes: &[Scope],
+) -> Result<usize> {
+    let reader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()?;
+    let searcher = reader.searcher();
+
+    let translation = TermQuery::new(
+        Term::from_facet(fields.translation, &translation.facet()),
+        IndexRecordOption::Basic,
+    );
+    let mut clauses = vec![Box::new(translation) as Box<dyn Query>];
+    clauses.extend(scope_query(fields, scopes));
+    Ok(searcher.search(&BooleanQuery::intersection(clauses), &Count)?)
+}
+
+/// Verses of a translation containing a run of already-tokenized terms.
+fn term_query(
+    fields: &SearchFields,
+    terms: &[String],
+    translation: Translation,
+    scopes: &[Scope],
+) -> BooleanQuery {
+    let content: Box<dyn Query> = match terms {
+        [term] => Box::new(TermQuery::new(
+            Term::from_field_text(fields.content, term),
+            IndexRecordOption::Basic,
+        )),
+        terms => Box::new(PhraseQuery::new(
+            terms
+                .iter()
+                .map(|term| Term::from_field_text(fields.content, term))
+                .collect(),
+        )),
+    };
+    let translation = TermQuery::new(
+        Term::from_facet(fields.translation, &translation.facet()),
+        IndexRecordOption::Basic,
+    );
+    let mut clauses = vec![content, Box::new(translation) as Box<dyn Query>];
+    clauses.extend(scope_query(fields, scopes));
+    BooleanQuery::intersection(clauses)
+}
+
+/// The tokenizer the content is indexed with, which is tantivy's default: words split at anything
+/// but letters and digits, and lowercased.
+pub(crate) fn tokenizer() -> TextAnalyzer {
+    TokenizerManager::default().get("default").unwrap()
+}
+
+/// The tokens of a word or phrase to look for, as they'd be found in the index.
+pub(crate) fn terms(tokenizer: &mut TextAnalyzer, phrase: &str) -> Vec<String> {
+    tokens(tokenizer, phrase)
+        .into_iter()
+        .map(|(term, _)| term)
+        .collect()
+}
+
+/// The byte range of each run of `terms` in some content.
+pub(crate) fn occurrences(
+    tokenizer: &mut TextAnalyzer,
+    terms: &[String],
+    content: &str,
+) -> Vec<Range<usize>> {
+    tokens(tokenizer, content)
+        .windows(terms.len())
+        .filter(|window| window.iter().map(|(word, _)| word).eq(terms))
+        .map(|window| window[0].1.start..window[window.len() - 1].1.end)
+        .collect()
+}
+
+/// Each token in some text, with its byte range.
+fn tokens(tokenizer: &mut TextAnalyzer, text: &str) -> Vec<(String, Range<usize>)> {
+    let mut stream = tokenizer.token_stream(text);
+    let mut tokens = Vec::new();
+    while stream.advance() {
+        let token = stream.token();
+        tokens.push((token.text.clone(), token.offset_from..token.offset_to));
+    }
+    tokens
+}
+
+fn build_query(
+    fields: &SearchFields,
+    query: &str,
+    translation: Translation,
+    scopes: &[Scope],
+) -> Result<BooleanQuery> {
+    // This query parser constructs a query from the user's search string. We can break the search
+    // string into multiple strings at some point to make the cli less annoying, maybe? But for now
+    // the user provides a monolithic string.
+
+    let query = parse_query(query)?;
+
+    // That gives us one search term. We need to make a second term for the facet referencing the
+    // correct translation.
+
+    let translation_term = Term::from_facet(fields.translation, &translation.facet());
+    let term_query = TermQuery::new(translation_term, IndexRecordOption::Basic);
+
+    // Damned if I know the correct way to do this, but this seems to work, so....
+
+    let mut clauses = vec![query, Box::new(term_query) as Box<dyn Query>];
+    clauses.extend(scope_query(fields, scopes));
+    Ok(BooleanQuery::intersection(clauses))
+}
+
+/// A facet term matches everything beneath it, so /45 finds every verse in Romans. A search in
+/// several books wants any one of them. No scopes means no restriction at all.
+fn scope_query(fields: &SearchFields, scopes: &[Scope]) -> Option<Box<dyn Query>> {
+    if scopes.is_empty() {
+        return None;
+    }
+
+    let books: Vec<Box<dyn Query>> = scopes
+        .iter()
+        .flat_map(|scope| scope.books())
+        .map(|book| {
+            let facet = Facet::from(&format!("/{}", book as u8));
+            Box::new(TermQuery::new(
+                Term::from_facet(fields.location, &facet),
+                IndexRecordOption::Basic,
+            )) as Box<dyn Query>
+        })
+        .collect();
+    Some(Box::new(BooleanQuery::union(books)))
+}
+
+/// Rank some pieces of text, such as your notes, against a search query in the same syntax as
+/// [`Bible::search`](crate::Bible::search). Each match is its position in `texts`, with its score,
+/// best first.
+///
+/// They get an index of their own, in memory: a few hundred notes take no time to index, and
+/// there's nothing on disk to go stale when they change.
+pub fn search_texts(texts: &[&str], query: &str, limit: usize) -> Result<Vec<(f32, usize)>> {
+    use tantivy::{doc, schema, schema::Value, TantivyDocument};
+
+    let mut builder = Schema::builder();
+    let content = builder.add_text_field("content", schema::TEXT);
+    let position = builder.add_u64_field("position", schema::STORED);
+    let schema = builder.build();
+    let query = parse_query_in(query, schema.clone(), content)?;
+    if texts.is_empty() || limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    let index = Index::create_in_ram(schema);
+    let mut writer: IndexWriter = index.writer_with_num_threads(1, 15_000_000)?;
+    for (idx, &text) in texts.iter().enumerate() {
+        writer.add_document(doc!(content => text, position => idx as u64))?;
+    }
+    writer.commit()?;
+
+    let searcher = index.reader()?.searcher();
+    let mut hits = Vec::new();
+    for (score, address) in searcher.search(&query, &TopDocs::with_limit(limit))? {
+        let document: TantivyDocument = searcher.doc(address)?;
+        if let Some(idx) = document
+            .get_first(position)
+            .and_then(|value| value.as_u64())
+        {
+            hits.push((score, idx as usize));
+        }
+    }
+    Ok(hits)
+}
+
+/// Byte ranges of the words in `content` which match the terms of a search query.
+///
+/// The content is tokenized the same way it was for indexing, so "LOVED" in a query still finds
+/// "loved" in the text (and the stemmed forms, if we ever turn stemming on).
+pub(crate) fn highlights(query: &str, content: &str) -> Result<Vec<Range<usize>>> {
+    let query = parse_query(query)?;
+    let mut terms = HashSet::new();
+    let fields = SearchFields::from_schema(&build_schema());
+    collect_terms(query.as_ref(), fields.content, &mut terms);
+
+    let mut tokenizer = tokenizer();
+    let mut stream = tokenizer.token_stream(content);
+    let mut ranges = Vec::new();
+    while stream.advance() {
+        let token = stream.token();
+        if terms.contains(&token.text) {
+            ranges.push(token.offset_from..token.offset_to);
+        }
+    }
+
+    Ok(ranges)
+}
+
+/// Excluded terms (-word) don't appear in any result, so there's nothing to highlight for them;
+/// everything else gets collected.
+fn collect_terms(query: &dyn Query, field: Field, terms: &mut HashSet<String>) {
+    if let Some(query) = query.downcast_ref::<BooleanQuery>() {
+        for (occur, query) in query.clauses() {
+            if *occur != Occur::MustNot {
+                collect_terms(query.as_ref(), field, terms);
+            }
+        }
+        return;
+    }
+
+    query.query_terms(&mut |term, _| {
+        if term.field() == field {
+            if let Some(text) = term.value().as_str() {
+                terms.insert(text.to_string());
+            }
+        }
+    });
+}
+
+/// Parse a search string with tantivy's query parser, which gives us the syntax for free:
+///
+/// - words are optional, but verses matching more of them rank higher
+/// - `+word` must appear and `-word` must not
+/// - `"a phrase"` must appear exactly, word for word
+/// - `a OR b` matches either; `a AND b` matches both
+///
+/// The parser's own errors don't say what's wrong, so the usual mistakes are caught here first.
+pub(crate) fn parse_query(query: &str) -> Result<Box<dyn Query>> {
+    let schema = build_schema();
+    let fields = SearchFields::from_schema(&schema);
+    parse_query_in(query, schema, fields.content)
+}
+
+/// [`parse_query`], for the words in some other schema's field.
+fn parse_query_in(query: &str, schema: Schema, field: Field) -> Result<Box<dyn Query>> {
+    check_syntax(query).map_err(|message| Error::bad_query(query, message))?;
+
+    let query_parser = QueryParser::new(schema, vec![field], TokenizerManager::default());
+    query_parser.parse_query(query).map_err(|e| {
+        let message = match e {
+            QueryParserError::AllButQueryForbidden => {
+                "at least one word has to be something other than -excluded"
+            }
+            QueryParserError::SyntaxError(_) => {
+                "the query isn't valid search syntax; see `flux search --help`"
+            }
+            QueryParserError::UnsupportedQuery(_) => "that kind of query isn't supported",
+            e => return Error::from(e),
+        };
+        Error::bad_query(query, message)
+    })
+}
+
+fn check_syntax(query: &str) -> std::result::Result<(), String> {
+    if !query.matches('"').count().is_multiple_of(2) {
+        return Err(String::from("a quoted phrase is missing its closing quote"));
+    }
+
+    // Everything outside of quotes, since a phrase can contain whatever it likes.
+    let bare: String = query
+        .split('"')
+        .step_by(2)
+        .collect::<Vec<_>>()
+        .join(" \"\" ");
+
+    let mut depth = 0usize;
+    for c in bare.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Err(String::from("')' without a matching '('")),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    if depth > 0 {
+        return Err(String::from("'(' without a matching ')'"));
+    }
+
+    let words: Vec<_> = bare
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    for word in &words {
+        if word.contains(':') {
+            return Err(format!(
+                "':' is reserved; put {word} in quotes to search for it"
+            ));
+        }
+        if *word == "+" || *word == "-" {
+            return Err(format!(
+                "'{word}' goes directly before a word, as in {word}faith"
+            ));
+        }
+    }
+
+    let is_operator = |word: &&str| *word == "OR" || *word == "AND";
+    if words.first().is_some_and(is_operator)
+        || words.last().is_some_and(is_operator)
+        || words.windows(2).any(|pair| pair.iter().all(is_operator))
+    {
+        return Err(String::from("OR and AND need a word on each side"));
+    }
+
+    Ok(())
+}
+
+/// Open the index in `data_dir`, building it first if need be. A lean index doesn't keep its own
+/// copy of the embedded translations' text, which takes about half off its size; verses are read
+/// from the text built into the binary instead.
+pub(crate) fn initialize_search(
+    data_dir: &Path,
+    options: &IndexOptions,
+) -> tantivy::Result<(Index, SearchFields)> {
+    // Well need to ensure the directory exists. That's easy, but I'm not sure how to know if
+    // there is an existing index in an existing directory. That seems important.
+
+    // An index built to another schema or from other text is no good to us, and neither is one
+    // whose build was cut short, which never got its stamp.
+    let index_path = index_path(data_dir);
+    let stamp = Stamp::current().with_lean(options.lean);
+    if index_path.exists() && Stamp::read(data_dir) != Some(stamp) {
+        std::fs::remove_dir_all(&index_path)?;
+    }
+    if !index_path.exists() {
+        std::fs::create_dir_all(&index_path)?;
+    }
+
+    let schema = build_schema();
+    let fields = SearchFields::from_schema(&schema);
+
+    let index_dir = MmapDirectory::open(&index_path)?;
+    if !tantivy::Index::exists(&index_dir)? {
+        let index = Index::create_in_dir(index_path, schema)?;
+        fill_index(&index, &fields, Some(data_dir), options)?;
+        stamp.write(data_dir)?;
+        Ok((index, fields))
+    } else {
+        Ok((tantivy::Index::open(index_dir)?, fields))
+    }
+}
+
+/// Build the whole index in memory, for when there's nowhere to keep it (or we were asked not to),
+/// with the user's translations from `data_dir` if there is one.
+pub(crate) fn initialize_search_in_ram(
+    data_dir: Option<&Path>,
+    options: &IndexOptions,
+) -> tantivy::Result<(Index, SearchFields)> {
+    let schema = build_schema();
+    let fields = SearchFields::from_schema(&schema);
+    let index = Index::create_in_ram(schema);
+    fill_index(&index, &fields, data_dir, options)?;
+    Ok((index, fields))
+}
+
+fn fill_index(
+    index: &Index,
+    fields: &SearchFields,
+    data_dir: Option<&Path>,
+    options: &IndexOptions,
+) -> tantivy::Result<()> {
+    /// 500 megabytes
+    const ARENA_SIZE: usize = 0x100000 * 500;
+    let mut writer = options.writer(index, ARENA_SIZE)?;
+    for translation in Translation::ALL {
+        if let Some(text) = translation.text() {
+            write_index(translation, text, !options.lean, fields, &mut writer)?;
+        }
+    }
+
+    // If the index was deleted out from under us, user translations need to come back too.
+    if let Some(data_dir) = data_dir {
+        for translation in user::registered(data_dir)? {
+            let text = std::fs::read_to_string(user::path(data_dir, translation))?;
+            write_index(translation, &text, true, fields, &mut writer)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Index a translation's verses, keeping each one's text too if `store`. Only the embedded
+/// translations can do without, since there's nowhere else to read a user translation's verses from
+/// quickly.
+pub(crate) fn write_index(
+    translation: Translation,
+    text: &str,
+    store: bool,
+    fields: &SearchFields,
+    writer: &mut IndexWriter,
+) -> tantivy::Result<()> {
+    use tantivy::doc;
+
+    for (id, text) in parse_verses_with_id(text) {
+        let Location {
+            book,
+            chapter,
+            verse,
+        } = Location::from_id(id);
+
+        let book = book as u8;
+        let location = Facet::from(&format!("/{book}/{chapter}/{verse}"));
+
+        let mut document = doc!(
+            fields.translation => translation.facet(),
+            fields.location => location,
+            fields.content => text,
+        );
+        if store {
+            document.add_text(fields.text, text);
+        }
+        writer.add_document(document)?;
+    }
+
+    writer.commit()?;
+    Ok(())
+}
+
+pub(crate) fn build_schema() -> Schema {
+    use tantivy::schema;
+
+    let facet_options = schema::INDEXED | schema::STORED;
+
+    let mut builder = Schema::builder();
+    builder.add_facet_field("translation", facet_options.clone());
+    builder.add_facet_field("location", facet_options);
+    builder.add_text_field("content", schema::TEXT);
+    builder.add_text_field("text", schema::STORED);
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_syntax, search_texts};
+
+    #[test]
+    fn syntax() {
+        assert!(check_syntax(r#"+faith -works "by grace""#).is_ok());
+        assert!(check_syntax(r#"(faith OR hope) AND "and now: abideth""#).is_ok());
+        assert!(check_syntax(r#""in the beginning"#).is_err());
+        assert!(check_syntax("(faith").is_err());
+        assert!(check_syntax("love OR").is_err());
+        assert!(check_syntax("Behold: the").is_err());
+        assert!(check_syntax("faith - works").is_err());
+    }
+
+    #[test]
+    fn texts() {
+        let notes = [
+            "outline for the sermon on humility",
+            "pride goes before a fall",
+            "humility, again",
+        ];
+        let hits: Vec<_> = search_texts(&notes, "+humility sermon", 10)
+            .unwrap()
+            .into_iter()
+            .map(|(_, idx)| idx)
+            .collect();
+        assert_eq!(vec![0, 2], hits);
+        assert!(search_texts(&[], "humility", 10).unwrap().is_empty());
+    }
+}
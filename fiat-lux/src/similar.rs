@@ -0,0 +1,131 @@
+//! Chapters which read alike, by TF-IDF cosine similarity.
+//!
+//! Each chapter is treated as a bag of words. A word counts for more the more often it turns up
+//! in a chapter and the fewer chapters it turns up in at all, so two chapters score highly when
+//! they share unusual words--the names and places of a parallel account, say--rather than just
+//! "said" and "king".
+
+use std::collections::HashMap;
+
+use crate::{
+    book::Book,
+    stats::{words_in, STOPWORDS},
+    text::Text,
+};
+
+/// Every other chapter among `texts`, most similar to the given one first, with its cosine
+/// similarity (between 0 and 1). Chapters with nothing in common are left out.
+pub(crate) fn similar_chapters(texts: &[Text], book: Book, chapter: u16) -> Vec<(Book, u16, f64)> {
+    let chapters: Vec<_> = texts
+        .chunk_by(|a, b| a.book == b.book && a.chapter == b.chapter)
+        .map(|verses| {
+            let mut counts: HashMap<String, usize> = HashMap::new();
+            for word in verses.iter().flat_map(|text| words_in(&text.content)) {
+                if !STOPWORDS.contains(&word.as_str()) {
+                    *counts.entry(word).or_default() += 1;
+                }
+            }
+            (verses[0].book, verses[0].chapter, counts)
+        })
+        .collect();
+
+    let mut frequency: HashMap<&str, usize> = HashMap::new();
+    for (_, _, counts) in &chapters {
+        for word in counts.keys() {
+            *frequency.entry(word).or_default() += 1;
+        }
+    }
+
+    let total = chapters.len() as f64;
+    let weights = |counts: &HashMap<String, usize>| -> HashMap<String, f64> {
+        counts
+            .iter()
+            .map(|(word, &count)| {
+                let idf = (total / frequency[word.as_str()] as f64).ln();
+                (word.clone(), (1.0 + (count as f64).ln()) * idf)
+            })
+            .collect()
+    };
+    let norm = |weights: &HashMap<String, f64>| weights.values().map(|w| w * w).sum::<f64>().sqrt();
+
+    let Some((_, _, target)) = chapters
+        .iter()
+        .find(|&&(b, c, _)| b == book && c == chapter)
+    else {
+        return Vec::new();
+    };
+    let target = weights(target);
+    let target_norm = norm(&target);
+    if target_norm == 0.0 {
+        return Vec::new();
+    }
+
+    let mut similar: Vec<_> = chapters
+        .iter()
+        .filter(|&&(b, c, _)| (b, c) != (book, chapter))
+        .filter_map(|(b, c, counts)| {
+            let other = weights(counts);
+            let dot: f64 = target
+                .iter()
+                .filter_map(|(word, w)| other.get(word).map(|v| w * v))
+                .sum();
+            let score = dot / (target_norm * norm(&other));
+            (score > 0.0).then_some((*b, *c, score))
+        })
+        .collect();
+
+    similar.sort_by(|a, b| b.2.total_cmp(&a.2));
+    similar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::similar_chapters;
+    use crate::{book::Book, text::Text};
+
+    #[test]
+    fn parallel_accounts() {
+        let text = |book, chapter, content: &str| Text {
+            book,
+            chapter,
+            verse: 1,
+            content: content.to_string(),
+        };
+
+        let texts = [
+            text(
+                Book::Kings2,
+                18,
+                "Hezekiah the son of Ahaz began to reign over Judah",
+            ),
+            text(
+                Book::Kings2,
+                19,
+                "Sennacherib sent messengers unto Hezekiah",
+            ),
+            text(
+                Book::Chronicles2,
+                29,
+                "Hezekiah began to reign when he was five and twenty",
+            ),
+            text(
+                Book::Chronicles2,
+                32,
+                "Sennacherib king of Assyria came and entered into Judah",
+            ),
+            text(
+                Book::Isaiah,
+                37,
+                "Sennacherib king of Assyria sent messengers to Hezekiah",
+            ),
+        ];
+
+        let similar = similar_chapters(&texts, Book::Kings2, 19);
+        let (book, chapter, _) = similar[0];
+        assert_eq!((Book::Isaiah, 37), (book, chapter));
+        assert!(similar
+            .iter()
+            .all(|&(_, _, score)| score > 0.0 && score <= 1.0));
+        assert!(similar_chapters(&texts, Book::Kings2, 20).is_empty());
+    }
+}
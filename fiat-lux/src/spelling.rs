@@ -0,0 +1,153 @@
+//! Modern spelling for the KJV and its like, put on the text as it's shown: "sheweth" becomes
+//! "shows" and "spake" "spoke". "Thee" and "thou", with the verb forms that go with them, are left
+//! alone unless they're asked for too, since they're how the text tells one "you" from several.
+
+use std::{collections::HashMap, sync::OnceLock};
+
+/// Archaic words and their modern forms, one per line as tab-separated fields: the archaic word,
+/// the modern one and, for "thou" and the verbs which go with it, "thou". The `-eth` and `-est`
+/// verbs cover every one in the embedded translations.
+static SPELLING_DAT: &str = include_str!("../resource/spelling.dat");
+
+/// The pronouns themselves, which change wherever they are. Verbs like "hast" only change next
+/// to a "thou", since "art" is a noun as often as not.
+const PRONOUNS: [&str; 6] = ["thou", "thee", "thy", "thine", "ye", "thyself"];
+
+/// How far from a "thou" its verb can be: "thou art", "art thou", "thou also hast".
+const THOU_DISTANCE: usize = 3;
+
+struct Modern {
+    word: &'static str,
+    thou: bool,
+}
+
+fn table() -> &'static HashMap<&'static str, Modern> {
+    static TABLE: OnceLock<HashMap<&'static str, Modern>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        SPELLING_DAT
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                let archaic = fields.next()?;
+                let word = fields.next()?;
+                let thou = fields.next() == Some("thou");
+                Some((archaic, Modern { word, thou }))
+            })
+            .collect()
+    })
+}
+
+/// `text` with archaic spellings and verb forms brought up to date. With `pronouns`, "thou",
+/// "thee", "thy" and "ye" become "you" and "your" as well, and verbs like "hast" and "lovest" go
+/// along with them.
+pub fn modernize(text: &str, pronouns: bool) -> String {
+    let table = table();
+    let words: Vec<_> = text.split(' ').collect();
+    let cores: Vec<_> = words
+        .iter()
+        .map(|word| core(word).1.to_lowercase())
+        .collect();
+    let mut buf = String::with_capacity(text.len());
+
+    for (idx, word) in words.iter().enumerate() {
+        if idx > 0 {
+            buf.push(' ');
+        }
+
+        let (before, core_word, after) = core(word);
+        let Some(modern) = table.get(cores[idx].as_str()) else {
+            buf.push_str(word);
+            continue;
+        };
+
+        let pronoun = PRONOUNS.contains(&cores[idx].as_str());
+        let near_thou = || {
+            let start = idx.saturating_sub(THOU_DISTANCE);
+            let end = (idx + THOU_DISTANCE + 1).min(cores.len());
+            cores[start..end].iter().any(|core| core == "thou")
+        };
+        if modern.thou && !(pronouns && (pronoun || near_thou())) {
+            buf.push_str(word);
+            continue;
+        }
+
+        // "Thine" before a noun is "your", but on its own (or before a verb) it's "yours".
+        let replacement = if cores[idx] == "thine"
+            && (!after.is_empty()
+                || cores.get(idx + 1).is_none_or(|next| {
+                    matches!(next.as_str(), "" | "is" | "are" | "was" | "were" | "be")
+                })) {
+            "yours"
+        } else {
+            modern.word
+        };
+
+        buf.push_str(before);
+        buf.push_str(&match_case(core_word, replacement));
+        buf.push_str(after);
+    }
+
+    buf
+}
+
+/// A word split into any punctuation before it, the word itself, and any punctuation after.
+fn core(word: &str) -> (&str, &str, &str) {
+    let start = word.find(char::is_alphabetic).unwrap_or(word.len());
+    let end = word.rfind(char::is_alphabetic).map_or(start, |idx| {
+        idx + word[idx..].chars().next().unwrap().len_utf8()
+    });
+    (&word[..start], &word[start..end], &word[end..])
+}
+
+/// `replacement`, capitalized like `original`.
+fn match_case(original: &str, replacement: &str) -> String {
+    let mut chars = original.chars();
+    match chars.next() {
+        Some(first)
+            if first.is_uppercase()
+                && chars.clone().count() > 0
+                && chars.all(char::is_uppercase) =>
+        {
+            replacement.to_uppercase()
+        }
+        Some(first) if first.is_uppercase() => {
+            let mut replacement = replacement.chars();
+            replacement
+                .next()
+                .map(|first| first.to_uppercase().chain(replacement).collect())
+                .unwrap_or_default()
+        }
+        _ => replacement.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::modernize;
+
+    #[test]
+    fn every_line_parses() {
+        let lines = super::SPELLING_DAT.lines().count();
+        assert_eq!(lines, super::table().len());
+    }
+
+    #[test]
+    fn modernizing() {
+        let text = "Thou art my son; this day have I begotten thee. He that cometh unto me, \
+                    he sheweth it, saith the LORD. Thine is the kingdom, and thine eyes, and \
+                    the art of the apothecary: Lovest thou me?";
+        assert_eq!(
+            "Thou art my son; this day have I begotten thee. He that comes unto me, he shows \
+             it, says the LORD. Thine is the kingdom, and thine eyes, and the art of the \
+             apothecary: Lovest thou me?",
+            modernize(text, false)
+        );
+        assert_eq!(
+            "You are my son; this day have I begotten you. He that comes unto me, he shows \
+             it, says the LORD. Yours is the kingdom, and your eyes, and the art of the \
+             apothecary: Love you me?",
+            modernize(text, true)
+        );
+        assert_eq!("Seth and Nazareth", modernize("Seth and Nazareth", true));
+    }
+}
@@ -0,0 +1,500 @@
+//! Verses and their full-text index kept in a single SQLite file, as an alternative to a tantivy
+//! directory: easier to back up and to ship, at the cost of a little speed.
+//!
+//! Queries are parsed with tantivy's parser either way, so the syntax is the same, and then
+//! written out again as an FTS5 query.
+
+use std::{ops::Range, path::Path};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use tantivy::query::{BooleanQuery, Occur, PhraseQuery, Query, TermQuery};
+
+use crate::{
+    book::Book,
+    index::Stamp,
+    location::{Location, PartialLocation},
+    offsets::id_range,
+    parse_verses_with_id,
+    scope::Scope,
+    search,
+    text::Text,
+    translation::Translation,
+    user, Error, Result,
+};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+    CREATE TABLE IF NOT EXISTS verses (
+        rowid INTEGER PRIMARY KEY,
+        translation TEXT NOT NULL,
+        id INTEGER NOT NULL,
+        content TEXT NOT NULL
+    );
+    CREATE UNIQUE INDEX IF NOT EXISTS verses_location ON verses (translation, id);
+    CREATE VIRTUAL TABLE IF NOT EXISTS verses_fts USING fts5 (
+        content, content = 'verses', content_rowid = 'rowid'
+    );
+";
+
+pub(crate) struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Open (or create) `bible.sqlite` in `data_dir`, filling it again if it was built from other
+    /// text or to another schema.
+    pub(crate) fn open(data_dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(data_dir)?;
+        let store = Self {
+            conn: Connection::open(data_dir.join("bible.sqlite"))?,
+        };
+        store.conn.execute_batch(SCHEMA)?;
+        if store.stamp()? != Some(Stamp::current()) {
+            store.fill(Some(data_dir))?;
+        }
+        Ok(store)
+    }
+
+    /// Build the store in memory, along with the translations the user has added to `data_dir`.
+    pub(crate) fn open_in_memory(data_dir: Option<&Path>) -> Result<Self> {
+        let store = Self {
+            conn: Connection::open_in_memory()?,
+        };
+        store.conn.execute_batch(SCHEMA)?;
+        store.fill(data_dir)?;
+        Ok(store)
+    }
+
+    fn stamp(&self) -> Result<Option<Stamp>> {
+        let stamp: Option<String> = self
+            .conn
+            .query_row("SELECT value FROM meta WHERE key = 'stamp'", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        Ok(stamp.as_deref().and_then(Stamp::decode))
+    }
+
+    fn fill(&self, data_dir: Option<&Path>) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute_batch(
+            "DELETE FROM verses; INSERT INTO verses_fts (verses_fts) VALUES ('delete-all');
+             DELETE FROM meta WHERE key LIKE 'unindexed:%';",
+        )?;
+        for translation in Translation::ALL {
+            if let Some(text) = translation.text() {
+                insert(&tx, translation, text)?;
+            }
+        }
+
+        if let Some(data_dir) = data_dir {
+            for translation in user::registered(data_dir)? {
+                let text = std::fs::read_to_string(user::path(data_dir, translation))?;
+                insert(&tx, translation, &text)?;
+            }
+        }
+
+        tx.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('stamp', ?1)",
+            [Stamp::current().encode()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub(crate) fn lookup(
+        &self,
+        book: Book,
+        location: Option<PartialLocation>,
+        translation: Translation,
+    ) -> Result<Vec<Text>> {
+        let (first, last) = id_range(book, location);
+        let mut statement = self.conn.prepare_cached(
+            "SELECT id, content FROM verses
+             WHERE translation = ?1 AND id BETWEEN ?2 AND ?3 ORDER BY id",
+        )?;
+        let texts = statement
+            .query_map(params![translation.to_string(), first, last], |row| {
+                Ok(text(row.get(0)?, row.get(1)?))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(texts)
+    }
+
+    /// Hits for a query with their scores, best first, up to `limit` of them if given.
+    pub(crate) fn search(
+        &self,
+        query: &str,
+        translation: Translation,
+        scopes: &[Scope],
+        limit: Option<usize>,
+    ) -> Result<Vec<(f32, Text)>> {
+        let fts = fts_query(query)?;
+        // bm25() is lower for a better match, where tantivy's scores are higher.
+        let sql = format!(
+            "SELECT v.id, v.content, -bm25(verses_fts) FROM verses_fts
+             JOIN verses v ON v.rowid = verses_fts.rowid
+             WHERE verses_fts MATCH ?1 AND v.translation = ?2{}
+             ORDER BY bm25(verses_fts) LIMIT ?3",
+            scope_clause(scopes)
+        );
+        let limit = limit.map_or(-1, |limit| limit as i64);
+        let mut statement = self.conn.prepare(&sql)?;
+        let hits = statement
+            .query_map(params![fts, translation.to_string(), limit], |row| {
+                Ok((row.get::<_, f64>(2)? as f32, text(row.get(0)?, row.get(1)?)))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(hits)
+    }
+
+    pub(crate) fn count_by_chapter(
+        &self,
+        query: &str,
+        translation: Translation,
+        scopes: &[Scope],
+    ) -> Result<Vec<(Book, u16, u64)>> {
+        let sql = format!(
+            "SELECT v.id / 1000, COUNT(*) FROM verses_fts
+             JOIN verses v ON v.rowid = verses_fts.rowid
+             WHERE verses_fts MATCH ?1 AND v.translation = ?2{}
+             GROUP BY v.id / 1000 ORDER BY v.id / 1000",
+            scope_clause(scopes)
+        );
+        let mut statement = self.conn.prepare(&sql)?;
+        let counts = statement
+            .query_map(params![fts_query(query)?, translation.to_string()], |row| {
+                let chapter: u32 = row.get(0)?;
+                let book = Book::from_u8((chapter / 1000) as u8);
+                Ok((book, (chapter % 1000) as u16, row.get::<_, i64>(1)? as u64))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(counts)
+    }
+
+    /// Every verse where a word or phrase occurs, in canonical order, with the byte range of each
+    /// occurrence; see [`search::find_term`].
+    pub(crate) fn find_term(
+        &self,
+        phrase: &str,
+        translation: Translation,
+        scopes: &[Scope],
+    ) -> Result<Vec<(Text, Vec<Range<usize>>)>> {
+        let mut tokenizer = search::tokenizer();
+        let terms = search::terms(&mut tokenizer, phrase);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!(
+            "SELECT v.id, v.content FROM verses_fts
+             JOIN verses v ON v.rowid = verses_fts.rowid
+             WHERE verses_fts MATCH ?1 AND v.translation = ?2{}
+             ORDER BY v.id",
+            scope_clause(scopes)
+        );
+        let mut statement = self.conn.prepare(&sql)?;
+        let texts = statement
+            .query_map(
+                params![phrase_query(&terms), translation.to_string()],
+                |row| Ok(text(row.get(0)?, row.get(1)?)),
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(texts
+            .into_iter()
+            .filter_map(|text| {
+                let ranges = search::occurrences(&mut tokenizer, &terms, &text.content);
+                (!ranges.is_empty()).then_some((text, ranges))
+            })
+            .collect())
+    }
+
+    /// How many verses contain each of some words or phrases.
+    pub(crate) fn verse_counts(
+        &self,
+        phrases: &[&str],
+        translation: Translation,
+        scopes: &[Scope],
+    ) -> Result<Vec<usize>> {
+        let mut tokenizer = search::tokenizer();
+        let sql = format!(
+            "SELECT COUNT(*) FROM verses_fts
+             JOIN verses v ON v.rowid = verses_fts.rowid
+             WHERE verses_fts MATCH ?1 AND v.translation = ?2{}",
+            scope_clause(scopes)
+        );
+        let mut statement = self.conn.prepare(&sql)?;
+
+        let mut counts = Vec::with_capacity(phrases.len());
+        for phrase in phrases {
+            let terms = search::terms(&mut tokenizer, phrase);
+            let count = if terms.is_empty() {
+                0
+            } else {
+                statement.query_row(
+                    params![phrase_query(&terms), translation.to_string()],
+                    |row| row.get::<_, i64>(0),
+                )? as usize
+            };
+            counts.push(count);
+        }
+        Ok(counts)
+    }
+
+    pub(crate) fn verse_total(&self, translation: Translation, scopes: &[Scope]) -> Result<usize> {
+        let sql = format!(
+            "SELECT COUNT(*) FROM verses v WHERE v.translation = ?1{}",
+            scope_clause(scopes)
+        );
+        let total: i64 = self
+            .conn
+            .query_row(&sql, [translation.to_string()], |row| row.get(0))?;
+        Ok(total as usize)
+    }
+
+    /// Replace whatever's stored for a translation with the verses of a dat file.
+    pub(crate) fn add_translation(&self, translation: Translation, text: &str) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        delete(&tx, translation)?;
+        insert(&tx, translation, text)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub(crate) fn remove_translation(&self, translation: Translation) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        delete(&tx, translation)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Take a translation's verses out of the full-text index, keeping them for lookups.
+    pub(crate) fn unindex_translation(&self, translation: Translation) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        if !is_unindexed(&tx, translation)? {
+            unindex(&tx, translation)?;
+            tx.execute(
+                "INSERT INTO meta (key, value) VALUES (?1, '')",
+                [unindexed_key(translation)],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+fn insert(conn: &Connection, translation: Translation, text: &str) -> Result<()> {
+    let name = translation.to_string();
+    let mut verse =
+        conn.prepare_cached("INSERT INTO verses (translation, id, content) VALUES (?1, ?2, ?3)")?;
+    let mut fts = conn.prepare_cached("INSERT INTO verses_fts (rowid, content) VALUES (?1, ?2)")?;
+    for (id, content) in parse_verses_with_id(text) {
+        verse.execute(params![name, id as i64, content])?;
+        fts.execute(params![conn.last_insert_rowid(), content])?;
+    }
+    Ok(())
+}
+
+fn delete(conn: &Connection, translation: Translation) -> Result<()> {
+    if !is_unindexed(conn, translation)? {
+        unindex(conn, translation)?;
+    }
+    conn.execute(
+        "DELETE FROM verses WHERE translation = ?1",
+        [translation.to_string()],
+    )?;
+    conn.execute(
+        "DELETE FROM meta WHERE key = ?1",
+        [unindexed_key(translation)],
+    )?;
+    Ok(())
+}
+
+/// An external-content FTS5 table has to be told exactly what's leaving it, and only once: rows it
+/// was never told about would throw its counts off, so what's been unindexed is noted in `meta`.
+fn unindex(conn: &Connection, translation: Translation) -> Result<()> {
+    conn.execute(
+        "INSERT INTO verses_fts (verses_fts, rowid, content)
+         SELECT 'delete', rowid, content FROM verses WHERE translation = ?1",
+        [translation.to_string()],
+    )?;
+    Ok(())
+}
+
+fn is_unindexed(conn: &Connection, translation: Translation) -> Result<bool> {
+    let unindexed = conn
+        .query_row(
+            "SELECT 1 FROM meta WHERE key = ?1",
+            [unindexed_key(translation)],
+            |_| Ok(()),
+        )
+        .optional()?;
+    Ok(unindexed.is_some())
+}
+
+fn unindexed_key(translation: Translation) -> String {
+    format!("unindexed:{translation}")
+}
+
+fn text(id: u32, content: String) -> Text {
+    let Location {
+        book,
+        chapter,
+        verse,
+    } = Location::from_id(id);
+    Text {
+        book,
+        chapter,
+        verse,
+        content,
+    }
+}
+
+/// Limits a query over `verses v` to some books; no scopes means the whole Bible. The ids are
+/// numbers, so they go straight into the sql.
+fn scope_clause(scopes: &[Scope]) -> String {
+    if scopes.is_empty() {
+        return String::new();
+    }
+
+    let books: Vec<_> = scopes
+        .iter()
+        .flat_map(|scope| scope.books())
+        .map(|book| (book as u8).to_string())
+        .collect();
+    format!(" AND v.id / 1000000 IN ({})", books.join(", "))
+}
+
+/// A search string, parsed the way tantivy would parse it, as an FTS5 query.
+fn fts_query(query: &str) -> Result<String> {
+    let parsed = search::parse_query(query)?;
+    to_fts(parsed.as_ref()).ok_or_else(|| {
+        Error::bad_query(
+            query,
+            "that kind of query isn't supported with the sqlite backend",
+        )
+    })
+}
+
+/// Each of tantivy's clauses has an FTS5 equivalent: words must appear where they're required,
+/// otherwise any one of them will do (which is how tantivy matches, too, if not how it ranks),
+/// and excluded words come off the end with NOT.
+fn to_fts(query: &dyn Query) -> Option<String> {
+    if let Some(term) = query.downcast_ref::<TermQuery>() {
+        return Some(format!("\"{}\"", term.term().value().as_str()?));
+    }
+
+    if let Some(phrase) = query.downcast_ref::<PhraseQuery>() {
+        let terms: Option<Vec<_>> = phrase
+            .phrase_terms()
+            .iter()
+            .map(|term| term.value().as_str().map(String::from))
+            .collect();
+        return Some(phrase_query(&terms?));
+    }
+
+    let boolean = query.downcast_ref::<BooleanQuery>()?;
+    let clauses = |wanted: Occur| -> Option<Vec<String>> {
+        boolean
+            .clauses()
+            .iter()
+            .filter(|(occur, _)| *occur == wanted)
+            .map(|(_, query)| to_fts(query.as_ref()))
+            .collect()
+    };
+
+    let must = clauses(Occur::Must)?;
+    let should = clauses(Occur::Should)?;
+    let mut fts = if !must.is_empty() {
+        format!("({})", must.join(" AND "))
+    } else if !should.is_empty() {
+        format!("({})", should.join(" OR "))
+    } else {
+        return None;
+    };
+    for excluded in clauses(Occur::MustNot)? {
+        fts = format!("({fts} NOT {excluded})");
+    }
+    Some(fts)
+}
+
+fn phrase_query(terms: &[String]) -> String {
+    format!("\"{}\"", terms.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fts_query, Store};
+    use crate::{Book, Scope, Translation};
+
+    #[test]
+    fn store() {
+        let store = Store::open_in_memory(None).unwrap();
+        let location = Some("11:35".parse().unwrap());
+        let texts = store
+            .lookup(Book::John, location, Translation::Kjv)
+            .unwrap();
+        assert_eq!("Jesus wept.", texts[0].content);
+
+        let hits = store
+            .search("+jesus +wept", Translation::Kjv, &[], None)
+            .unwrap();
+        assert_eq!(3, hits.len());
+        assert_eq!(
+            (Book::John, 11, 35),
+            (hits[0].1.book, hits[0].1.chapter, hits[0].1.verse)
+        );
+
+        let gospels = [Scope::Gospels];
+        let counts = store
+            .count_by_chapter("wept", Translation::Kjv, &gospels)
+            .unwrap();
+        assert!(counts
+            .iter()
+            .all(|&(book, _, _)| book >= Book::Matthew && book <= Book::John));
+        assert!(counts.contains(&(Book::John, 11, 1)));
+
+        let found = store
+            .find_term("jesus wept", Translation::Kjv, &[])
+            .unwrap();
+        assert_eq!(vec![0..10], found[0].1);
+    }
+
+    #[test]
+    fn unindexing() {
+        let store = Store::open_in_memory(None).unwrap();
+        let location = Some("11:35".parse().unwrap());
+        store.unindex_translation(Translation::Kjv).unwrap();
+        store.unindex_translation(Translation::Kjv).unwrap();
+
+        let hits = store.search("wept", Translation::Kjv, &[], None).unwrap();
+        assert!(hits.is_empty());
+        let texts = store
+            .lookup(Book::John, location, Translation::Kjv)
+            .unwrap();
+        assert_eq!("Jesus wept.", texts[0].content);
+
+        let text = Translation::Kjv.text().unwrap();
+        store.add_translation(Translation::Kjv, text).unwrap();
+        let hits = store
+            .search("+jesus +wept", Translation::Kjv, &[], None)
+            .unwrap();
+        assert_eq!(3, hits.len());
+    }
+
+    #[test]
+    fn queries() {
+        assert_eq!("\"love\"", fts_query("love").unwrap());
+        assert_eq!("(\"faith\" OR \"hope\")", fts_query("faith hope").unwrap());
+        assert_eq!(
+            "((\"faith\") NOT \"works\")",
+            fts_query("+faith -works").unwrap()
+        );
+        assert_eq!(
+            "(\"by grace\" AND \"faith\")",
+            fts_query("\"by grace\" AND faith").unwrap()
+        );
+        assert_eq!("\"benjamin s\"", fts_query("Benjamin's").unwrap());
+    }
+}
@@ -0,0 +1,284 @@
+//! Word counts and frequencies for a passage.
+
+use std::{collections::HashMap, ops::Range, time::Duration};
+
+use crate::{book::Book, text::Text};
+
+/// Words too common to say anything about a passage, including the archaic ones the KJV is full
+/// of.
+#[rustfmt::skip]
+pub(crate) static STOPWORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "am", "an", "and", "any", "are", "art", "as", "at", "be",
+    "because", "been", "before", "being", "but", "by", "came", "can", "come", "did", "do", "doth",
+    "even", "every", "for", "from", "had", "hast", "hath", "have", "he", "her", "him", "himself",
+    "his", "how", "i", "if", "in", "into", "is", "it", "its", "let", "may", "me", "mine", "my",
+    "neither", "no", "nor", "not", "now", "o", "of", "on", "one", "or", "our", "out", "own", "said",
+    "saith", "saying", "shall", "she", "should", "so", "than", "that", "the", "thee", "their",
+    "them", "themselves", "then", "there", "therefore", "these", "they", "thine", "this", "those",
+    "thou", "thus", "thy", "to", "unto", "up", "upon", "us", "was", "we", "were", "what", "when",
+    "which", "who", "whom", "will", "with", "would", "ye", "yet", "you", "your",
+];
+
+/// How often a word or phrase turns up in a translation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TermCount {
+    pub occurrences: usize,
+    pub verses: usize,
+    pub books: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    pub verses: usize,
+    pub words: usize,
+    pub unique_words: usize,
+
+    /// The most frequent words, leaving out stopwords, most frequent first.
+    pub top: Vec<(String, usize)>,
+}
+
+impl Stats {
+    /// Count the words in a passage, keeping the `top` most frequent.
+    pub fn new(texts: &[Text], top: usize) -> Self {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut words = 0;
+        for text in texts {
+            for word in words_in(&text.content) {
+                words += 1;
+                *counts.entry(word).or_default() += 1;
+            }
+        }
+
+        let unique_words = counts.len();
+        let mut frequent: Vec<_> = counts
+            .into_iter()
+            .filter(|(word, _)| !STOPWORDS.contains(&word.as_str()))
+            .collect();
+
+        // Ties go alphabetically, so the list doesn't shuffle from one run to the next.
+        frequent.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+        frequent.truncate(top);
+
+        Stats {
+            verses: texts.len(),
+            words,
+            unique_words,
+            top: frequent,
+        }
+    }
+
+    /// Roughly how long the passage takes to read at a given pace.
+    pub fn reading_time(&self, wpm: u32) -> Duration {
+        Duration::from_secs(self.words as u64 * 60 / u64::from(wpm.max(1)))
+    }
+}
+
+/// How a word or phrase is used across the verses where it occurs.
+#[derive(Clone, Debug, Default)]
+pub struct Study {
+    pub occurrences: usize,
+    pub verses: usize,
+
+    /// Occurrences in each chapter with any, in canonical order.
+    pub chapters: Vec<(Book, u16, usize)>,
+
+    /// The words found most often near the term, leaving out stopwords, most frequent first.
+    pub collocates: Vec<(String, usize)>,
+}
+
+impl Study {
+    /// How many words either side of an occurrence count as near it.
+    pub const WINDOW: usize = 5;
+
+    /// Study a term from its concordance: each verse it occurs in, with the byte range of each
+    /// occurrence (see [`Bible::concordance`](crate::Bible::concordance)). Keeps the `top` most
+    /// frequent collocates.
+    pub fn new(concordance: &[(Text, Vec<Range<usize>>)], top: usize) -> Self {
+        let mut chapters: Vec<(Book, u16, usize)> = Vec::new();
+        let mut occurrences = 0;
+        for (text, ranges) in concordance {
+            occurrences += ranges.len();
+            match chapters.last_mut() {
+                Some((book, chapter, count)) if *book == text.book && *chapter == text.chapter => {
+                    *count += ranges.len()
+                }
+                _ => chapters.push((text.book, text.chapter, ranges.len())),
+            }
+        }
+
+        let mut collocates = near_words(concordance, Self::WINDOW);
+        collocates.truncate(top);
+
+        Study {
+            occurrences,
+            verses: concordance.len(),
+            chapters,
+            collocates,
+        }
+    }
+}
+
+/// A word found near a term, and how much likelier it is to turn up there than anywhere else.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Collocate {
+    pub word: String,
+
+    /// How many times the word turns up near the term.
+    pub count: usize,
+
+    /// Pointwise mutual information, in bits: the log of how much more often the word turns up
+    /// near the term than in a verse picked at random. Zero means no more often than chance.
+    pub pmi: f64,
+}
+
+impl Collocate {
+    /// `occurrences` is how many times the term occurs, `verses` how many verses there are to
+    /// choose from and `with_word` how many of those contain the word.
+    pub(crate) fn new(
+        word: String,
+        count: usize,
+        occurrences: usize,
+        with_word: usize,
+        verses: usize,
+    ) -> Self {
+        let near = count as f64 / occurrences.max(1) as f64;
+        let anywhere = with_word.max(1) as f64 / verses.max(1) as f64;
+        Collocate {
+            word,
+            count,
+            pmi: (near / anywhere).log2(),
+        }
+    }
+}
+
+/// The words within `window` words either side of each occurrence in a concordance, with how
+/// often each turns up there, most frequent first. Stopwords and the words of the term itself are
+/// left out.
+pub(crate) fn near_words(
+    concordance: &[(Text, Vec<Range<usize>>)],
+    window: usize,
+) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for (text, ranges) in concordance {
+        let words: Vec<_> = word_spans(&text.content).collect();
+        for range in ranges {
+            let term: Vec<_> = words_in(&text.content[range.clone()]).collect();
+            let before = words.iter().filter(|(_, span)| span.end <= range.start);
+            let after = words.iter().filter(|(_, span)| span.start >= range.end);
+            let near = before.rev().take(window).chain(after.take(window));
+
+            for (word, _) in near {
+                if !STOPWORDS.contains(&word.as_str()) && !term.contains(word) {
+                    *counts.entry(word.clone()).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    // Ties go alphabetically, as with the most frequent words.
+    let mut words: Vec<_> = counts.into_iter().collect();
+    words.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+    words
+}
+
+pub(crate) fn words_in(content: &str) -> impl Iterator<Item = String> + '_ {
+    word_spans(content).map(|(word, _)| word)
+}
+
+/// Each word in some text, lowercased, with its byte range.
+fn word_spans(content: &str) -> impl Iterator<Item = (String, Range<usize>)> + '_ {
+    content
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.trim_matches('\''))
+        .filter(|word| !word.is_empty())
+        .map(move |word| {
+            let start = word.as_ptr() as usize - content.as_ptr() as usize;
+            (word.to_lowercase(), start..start + word.len())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Collocate, Stats, Study};
+    use crate::{book::Book, text::Text};
+
+    #[test]
+    fn stats() {
+        let texts = [
+            Text {
+                book: Book::John,
+                chapter: 11,
+                verse: 35,
+                content: String::from("Jesus wept."),
+            },
+            Text {
+                book: Book::John,
+                chapter: 11,
+                verse: 36,
+                content: String::from("Then said the Jews, Behold how he loved him!"),
+            },
+        ];
+
+        let stats = Stats::new(&texts, 3);
+        assert_eq!(2, stats.verses);
+        assert_eq!(11, stats.words);
+        assert_eq!(11, stats.unique_words);
+        assert_eq!(
+            vec![("behold", 1), ("jesus", 1), ("jews", 1)],
+            stats
+                .top
+                .iter()
+                .map(|(word, count)| (word.as_str(), *count))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn study() {
+        let grace = |chapter, verse, content: &str| {
+            let ranges = content
+                .match_indices("grace")
+                .map(|(start, word)| start..start + word.len())
+                .collect();
+            let text = Text {
+                book: Book::Ephesians,
+                chapter,
+                verse,
+                content: content.to_string(),
+            };
+            (text, ranges)
+        };
+
+        let concordance = [
+            grace(2, 5, "by grace ye are saved;"),
+            grace(2, 8, "For by grace are ye saved through faith"),
+            grace(
+                4,
+                7,
+                "But unto every one of us is given grace according to the measure",
+            ),
+        ];
+
+        let study = Study::new(&concordance, 2);
+        assert_eq!(3, study.occurrences);
+        assert_eq!(3, study.verses);
+        assert_eq!(
+            vec![(Book::Ephesians, 2, 2), (Book::Ephesians, 4, 1)],
+            study.chapters
+        );
+        assert_eq!(
+            vec![(String::from("saved"), 2), (String::from("according"), 1)],
+            study.collocates
+        );
+    }
+
+    #[test]
+    fn pmi() {
+        // Near the term half the time, and in half of all verses: no association at all.
+        let chance = Collocate::new(String::from("and"), 5, 10, 50, 100);
+        assert_eq!(0.0, chance.pmi);
+
+        let twice = Collocate::new(String::from("dread"), 4, 8, 25, 100);
+        assert_eq!(1.0, twice.pmi);
+    }
+}
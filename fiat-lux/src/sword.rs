@@ -0,0 +1,672 @@
+//! Read-only access to installed SWORD modules, the library format shared by Xiphos, BibleTime,
+//! AndBible and friends.
+//!
+//! Only Bible text modules are supported, and only in the KJV versification, which covers the
+//! bulk of what CrossWire distributes. The format is undocumented beyond the SWORD source, so for
+//! the record: each testament has an index file of fixed-size entries, one per verse *plus* one
+//! per book and chapter heading, pointing into a data file (raw text) or into zlib-compressed
+//! blocks (ztext).
+
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::OnceLock,
+};
+
+use crate::{
+    book::Book,
+    error::Error,
+    location::{Location, PartialLocation},
+    text::{parse_verses_with_id, Text},
+    translation::Translation,
+};
+
+pub(crate) struct Sword {
+    modules: Vec<Module>,
+}
+
+impl Sword {
+    /// Scan each library directory (e.g. ~/.sword) for module configs. Modules we can't read are
+    /// skipped rather than reported, since most libraries hold commentaries and dictionaries too.
+    pub fn load(libraries: &[PathBuf]) -> Self {
+        let mut modules = Vec::new();
+        for library in libraries {
+            let Ok(entries) = fs::read_dir(library.join("mods.d")) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == "conf") {
+                    if let Some(module) = fs::read_to_string(&path)
+                        .ok()
+                        .and_then(|conf| Module::from_conf(&conf, library))
+                    {
+                        modules.push(module);
+                    }
+                }
+            }
+        }
+
+        modules.sort_by(|a, b| a.name.cmp(&b.name));
+        Self { modules }
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.modules.iter().map(|module| module.name.as_str())
+    }
+
+    pub fn contains(&self, translation: Translation) -> bool {
+        self.find(translation).is_some()
+    }
+
+    pub fn lookup(
+        &self,
+        book: Book,
+        location: Option<PartialLocation>,
+        translation: Translation,
+    ) -> io::Result<Vec<Text>> {
+        self.find(translation)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such module"))?
+            .lookup(book, location)
+    }
+
+    /// Each English word or phrase tagged with a Strong's number in the named module, lowercased,
+    /// with how many times it's used, most used first. `None` if there's no such module.
+    pub fn renderings(
+        &self,
+        name: &str,
+        strongs: Strongs,
+    ) -> io::Result<Option<Vec<(String, usize)>>> {
+        let Some(module) = self.module(name) else {
+            return Ok(None);
+        };
+
+        // Hebrew numbers are only found in the old testament and Greek in the new.
+        let books = match strongs.language {
+            'H' => 1..=39,
+            _ => 40..=66,
+        };
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for book in books.map(Book::from_u8) {
+            for (_, _, raw) in module.raw(book, None)? {
+                for rendering in tagged(&raw, strongs) {
+                    *counts.entry(rendering).or_default() += 1;
+                }
+            }
+        }
+
+        let mut renderings: Vec<_> = counts.into_iter().collect();
+        renderings.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(Some(renderings))
+    }
+
+    /// The words in a passage of the named module tagged with Strong's numbers, with the chapter
+    /// and verse each is in. `None` if there's no such module.
+    pub fn tags(
+        &self,
+        name: &str,
+        book: Book,
+        location: Option<PartialLocation>,
+    ) -> io::Result<Option<Vec<Tag>>> {
+        let Some(module) = self.module(name) else {
+            return Ok(None);
+        };
+
+        let mut found = Vec::new();
+        for (chapter, verse, raw) in module.raw(book, location)? {
+            found.extend(tags(&raw).into_iter().map(|(strongs, word)| Tag {
+                chapter,
+                verse,
+                strongs,
+                word,
+            }));
+        }
+        Ok(Some(found))
+    }
+
+    fn module(&self, name: &str) -> Option<&Module> {
+        self.modules
+            .iter()
+            .find(|module| module.name.eq_ignore_ascii_case(name))
+    }
+
+    fn find(&self, translation: Translation) -> Option<&Module> {
+        let name = translation.to_string();
+        self.modules
+            .iter()
+            .find(|module| module.name.eq_ignore_ascii_case(&name))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Driver {
+    RawText,
+    RawText4,
+    ZText,
+    ZText4,
+}
+
+impl Driver {
+    /// Bytes per entry in the verse index.
+    fn entry_size(self) -> u64 {
+        match self {
+            Driver::RawText => 6,
+            Driver::RawText4 => 8,
+            Driver::ZText => 10,
+            Driver::ZText4 => 12,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Module {
+    name: String,
+    path: PathBuf,
+    driver: Driver,
+    latin1: bool,
+}
+
+impl Module {
+    fn from_conf(conf: &str, library: &Path) -> Option<Self> {
+        let mut lines = conf.lines().map(str::trim);
+        let name = lines
+            .find(|line| !line.is_empty())?
+            .strip_prefix('[')?
+            .strip_suffix(']')?
+            .to_string();
+
+        let fields: HashMap<_, _> = lines
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .collect();
+
+        let driver = match *fields.get("ModDrv")? {
+            "RawText" => Driver::RawText,
+            "RawText4" => Driver::RawText4,
+            "zText" => Driver::ZText,
+            "zText4" => Driver::ZText4,
+            _ => return None,
+        };
+
+        // Locked modules need a key, other versifications need a whole new set of tables, and
+        // we only have a decompressor for zip.
+        let unsupported = fields.get("CipherKey").is_some_and(|key| !key.is_empty())
+            || fields.get("Versification").is_some_and(|v| *v != "KJV")
+            || fields
+                .get("CompressType")
+                .is_some_and(|compress| *compress != "ZIP");
+        if unsupported {
+            return None;
+        }
+
+        let path = library.join(fields.get("DataPath")?.trim_start_matches("./"));
+        let latin1 = fields
+            .get("Encoding")
+            .is_none_or(|encoding| *encoding != "UTF-8");
+        Some(Self {
+            name,
+            path,
+            driver,
+            latin1,
+        })
+    }
+
+    fn lookup(&self, book: Book, location: Option<PartialLocation>) -> io::Result<Vec<Text>> {
+        let texts = self
+            .raw(book, location)?
+            .into_iter()
+            .map(|(chapter, verse, raw)| Text {
+                book,
+                chapter,
+                verse,
+                content: plain_text(&raw),
+            })
+            .filter(|text| !text.content.is_empty())
+            .collect();
+        Ok(texts)
+    }
+
+    /// The verses of a book, or of a chapter of it, markup and all.
+    fn raw(
+        &self,
+        book: Book,
+        location: Option<PartialLocation>,
+    ) -> io::Result<Vec<(u16, u16, String)>> {
+        let Some(chapters) = versification().get(usize::from(book as u8) - 1) else {
+            return Ok(Vec::new());
+        };
+
+        let testament = if (book as u8) < 40 { "ot" } else { "nt" };
+        let mut reader = match Reader::open(&self.path, testament, self.driver) {
+            Ok(reader) => reader,
+            // Plenty of modules are New Testament only.
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let wanted = match location {
+            Some(location) => location.chapter..=location.chapter,
+            None => 1..=chapters.len() as u16,
+        };
+
+        let mut verses = Vec::new();
+        for chapter in wanted {
+            let Some(&count) = chapters.get(usize::from(chapter).wrapping_sub(1)) else {
+                continue;
+            };
+
+            for verse in 1..=count {
+                if location.is_some_and(|location| !location.contains(verse)) {
+                    continue;
+                }
+
+                let bytes = reader.verse(index(book, chapter, verse))?;
+                let raw = if self.latin1 {
+                    bytes.iter().map(|&u| char::from(u)).collect()
+                } else {
+                    String::from_utf8_lossy(&bytes).into_owned()
+                };
+
+                verses.push((chapter, verse, raw));
+            }
+        }
+
+        Ok(verses)
+    }
+}
+
+/// Open index and data files for one testament, keeping the last decompressed block around
+/// since a chapter almost always lives in a single block.
+struct Reader {
+    driver: Driver,
+    index: File,
+    data: File,
+    blocks: Option<File>,
+    block: Option<(u32, Vec<u8>)>,
+}
+
+impl Reader {
+    fn open(path: &Path, testament: &str, driver: Driver) -> io::Result<Self> {
+        match driver {
+            Driver::RawText | Driver::RawText4 => Ok(Self {
+                driver,
+                index: File::open(path.join(format!("{testament}.vss")))?,
+                data: File::open(path.join(testament))?,
+                blocks: None,
+                block: None,
+            }),
+            Driver::ZText | Driver::ZText4 => Ok(Self {
+                driver,
+                index: File::open(path.join(format!("{testament}.bzv")))?,
+                data: File::open(path.join(format!("{testament}.bzz")))?,
+                blocks: Some(File::open(path.join(format!("{testament}.bzs")))?),
+                block: None,
+            }),
+        }
+    }
+
+    fn verse(&mut self, idx: u64) -> io::Result<Vec<u8>> {
+        let size = self.driver.entry_size();
+        let entry = match read_at(&mut self.index, idx * size, size as usize) {
+            Ok(entry) => entry,
+            // The index stops short if the module doesn't cover the whole testament.
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        match self.driver {
+            Driver::RawText | Driver::RawText4 => {
+                let start = u32_at(&entry, 0);
+                let len = if self.driver == Driver::RawText {
+                    u32::from(u16_at(&entry, 4))
+                } else {
+                    u32_at(&entry, 4)
+                };
+                read_at(&mut self.data, u64::from(start), len as usize)
+            }
+
+            Driver::ZText | Driver::ZText4 => {
+                let block = u32_at(&entry, 0);
+                let start = u32_at(&entry, 4) as usize;
+                let len = if self.driver == Driver::ZText {
+                    usize::from(u16_at(&entry, 8))
+                } else {
+                    u32_at(&entry, 8) as usize
+                };
+
+                if len == 0 {
+                    return Ok(Vec::new());
+                }
+
+                let text = self.block(block)?;
+                Ok(text.get(start..start + len).unwrap_or_default().to_vec())
+            }
+        }
+    }
+
+    fn block(&mut self, number: u32) -> io::Result<&[u8]> {
+        if self
+            .block
+            .as_ref()
+            .is_none_or(|(cached, _)| *cached != number)
+        {
+            let blocks = self
+                .blocks
+                .as_mut()
+                .expect("ztext reader without block index");
+            let entry = read_at(blocks, u64::from(number) * 12, 12)?;
+            let offset = u32_at(&entry, 0);
+            let compressed = read_at(
+                &mut self.data,
+                u64::from(offset),
+                u32_at(&entry, 4) as usize,
+            )?;
+
+            let mut text = Vec::with_capacity(u32_at(&entry, 8) as usize);
+            flate2::read::ZlibDecoder::new(&compressed[..]).read_to_end(&mut text)?;
+            self.block = Some((number, text));
+        }
+
+        Ok(&self.block.as_ref().unwrap().1)
+    }
+}
+
+fn read_at(file: &mut File, pos: u64, len: usize) -> io::Result<Vec<u8>> {
+    let mut buf = vec![0; len];
+    file.seek(SeekFrom::Start(pos))?;
+    file.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn u16_at(buf: &[u8], pos: usize) -> u16 {
+    u16::from_le_bytes([buf[pos], buf[pos + 1]])
+}
+
+fn u32_at(buf: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([buf[pos], buf[pos + 1], buf[pos + 2], buf[pos + 3]])
+}
+
+/// Verses per chapter for each book in the KJV versification, which happens to be exactly what
+/// our KJV text contains.
+fn versification() -> &'static [Vec<u16>] {
+    static VERSIFICATION: OnceLock<Vec<Vec<u16>>> = OnceLock::new();
+    VERSIFICATION.get_or_init(|| {
+        let mut books = vec![Vec::new(); 66];
+        for (id, _) in parse_verses_with_id(Translation::Kjv.text().unwrap_or_default()) {
+            let Location {
+                book,
+                chapter,
+                verse,
+            } = Location::from_id(id);
+
+            let chapters: &mut Vec<u16> = &mut books[usize::from(book as u8) - 1];
+            let chapter = usize::from(chapter);
+            if chapters.len() < chapter {
+                chapters.resize(chapter, 0);
+            }
+            chapters[chapter - 1] = chapters[chapter - 1].max(verse);
+        }
+        books
+    })
+}
+
+/// Position of a verse in its testament's index. Entry 0 is the module heading and entry 1 the
+/// testament heading; each book then starts with its own heading, and each chapter with one too
+/// (verse 0), so Genesis 1:1 is entry 4.
+fn index(book: Book, chapter: u16, verse: u16) -> u64 {
+    let books = versification();
+    let first = if (book as u8) < 40 { 1 } else { 40 };
+
+    let mut idx = 2;
+    for chapters in &books[first - 1..usize::from(book as u8) - 1] {
+        idx += 1 + chapters.iter().map(|&n| 1 + u64::from(n)).sum::<u64>();
+    }
+
+    idx += 1;
+    let chapters = &books[usize::from(book as u8) - 1];
+    for &n in &chapters[..usize::from(chapter) - 1] {
+        idx += 1 + u64::from(n);
+    }
+
+    idx + u64::from(verse)
+}
+
+/// A word in a verse tagged with a Strong's number, like "loved" (G25) in John 3:16.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tag {
+    pub chapter: u16,
+    pub verse: u16,
+    pub strongs: Strongs,
+    /// The word as the module has it, without punctuation.
+    pub word: String,
+}
+
+/// A Strong's number, like G25 (ἀγαπάω) or H430 (אֱלֹהִים).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Strongs {
+    /// 'H' for Hebrew or 'G' for Greek.
+    pub language: char,
+    pub number: u16,
+}
+
+impl FromStr for Strongs {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = || Error::BadStrongs(s.to_string());
+        let s = s.trim();
+        let language = s.chars().next().ok_or_else(bad)?.to_ascii_uppercase();
+        // Modules pad the number (H0430) and some add a letter (H1254a), which is ignored.
+        let digits: String = s[1..].chars().take_while(char::is_ascii_digit).collect();
+        match (language, digits.parse()) {
+            ('H' | 'G', Ok(number)) => Ok(Self { language, number }),
+            _ => Err(bad()),
+        }
+    }
+}
+
+impl fmt::Display for Strongs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.language, self.number)
+    }
+}
+
+/// The words in an OSIS verse marked with a Strong's number, as in
+/// `<w lemma="strong:G3588 strong:G2316">God</w>`, lowercased and without punctuation.
+fn tagged(raw: &str, strongs: Strongs) -> Vec<String> {
+    tags(raw)
+        .into_iter()
+        .filter(|(lemma, _)| *lemma == strongs)
+        .map(|(_, word)| word.to_lowercase())
+        .collect()
+}
+
+/// Every word in an OSIS verse marked with Strong's numbers, without punctuation, once for each
+/// number it's marked with.
+fn tags(raw: &str) -> Vec<(Strongs, String)> {
+    let mut tags = Vec::new();
+    let mut rest = raw;
+    while let Some(open) = rest.find("<w ") {
+        rest = &rest[open..];
+        let Some(close) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..close];
+        rest = &rest[close + 1..];
+        if tag.ends_with('/') {
+            continue;
+        }
+
+        let lemmas: Vec<Strongs> = tag
+            .split_once("lemma=\"")
+            .and_then(|(_, lemma)| lemma.split_once('"'))
+            .map_or("", |(lemma, _)| lemma)
+            .split_whitespace()
+            .filter_map(|lemma| lemma.strip_prefix("strong:")?.parse().ok())
+            .collect();
+        if lemmas.is_empty() {
+            continue;
+        }
+
+        let Some(end) = rest.find("</w>") else {
+            break;
+        };
+        let word = plain_text(&rest[..end])
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_string();
+        rest = &rest[end..];
+        if !word.is_empty() {
+            tags.extend(lemmas.into_iter().map(|lemma| (lemma, word.clone())));
+        }
+    }
+    tags
+}
+
+/// Strip OSIS/ThML/GBF markup, dropping footnotes entirely.
+fn plain_text(raw: &str) -> String {
+    let mut text = String::with_capacity(raw.len());
+    let mut rest = raw;
+    let mut in_note = false;
+
+    while let Some(open) = rest.find('<') {
+        if !in_note {
+            text.push_str(&rest[..open]);
+        }
+
+        let Some(close) = rest[open..].find('>') else {
+            rest = "";
+            break;
+        };
+
+        let tag = &rest[open + 1..open + close];
+        let name = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        let self_closing = tag.ends_with('/');
+        match name {
+            // OSIS and ThML notes, and GBF's <RF>...<Rf>.
+            "note" | "RF" if !self_closing => in_note = true,
+            "" if tag.starts_with("/note") => in_note = false,
+            "Rf" => in_note = false,
+            _ => {}
+        }
+
+        // Tags like <lb/> separate words.
+        text.push(' ');
+        rest = &rest[open + close + 1..];
+    }
+
+    if !in_note {
+        text.push_str(rest);
+    }
+
+    let text = text
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'");
+
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        // Joining on space leaves a gap wherever a tag sat before punctuation.
+        .replace(" ,", ",")
+        .replace(" .", ".")
+        .replace(" ;", ";")
+        .replace(" :", ":")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Write};
+
+    use super::{index, plain_text, tagged, tags, Strongs, Sword};
+    use crate::{book::Book, location::PartialLocation, translation::Translation};
+
+    #[test]
+    fn index_positions() {
+        assert_eq!(4, index(Book::Genesis, 1, 1));
+        assert_eq!(34, index(Book::Genesis, 1, 31));
+        assert_eq!(36, index(Book::Genesis, 2, 1));
+        assert_eq!(4, index(Book::Matthew, 1, 1));
+    }
+
+    #[test]
+    fn markup() {
+        let raw = r#"<w lemma="strong:G2316">God</w> so loved<note type="x">Or, "so much"</note> the world<lb/>."#;
+        assert_eq!("God so loved the world.", plain_text(raw));
+    }
+
+    #[test]
+    fn strongs_tags() {
+        let raw = r#"<w lemma="strong:G3779">so</w> <w lemma="strong:G25" morph="robinson:V-AAI-3S">loved</w> <w lemma="strong:G3588 strong:G2889">the world</w>, <w lemma="strong:G0025">Beloved</w>,"#;
+        let g25: Strongs = "g25".parse().unwrap();
+        assert_eq!("G25", g25.to_string());
+        assert_eq!(vec!["loved", "beloved"], tagged(raw, g25));
+        assert_eq!(vec!["the world"], tagged(raw, "G2889".parse().unwrap()));
+        assert_eq!((g25, String::from("Beloved")), tags(raw).pop().unwrap());
+        assert!("X25".parse::<Strongs>().is_err());
+    }
+
+    #[test]
+    fn ztext_module() {
+        let library = std::env::temp_dir().join(format!("fiat-lux-sword-{}", std::process::id()));
+        let data = library.join("modules/texts/ztext/test");
+        fs::create_dir_all(library.join("mods.d")).unwrap();
+        fs::create_dir_all(&data).unwrap();
+        fs::write(
+            library.join("mods.d/test.conf"),
+            "[Test]\nDataPath=./modules/texts/ztext/test/\nModDrv=zText\nCompressType=ZIP\nEncoding=UTF-8\n",
+        )
+        .unwrap();
+
+        // One block holding Genesis 1:1-2.
+        let verses = ["In the beginning God created.", "And the earth."];
+        let text = verses.concat();
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut bzs = Vec::new();
+        bzs.extend(0u32.to_le_bytes());
+        bzs.extend((compressed.len() as u32).to_le_bytes());
+        bzs.extend((text.len() as u32).to_le_bytes());
+
+        let mut bzv = vec![0; 10 * 4];
+        let mut start = 0;
+        for verse in verses {
+            bzv.extend(0u32.to_le_bytes());
+            bzv.extend((start as u32).to_le_bytes());
+            bzv.extend((verse.len() as u16).to_le_bytes());
+            start += verse.len();
+        }
+
+        fs::write(data.join("ot.bzs"), bzs).unwrap();
+        fs::write(data.join("ot.bzv"), bzv).unwrap();
+        fs::write(data.join("ot.bzz"), compressed).unwrap();
+
+        let sword = Sword::load(std::slice::from_ref(&library));
+        let translation: Translation = "test".parse().unwrap();
+        let location = PartialLocation {
+            chapter: 1,
+            verse: None,
+            end: None,
+        };
+        let texts = sword
+            .lookup(Book::Genesis, Some(location), translation)
+            .unwrap();
+
+        fs::remove_dir_all(library).unwrap();
+        let contents: Vec<_> = texts.iter().map(|text| text.content.as_str()).collect();
+        assert_eq!(verses[..], contents[..]);
+    }
+}
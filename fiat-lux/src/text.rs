@@ -0,0 +1,96 @@
+use std::cmp::Ordering;
+
+#[cfg(feature = "index")]
+use tantivy::{schema::Value, TantivyDocument as Document};
+
+use crate::book::Book;
+#[cfg(feature = "index")]
+use crate::{offsets, search::SearchFields, translation::Translation};
+
+/// A single verse of text, along with its location.
+#[derive(Clone, Debug)]
+pub struct Text {
+    // translation: Translation,
+    pub book: Book,
+    pub chapter: u16,
+    pub verse: u16,
+    pub content: String,
+}
+
+#[cfg(feature = "index")]
+impl Text {
+    pub(crate) fn from_document(document: Document, fields: &SearchFields) -> Self {
+        let location = document
+            .get_first(fields.location)
+            .unwrap()
+            .as_facet()
+            .unwrap()
+            .to_string();
+        let mut segments = location.trim_start_matches('/').split('/');
+
+        let book = segments.next().unwrap().parse::<u8>().unwrap().into();
+        let chapter = segments.next().unwrap().parse().unwrap();
+        let verse = segments.next().unwrap().parse().unwrap();
+
+        // A lean index only keeps the text of user translations; the embedded ones are read from
+        // the text they were indexed from.
+        let content = match document.get_first(fields.text) {
+            Some(text) => text.as_str().unwrap().into(),
+            None => {
+                let translation: Translation = document
+                    .get_first(fields.translation)
+                    .unwrap()
+                    .as_facet()
+                    .unwrap()
+                    .to_path()[0]
+                    .parse()
+                    .unwrap();
+                let id = u32::from(book as u8) * 1_000_000
+                    + u32::from(chapter) * 1000
+                    + u32::from(verse);
+                offsets::embedded_verse(translation, id).unwrap_or_default()
+            }
+        };
+
+        Self {
+            book,
+            chapter,
+            verse,
+            content,
+        }
+    }
+}
+
+impl Eq for Text {}
+
+impl PartialEq for Text {
+    fn eq(&self, other: &Self) -> bool {
+        self.book == other.book && self.chapter == other.chapter && self.verse == other.verse
+    }
+}
+
+impl Ord for Text {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match self.book.cmp(&other.book) {
+            Ordering::Equal => match self.chapter.cmp(&other.chapter) {
+                Ordering::Equal => self.verse.cmp(&other.verse),
+                ordering => ordering,
+            },
+            ordering => ordering,
+        }
+    }
+}
+
+impl PartialOrd for Text {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Iterate over the verses of a fixed-width dat file, yielding each verse's numeric id and text.
+pub fn parse_verses_with_id(text: &str) -> impl Iterator<Item = (u64, &str)> {
+    text.lines().filter_map(|line| {
+        let id = line.get(..8)?.parse::<u64>().ok()?;
+        Some((id, line.get(9..)?))
+    })
+}
@@ -0,0 +1,54 @@
+//! A small topical index: well-known passages on comfort, hope, forgiveness and so on.
+
+use crate::location::Passage;
+
+/// Passages by topic, one per line, as tab-separated fields: topic, passage. Topics are in
+/// alphabetical order and passages in canonical order within each.
+static TOPICS_DAT: &str = include_str!("../resource/topics.dat");
+
+/// Every topic, alphabetically.
+pub fn topics() -> Vec<&'static str> {
+    let mut topics: Vec<_> = topical().map(|(topic, _)| topic).collect();
+    topics.dedup();
+    topics
+}
+
+/// The passages listed under a topic, ignoring case, in canonical order. An unknown topic has
+/// none.
+pub fn topic(name: &str) -> Vec<Passage> {
+    let name = name.trim();
+    topical()
+        .filter(|(topic, _)| topic.eq_ignore_ascii_case(name))
+        .map(|(_, passage)| passage)
+        .collect()
+}
+
+/// Every passage in the index, along with its topic. A passage can come up under more than one.
+pub fn topical() -> impl Iterator<Item = (&'static str, Passage)> {
+    TOPICS_DAT.lines().filter_map(|line| {
+        let (topic, passage) = line.split_once('\t')?;
+        Some((topic, passage.parse().ok()?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{topic, topics};
+
+    #[test]
+    fn every_line_parses() {
+        let lines = super::TOPICS_DAT.lines().count();
+        assert_eq!(lines, super::topical().count());
+    }
+
+    #[test]
+    fn lookup() {
+        let names = topics();
+        assert!(names.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(names.contains(&"comfort"));
+
+        let comfort = topic("Comfort");
+        assert!(comfort.contains(&"matthew 5:4".parse().unwrap()));
+        assert!(topic("nothing in particular").is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+use std::{fmt, str::FromStr};
+
+#[cfg(feature = "index")]
+use tantivy::schema::Facet;
+
+use crate::{embedded::Embedded, error::AbbrevStr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Translation {
+    Kjv = 1,
+    Asv = 2,
+
+    /// English Standard Version, via api.esv.org
+    Esv = 3,
+
+    /// New International Version, via API.Bible
+    Niv = 4,
+
+    /// A translation added by the user from a dat file.
+    User(UserTranslation),
+}
+
+impl Translation {
+    /// Every translation embedded in the crate, in indexing order.
+    pub const ALL: [Translation; 2] = [Translation::Kjv, Translation::Asv];
+
+    /// The raw, fixed-width text of the translation, if it's embedded in the crate.
+    pub fn text(self) -> Option<&'static str> {
+        Embedded::for_translation(self).map(Embedded::text)
+    }
+
+    /// Remote translations can't be redistributed, so they're fetched from their publishers at
+    /// query time rather than embedded and indexed.
+    pub fn is_remote(self) -> bool {
+        matches!(self, Translation::Esv | Translation::Niv)
+    }
+
+    /// Bibliographic details for citing the translation. User translations come from whatever
+    /// dat file the user had on hand, so we can't say who published them.
+    pub fn edition(self) -> Option<Edition> {
+        let (title, publisher, year) = match self {
+            Translation::Kjv => ("King James Version", "Robert Barker", 1611),
+            Translation::Asv => ("American Standard Version", "Thomas Nelson & Sons", 1901),
+            Translation::Esv => ("English Standard Version", "Crossway", 2001),
+            Translation::Niv => ("New International Version", "Biblica", 2011),
+            Translation::User(_) => return None,
+        };
+
+        Some(Edition {
+            title,
+            publisher,
+            year,
+        })
+    }
+
+    #[cfg(feature = "index")]
+    pub(crate) fn facet(self) -> Facet {
+        Facet::from(&format!("/{self}"))
+    }
+}
+
+impl FromStr for Translation {
+    type Err = ParseTranslationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "KJV" => Ok(Translation::Kjv),
+            "ASV" => Ok(Translation::Asv),
+            "ESV" => Ok(Translation::Esv),
+            "NIV" => Ok(Translation::Niv),
+            _ => UserTranslation::new(s)
+                .map(Translation::User)
+                .ok_or_else(|| ParseTranslationError::new(s)),
+        }
+    }
+}
+
+impl fmt::Display for Translation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Translation::Kjv => f.write_str("KJV"),
+            Translation::Asv => f.write_str("ASV"),
+            Translation::Esv => f.write_str("ESV"),
+            Translation::Niv => f.write_str("NIV"),
+            Translation::User(user) => f.write_str(user.as_str()),
+        }
+    }
+}
+
+/// Who published a translation, and when.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Edition {
+    pub title: &'static str,
+    pub publisher: &'static str,
+    pub year: u16,
+}
+
+/// The name of a user-supplied translation.
+///
+/// Names are short--at most fifteen letters, digits, dashes or underscores--and case-insensitive,
+/// which lets us keep them inline and keep [`Translation`] `Copy`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UserTranslation {
+    len: u8,
+    name: [u8; UserTranslation::MAX_LEN],
+}
+
+impl UserTranslation {
+    pub const MAX_LEN: usize = 15;
+
+    fn new(name: &str) -> Option<Self> {
+        let valid = !name.is_empty()
+            && name.len() <= Self::MAX_LEN
+            && name
+                .bytes()
+                .all(|u| u.is_ascii_alphanumeric() || u == b'-' || u == b'_');
+
+        if !valid {
+            return None;
+        }
+
+        let mut buf = [0; Self::MAX_LEN];
+        buf[..name.len()].copy_from_slice(name.to_ascii_uppercase().as_bytes());
+        Some(Self {
+            len: name.len() as u8,
+            name: buf,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only ever constructed from ascii.
+        std::str::from_utf8(&self.name[..usize::from(self.len)]).unwrap()
+    }
+}
+
+impl fmt::Debug for UserTranslation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("UserTranslation")
+            .field(&self.as_str())
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("unknown translation '{text}'")]
+pub struct ParseTranslationError {
+    text: String,
+}
+
+impl ParseTranslationError {
+    fn new(text: impl AbbrevStr) -> Self {
+        Self { text: text.get(20) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Translation;
+
+    #[test]
+    fn user_translation_names() {
+        let ylt: Translation = "ylt".parse().unwrap();
+        assert_eq!("YLT", ylt.to_string());
+        assert_eq!(ylt, "YLT".parse().unwrap());
+        assert_eq!(Translation::Kjv, "kjv".parse().unwrap());
+        assert!("not a name".parse::<Translation>().is_err());
+        assert!("much-too-long-a-name".parse::<Translation>().is_err());
+    }
+}
@@ -0,0 +1,318 @@
+//! Ancient weights, measures and money in modern terms.
+//!
+//! Values are the usual approximations (the long cubit of 18 inches, the 11.4 gram shekel and so
+//! on); actual standards varied by period and place, so results are rounded accordingly.
+
+use std::fmt::{self, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    /// meters
+    Length,
+    /// grams
+    Weight,
+    /// liters
+    Volume,
+    /// days' wages for a laborer
+    Money,
+}
+
+#[derive(Debug)]
+struct Unit {
+    names: &'static [&'static str],
+    kind: Kind,
+    value: f64,
+
+    /// Words like "finger" and "reed" are only units when counted: "four fingers", but not "the
+    /// finger of God".
+    counted: bool,
+}
+
+impl Unit {
+    const fn new(names: &'static [&'static str], kind: Kind, value: f64) -> Self {
+        Self {
+            names,
+            kind,
+            value,
+            counted: false,
+        }
+    }
+
+    const fn counted(mut self) -> Self {
+        self.counted = true;
+        self
+    }
+}
+
+static UNITS: &[Unit] = &[
+    Unit::new(&["cubit", "cubits"], Kind::Length, 0.4572),
+    Unit::new(&["span", "spans"], Kind::Length, 0.2286),
+    Unit::new(
+        &["handbreadth", "handbreadths", "handbreath"],
+        Kind::Length,
+        0.0762,
+    ),
+    Unit::new(&["finger", "fingers"], Kind::Length, 0.01905).counted(),
+    Unit::new(&["reed", "reeds"], Kind::Length, 2.7432).counted(),
+    Unit::new(&["fathom", "fathoms"], Kind::Length, 1.8288),
+    Unit::new(
+        &["furlong", "furlongs", "stadia", "stadion"],
+        Kind::Length,
+        185.0,
+    ),
+    Unit::new(&["gerah", "gerahs"], Kind::Weight, 0.57),
+    Unit::new(&["bekah", "beka"], Kind::Weight, 5.7),
+    Unit::new(&["shekel", "shekels"], Kind::Weight, 11.4),
+    Unit::new(&["maneh", "mina", "minas"], Kind::Weight, 570.0),
+    Unit::new(&["talent", "talents"], Kind::Weight, 34_200.0),
+    Unit::new(&["log"], Kind::Volume, 0.31),
+    Unit::new(&["cab"], Kind::Volume, 1.2),
+    Unit::new(&["omer", "omers"], Kind::Volume, 2.2),
+    Unit::new(&["hin"], Kind::Volume, 3.7),
+    Unit::new(&["seah"], Kind::Volume, 7.3),
+    Unit::new(&["ephah", "ephahs"], Kind::Volume, 22.0),
+    Unit::new(&["bath", "baths"], Kind::Volume, 22.0),
+    Unit::new(&["firkin", "firkins"], Kind::Volume, 39.0),
+    Unit::new(&["homer", "homers", "cor"], Kind::Volume, 220.0),
+    Unit::new(&["penny", "pence", "denarius", "denarii"], Kind::Money, 1.0),
+    Unit::new(&["farthing", "farthings"], Kind::Money, 1.0 / 16.0),
+    Unit::new(&["mite", "mites"], Kind::Money, 1.0 / 128.0),
+];
+
+/// A quantity of some ancient unit, e.g. forty cubits.
+#[derive(Clone, Copy, Debug)]
+pub struct Measure {
+    count: f64,
+    unit: &'static Unit,
+}
+
+impl Measure {
+    /// Parse a measure such as "40 cubits", "three hundred shekels of silver" or "a talent".
+    pub fn parse(s: &str) -> Option<Measure> {
+        let words: Vec<_> = s.split_whitespace().map(clean).collect();
+        let idx = words.iter().position(|word| find_unit(word).is_some())?;
+        let unit = find_unit(&words[idx])?;
+        let count = if idx == 0 {
+            1.0
+        } else {
+            parse_number(&words[..idx])?
+        };
+        Some(Measure { count, unit })
+    }
+}
+
+impl fmt::Display for Measure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.count * self.unit.value;
+        match self.unit.kind {
+            Kind::Length if value >= 1000.0 => {
+                write!(
+                    f,
+                    "{} km ({} mi)",
+                    round(value / 1000.0),
+                    round(value / 1609.344)
+                )
+            }
+            Kind::Length if value >= 1.0 => {
+                write!(f, "{} m ({} ft)", round(value), round(value / 0.3048))
+            }
+            Kind::Length => write!(
+                f,
+                "{} cm ({} in)",
+                round(value * 100.0),
+                round(value / 0.0254)
+            ),
+            Kind::Weight if value >= 1000.0 => {
+                write!(
+                    f,
+                    "{} kg ({} lb)",
+                    round(value / 1000.0),
+                    round(value / 453.592)
+                )
+            }
+            Kind::Weight => write!(f, "{} g ({} oz)", round(value), round(value / 28.3495)),
+            Kind::Volume if value >= 3.785 => {
+                write!(f, "{} L ({} US gal)", round(value), round(value / 3.785))
+            }
+            Kind::Volume => write!(f, "{} L ({} US qt)", round(value), round(value / 0.946)),
+            Kind::Money if value == 1.0 => f.write_str("a day's wage"),
+            Kind::Money => write!(f, "{} days' wages", round(value)),
+        }
+    }
+}
+
+/// Annotate each unit in a verse with its modern equivalent, e.g. "forty cubits [18.3 m (60.0
+/// ft)]". Units without a count in front of them are annotated as a single unit.
+pub fn annotate_units(text: &str) -> String {
+    let words: Vec<_> = text.split(' ').collect();
+    let mut buf = String::with_capacity(text.len());
+
+    for (idx, word) in words.iter().enumerate() {
+        if idx > 0 {
+            buf.push(' ');
+        }
+
+        let Some(unit) = find_unit(&clean(word)) else {
+            buf.push_str(word);
+            continue;
+        };
+
+        // Take the longest run of words before the unit which reads as a number.
+        let preceding: Vec<_> = words[idx.saturating_sub(6)..idx]
+            .iter()
+            .map(|word| clean(word))
+            .collect();
+        let count = (0..preceding.len()).find_map(|start| {
+            let words = &preceding[start..];
+            let article = matches!(words, [word] if word == "a" || word == "an");
+            parse_number(words).filter(|_| !(unit.counted && article))
+        });
+
+        // The annotation goes before any trailing punctuation.
+        let end = word.trim_end_matches(|c: char| !c.is_alphanumeric()).len();
+        buf.push_str(&word[..end]);
+        match count {
+            Some(count) => write!(buf, " [{}]", Measure { count, unit }).unwrap(),
+            None if !unit.counted => write!(buf, " [{}]", Measure { count: 1.0, unit }).unwrap(),
+            None => {}
+        }
+        buf.push_str(&word[end..]);
+    }
+
+    buf
+}
+
+fn find_unit(word: &str) -> Option<&'static Unit> {
+    UNITS.iter().find(|unit| unit.names.contains(&word))
+}
+
+fn clean(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_ascii_lowercase()
+}
+
+/// Parse a number written in digits or in words, as the KJV writes them: "forty", "an hundred
+/// and twenty", "threescore and ten", "two thousand".
+fn parse_number(words: &[String]) -> Option<f64> {
+    if let [word] = words {
+        if let Ok(n) = word.parse::<f64>() {
+            return Some(n);
+        }
+    }
+
+    let mut total = 0.0;
+    let mut current = 0.0;
+    let mut any = false;
+
+    for word in words {
+        let word = word.as_str();
+        if word == "and" && any {
+            continue;
+        }
+
+        if let Some(n) = small_number(word) {
+            current += n;
+        } else if let Some(n) = word.strip_suffix("score").and_then(small_number) {
+            current += n * 20.0;
+        } else {
+            match word {
+                "score" => current = current.max(1.0) * 20.0,
+                "hundred" => current = current.max(1.0) * 100.0,
+                "thousand" => {
+                    total += current.max(1.0) * 1000.0;
+                    current = 0.0;
+                }
+                _ => return None,
+            }
+        }
+        any = true;
+    }
+
+    any.then_some(total + current)
+}
+
+fn small_number(word: &str) -> Option<f64> {
+    let n = match word {
+        "a" | "an" | "one" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "six" => 6,
+        "seven" => 7,
+        "eight" => 8,
+        "nine" => 9,
+        "ten" => 10,
+        "eleven" => 11,
+        "twelve" => 12,
+        "thirteen" => 13,
+        "fourteen" => 14,
+        "fifteen" => 15,
+        "sixteen" => 16,
+        "seventeen" => 17,
+        "eighteen" => 18,
+        "nineteen" => 19,
+        "twenty" => 20,
+        "thirty" => 30,
+        "forty" => 40,
+        "fifty" => 50,
+        "sixty" => 60,
+        "seventy" => 70,
+        "eighty" => 80,
+        "ninety" => 90,
+        _ => return None,
+    };
+    Some(f64::from(n))
+}
+
+fn round(x: f64) -> String {
+    if x >= 100.0 {
+        format!("{x:.0}")
+    } else if x >= 10.0 {
+        format!("{x:.1}")
+    } else {
+        format!("{x:.2}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{annotate_units, Measure};
+
+    #[test]
+    fn parse_measures() {
+        assert_eq!(
+            "18.3 m (60.0 ft)",
+            Measure::parse("40 cubits").unwrap().to_string()
+        );
+        assert_eq!(
+            "3.42 kg (7.54 lb)",
+            Measure::parse("three hundred shekels of silver")
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            "32.0 m (105 ft)",
+            Measure::parse("threescore and ten cubits")
+                .unwrap()
+                .to_string()
+        );
+        assert!(Measure::parse("forty days").is_none());
+    }
+
+    #[test]
+    fn annotate() {
+        assert_eq!(
+            "the breadth of it fifty cubits [22.9 m (75.0 ft)],",
+            annotate_units("the breadth of it fifty cubits,")
+        );
+        assert_eq!(
+            "the thickness thereof was four fingers [7.62 cm (3.00 in)]:",
+            annotate_units("the thickness thereof was four fingers:")
+        );
+        assert_eq!(
+            "put it on a reed, and gave him to drink.",
+            annotate_units("put it on a reed, and gave him to drink.")
+        );
+    }
+}
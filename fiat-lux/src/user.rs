@@ -0,0 +1,68 @@
+//! User-supplied translations.
+//!
+//! Each one is a dat file copied into `translations/` under the data directory. The directory
+//! doubles as the registry, so there's no second list to keep in sync with the index.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{text::parse_verses_with_id, translation::Translation};
+
+fn dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("translations")
+}
+
+pub(crate) fn path(data_dir: &Path, translation: Translation) -> PathBuf {
+    dir(data_dir).join(format!("{translation}.dat"))
+}
+
+pub(crate) fn registered(data_dir: &Path) -> io::Result<Vec<Translation>> {
+    let entries = match fs::read_dir(dir(data_dir)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut translations = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "dat") {
+            continue;
+        }
+
+        let name = path.file_stem().and_then(|stem| stem.to_str());
+        if let Some(translation @ Translation::User(_)) = name.and_then(|name| name.parse().ok()) {
+            translations.push(translation);
+        }
+    }
+
+    translations.sort_by_key(|translation| translation.to_string());
+    Ok(translations)
+}
+
+pub(crate) fn is_registered(data_dir: &Path, translation: Translation) -> bool {
+    path(data_dir, translation).is_file()
+}
+
+pub(crate) fn save(data_dir: &Path, translation: Translation, text: &str) -> io::Result<()> {
+    fs::create_dir_all(dir(data_dir))?;
+    fs::write(path(data_dir, translation), text)
+}
+
+pub(crate) fn remove(data_dir: &Path, translation: Translation) -> io::Result<()> {
+    fs::remove_file(path(data_dir, translation))
+}
+
+/// The translations the user has added in `data_dir`, each with how many verses its file holds,
+/// found without opening the index.
+pub fn added_translations(data_dir: &Path) -> io::Result<Vec<(Translation, usize)>> {
+    registered(data_dir)?
+        .into_iter()
+        .map(|translation| {
+            let text = fs::read_to_string(path(data_dir, translation))?;
+            Ok((translation, parse_verses_with_id(&text).count()))
+        })
+        .collect()
+}
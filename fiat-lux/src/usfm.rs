@@ -0,0 +1,188 @@
+//! Conversion from USFM, the markup most translation projects are written in.
+//!
+//! We only care about the text of each verse, so headings, introductions, footnotes and cross
+//! references are all dropped, and character styles (words of Jesus, added words, etc.) are
+//! flattened into plain text. See https://ubsicap.github.io/usfm/ for the whole zoo.
+
+use std::fmt::Write;
+
+use crate::{book::Book, location::Location};
+
+/// Paragraph markers whose text is something other than scripture.
+const SKIP_PARAGRAPH: &[&str] = &[
+    "id", "ide", "h", "toc1", "toc2", "toc3", "toca1", "toca2", "toca3", "mt", "mt1", "mt2", "mt3",
+    "mte", "mte1", "mte2", "ms", "ms1", "ms2", "mr", "s", "s1", "s2", "s3", "s4", "sr", "r", "d",
+    "sp", "rem", "cl", "cd", "sts", "usfm", "restore",
+];
+
+/// Character markers whose contents, up to the closing marker, are not scripture.
+const SKIP_SPAN: &[&str] = &["f", "fe", "ef", "x", "ex", "ca", "va", "vp", "fig", "rq"];
+
+/// Convert a single USFM book into dat lines, one per verse, in canonical order.
+pub fn usfm_to_dat(text: &str) -> Result<String, ParseUsfmError> {
+    let mut parser = Parser::default();
+
+    for segment in text.split('\\').skip(1) {
+        let (marker, content) = segment
+            .find(|c: char| c.is_whitespace() || c == '*')
+            .map_or((segment, ""), |idx| {
+                // A closing marker keeps its asterisk; an opening marker's trailing space goes.
+                if segment[idx..].starts_with('*') {
+                    (&segment[..=idx], &segment[idx + 1..])
+                } else {
+                    (&segment[..idx], &segment[idx + 1..])
+                }
+            });
+
+        // Nested character markers look like \+w ... \+w*.
+        parser.marker(marker.trim_start_matches('+'), content)?;
+    }
+
+    parser.finish()
+}
+
+#[derive(Default)]
+struct Parser {
+    book: Option<Book>,
+    chapter: Option<u16>,
+    verse: Option<u16>,
+    content: String,
+    skipping: Option<String>,
+    verses: Vec<(u64, String)>,
+}
+
+impl Parser {
+    fn marker(&mut self, marker: &str, content: &str) -> Result<(), ParseUsfmError> {
+        if let Some(skipping) = &self.skipping {
+            if marker.strip_suffix('*') == Some(skipping) {
+                self.skipping = None;
+                self.push(content);
+            }
+            return Ok(());
+        }
+
+        match marker {
+            "id" => {
+                let code = content.split_whitespace().next().unwrap_or_default();
+                let book = Book::from_usfm_code(code)
+                    .ok_or_else(|| ParseUsfmError::UnknownBook(code.into()))?;
+                self.book = Some(book);
+            }
+
+            "c" => {
+                self.flush();
+                self.chapter = Some(number(content).ok_or(ParseUsfmError::BadChapter)?);
+                self.verse = None;
+            }
+
+            "v" => {
+                self.flush();
+                let (number_text, rest) = content
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((content, ""));
+
+                // Bridged verses (\v 1-2) go under the first number.
+                self.verse = Some(number(number_text).ok_or(ParseUsfmError::BadVerse)?);
+                self.push(rest);
+            }
+
+            // \w gracious|lemma="..."\w* has attributes we don't need.
+            "w" => self.push(content.split('|').next().unwrap_or_default()),
+
+            _ if SKIP_PARAGRAPH.contains(&marker) => {}
+
+            _ if SKIP_SPAN.contains(&marker) => self.skipping = Some(marker.into()),
+
+            // Everything else is a paragraph or character style, which we flatten.
+            _ => self.push(content),
+        }
+
+        Ok(())
+    }
+
+    fn push(&mut self, content: &str) {
+        // Whitespace in the source is significant: \w loved\w*, has no space before the comma.
+        if self.verse.is_some() {
+            self.content.push_str(content);
+        }
+    }
+
+    fn flush(&mut self) {
+        let content = std::mem::take(&mut self.content);
+        let (Some(book), Some(chapter), Some(verse)) = (self.book, self.chapter, self.verse) else {
+            return;
+        };
+
+        let content = content.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !content.is_empty() {
+            let id = Location {
+                book,
+                chapter,
+                verse,
+            }
+            .id();
+            self.verses.push((id, content));
+        }
+    }
+
+    fn finish(mut self) -> Result<String, ParseUsfmError> {
+        self.flush();
+        if self.book.is_none() {
+            return Err(ParseUsfmError::MissingId);
+        }
+
+        self.verses.sort_by_key(|&(id, _)| id);
+        let mut buf = String::new();
+        for (id, content) in self.verses {
+            writeln!(buf, "{id:08} {content}").unwrap();
+        }
+        Ok(buf)
+    }
+}
+
+/// The leading digits of a chapter or verse number, which may be followed by a bridge or letter.
+fn number(s: &str) -> Option<u16> {
+    let s = s.trim_start();
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s[..end].parse().ok()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ParseUsfmError {
+    #[error("missing \\id marker")]
+    MissingId,
+
+    #[error("unknown book code '{0}'")]
+    UnknownBook(String),
+
+    #[error("bad chapter number")]
+    BadChapter,
+
+    #[error("bad verse number")]
+    BadVerse,
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn usfm_to_dat() {
+        let text = r#"\id JHN Sample
+\h John
+\mt1 The Gospel of John
+\c 3
+\s1 Jesus and Nicodemus
+\p
+\v 16 \wj For God so \w loved|lemma="agapao"\w* the world,\wj*\f + \fr 3:16 \ft Or \fq only\f*
+\wj that he gave his only Son.\wj*
+\v 17-18 For God sent not his \nd Son\nd*,
+\q1 into the world.
+"#;
+
+        let dat = super::usfm_to_dat(text).unwrap();
+        assert_eq!(
+            dat,
+            "43003016 For God so loved the world, that he gave his only Son.\n\
+             43003017 For God sent not his Son, into the world.\n"
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! A verse of the day, the same for everyone on a given date.
+
+use crate::{calendar::Date, location::Passage};
+
+/// Passages to draw from, one per line. Mostly short, well-loved verses of encouragement, spread
+/// across both testaments.
+static VOTD_DAT: &str = include_str!("../resource/votd.dat");
+
+/// The passage for a date. The list is walked in order, a day at a time, so a verse comes back
+/// around only after every other one has had its turn.
+pub fn verse_of_the_day(date: Date) -> Passage {
+    let passages: Vec<Passage> = VOTD_DAT
+        .lines()
+        .filter_map(|line| line.parse().ok())
+        .collect();
+    let idx = date.days().rem_euclid(passages.len() as i64);
+    passages[idx as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::verse_of_the_day;
+    use crate::calendar::Date;
+
+    #[test]
+    fn every_line_parses() {
+        let lines = super::VOTD_DAT.lines().count();
+        let parsed = super::VOTD_DAT
+            .lines()
+            .filter(|line| line.parse::<crate::Passage>().is_ok())
+            .count();
+        assert_eq!(lines, parsed);
+    }
+
+    #[test]
+    fn changes_daily() {
+        let today = verse_of_the_day(Date::new(2025, 12, 31));
+        assert_eq!(today, verse_of_the_day(Date::new(2025, 12, 31)));
+        assert_ne!(today, verse_of_the_day(Date::new(2026, 1, 1)));
+    }
+}
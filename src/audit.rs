@@ -0,0 +1,73 @@
+use std::collections::BTreeSet;
+
+use clap::Parser;
+use fiat_lux::{Book, Translation};
+
+use crate::Result;
+
+#[derive(Clone, Debug, Parser)]
+pub struct AuditArgs {
+    left: Translation,
+    right: Translation,
+}
+
+/// Report every verse present in one translation but not the other, e.g. 3 John 1:15, which
+/// some translations have and others fold into verse 14.
+pub fn audit_versification(args: &AuditArgs) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let mut left_only = Vec::new();
+    let mut right_only = Vec::new();
+
+    for book in (1..=66).map(Book::from_u8) {
+        let left = verses(&bible, book, args.left)?;
+        let right = verses(&bible, book, args.right)?;
+        left_only.extend(left.difference(&right).map(|&(c, v)| (book, c, v)));
+        right_only.extend(right.difference(&left).map(|&(c, v)| (book, c, v)));
+    }
+
+    if left_only.is_empty() && right_only.is_empty() {
+        println!("{} and {} have the same verses", args.left, args.right);
+        return Ok(());
+    }
+
+    for (translation, locations) in [(args.left, &left_only), (args.right, &right_only)] {
+        if locations.is_empty() {
+            continue;
+        }
+
+        println!("only in {translation}:");
+        for range in ranges(locations) {
+            println!("  {range}");
+        }
+    }
+
+    Ok(())
+}
+
+fn verses(
+    bible: &fiat_lux::Bible,
+    book: Book,
+    translation: Translation,
+) -> Result<BTreeSet<(u16, u16)>> {
+    Ok(bible
+        .lookup(book, None, translation)?
+        .into_iter()
+        .map(|text| (text.chapter, text.verse))
+        .collect())
+}
+
+/// Collapse runs of consecutive verses, so a missing pericope reads as Mark 16:9-20 rather than
+/// twelve separate lines.
+fn ranges(locations: &[(Book, u16, u16)]) -> Vec<String> {
+    let runs = locations.chunk_by(|a, b| a.0 == b.0 && a.1 == b.1 && a.2 + 1 == b.2);
+    runs.map(|run| {
+        let (book, chapter, first) = run[0];
+        let last = run[run.len() - 1].2;
+        if first == last {
+            format!("{book} {chapter}:{first}")
+        } else {
+            format!("{book} {chapter}:{first}-{last}")
+        }
+    })
+    .collect()
+}
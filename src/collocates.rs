@@ -0,0 +1,79 @@
+use clap::{Parser, ValueEnum};
+use comfy_table::{Attribute, Cell, CellAlignment, Table};
+use fiat_lux::{Scope, Translation};
+
+use crate::Result;
+
+#[derive(Clone, Debug, Parser)]
+pub struct CollocatesArgs {
+    /// a word or phrase
+    term: String,
+
+    /// how many words either side of the term to look at
+    #[clap(short, long, default_value_t = 5)]
+    window: usize,
+
+    /// how many words to list
+    #[clap(short = 'n', long, default_value_t = 20)]
+    top: usize,
+
+    /// only look in these books (or ot, nt, gospels), comma-separated
+    #[clap(short, long = "book", alias = "in", value_delimiter = ',')]
+    scopes: Vec<Scope>,
+
+    /// rank by how often words turn up near the term, or by how much more often than chance
+    #[clap(long, value_enum, default_value_t = Rank::Count)]
+    by: Rank,
+
+    /// when ranking by pmi, leave out words seen near the term fewer times than this
+    #[clap(long, default_value_t = 3)]
+    min_count: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Rank {
+    Count,
+    Pmi,
+}
+
+pub fn collocates(args: &CollocatesArgs, translation: Translation) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let mut collocates = bible.collocates(&args.term, translation, &args.scopes, args.window)?;
+
+    // A word seen once near the term and hardly anywhere else has an enormous pmi which means
+    // nothing, so pmi only ranks the words seen often enough to count.
+    if args.by == Rank::Pmi {
+        collocates.retain(|collocate| collocate.count >= args.min_count);
+        collocates.sort_by(|a, b| b.pmi.total_cmp(&a.pmi).then_with(|| a.word.cmp(&b.word)));
+    }
+    collocates.truncate(args.top);
+
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::NOTHING);
+    table.set_header(
+        ["", "near", "pmi"]
+            .into_iter()
+            .map(|heading| Cell::new(heading).add_attribute(Attribute::Bold)),
+    );
+
+    for collocate in &collocates {
+        table.add_row(vec![
+            collocate.word.clone(),
+            collocate.count.to_string(),
+            format!("{:.2}", collocate.pmi),
+        ]);
+    }
+
+    for idx in 1..3 {
+        if let Some(column) = table.column_mut(idx) {
+            column.set_cell_alignment(CellAlignment::Right);
+        }
+    }
+
+    println!(
+        "within {} words of \"{}\" ({translation})",
+        args.window, args.term
+    );
+    println!("{table}");
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use std::{borrow::Cow, collections::BTreeMap};
+
+use clap::Parser;
+use comfy_table::{Attribute, Cell, CellAlignment, ContentArrangement, Table};
+use fiat_lux::{Book, PartialLocation, Translation};
+
+use crate::Result;
+
+#[derive(Clone, Debug, Parser)]
+pub struct CompareArgs {
+    book: Book,
+    location: Option<PartialLocation>,
+
+    /// translations to compare (default: all embedded translations)
+    #[clap(short, long, value_delimiter = ',')]
+    translations: Vec<Translation>,
+}
+
+pub fn compare(args: &CompareArgs) -> Result<()> {
+    let translations = if args.translations.is_empty() {
+        Translation::ALL.to_vec()
+    } else {
+        args.translations.clone()
+    };
+
+    // Translations don't always agree on versification, so rather than zipping the lists we key
+    // every verse by its location and leave a blank wherever a translation has nothing.
+    let bible = crate::open_bible()?;
+    let mut rows: BTreeMap<(Book, u16, u16), Vec<Option<String>>> = BTreeMap::new();
+    for (idx, &translation) in translations.iter().enumerate() {
+        for text in bible.lookup(args.book, args.location, translation)? {
+            let row = rows
+                .entry((text.book, text.chapter, text.verse))
+                .or_insert_with(|| vec![None; translations.len()]);
+            row[idx] = Some(text.content);
+        }
+    }
+
+    let width = terminal_size::terminal_size().map_or(100, |(terminal_size::Width(w), _)| w);
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::DynamicFullWidth);
+    table.load_preset(comfy_table::presets::NOTHING);
+    table.set_width(width);
+
+    let mut header = vec![Cell::new("")];
+    header.extend(
+        translations
+            .iter()
+            .map(|translation| Cell::new(translation).add_attribute(Attribute::Bold)),
+    );
+    table.set_header(header);
+
+    let mut current = None;
+    for ((book, chapter, verse), contents) in rows {
+        if current != Some((book, chapter)) {
+            current = Some((book, chapter));
+            table.add_row(vec![
+                Cell::new(""),
+                Cell::new(format!("\n{book} {chapter}")).add_attribute(Attribute::Bold),
+            ]);
+        }
+
+        let mut row = vec![Cow::from(format!("{verse:4}"))];
+        row.extend(
+            contents
+                .into_iter()
+                .map(|content| Cow::from(content.unwrap_or_default())),
+        );
+        table.add_row(row);
+    }
+
+    table
+        .column_mut(0)
+        .unwrap()
+        .set_cell_alignment(CellAlignment::Right);
+
+    println!("{table}");
+    Ok(())
+}
@@ -0,0 +1,453 @@
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::OnceLock,
+};
+
+use clap::ValueEnum;
+use crossterm::style::Color;
+use directories::ProjectDirs;
+use fiat_lux::{Genre, ReferenceProvider, Translation};
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+    format::{CopyStyle, Numerals, OutputFormat},
+    Error, Result,
+};
+
+/// Settings read from `config.toml` in the user's config directory. Anything also given on the
+/// command line is overridden by it.
+///
+/// ```toml
+/// translation = "asv"
+/// format = "markdown"
+/// copy_style = "logos"
+/// numerals = "arabic"
+/// pager = "less -R"
+/// width = 80
+/// color = false
+/// provider = "biblia"
+/// data_dir = "/mnt/fast/bible"
+/// backend = "sqlite"
+/// lean_index = true
+/// tint_books = true
+///
+/// [remote]
+/// esv_key = "..."
+/// api_bible_key = "..."
+/// niv_bible_id = "..."
+/// cache_ttl_days = 30
+/// cache_max_mb = 50
+/// max_retries = 3
+/// requests_per_minute = 60
+///
+/// [sword]
+/// libraries = ["~/.sword"]
+///
+/// [search]
+/// truncate = 120
+///
+/// [index]
+/// heap_mb = 60
+/// threads = 2
+/// merge = "none"
+///
+/// [palette]
+/// torah = "dark_yellow"
+/// epistles = "#5f87d7"
+///
+/// [expand]
+/// template = "“{text}” ({reference})"
+///
+/// [audio.urls]
+/// kjv = "https://example.org/kjv/{book_number:02}_{chapter:03}.mp3"
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// The translation to read when none is given; otherwise the KJV.
+    #[serde(deserialize_with = "parsed")]
+    pub translation: Option<Translation>,
+
+    /// The output format to use when none is given; otherwise a table.
+    #[serde(deserialize_with = "parsed")]
+    pub format: Option<OutputFormat>,
+
+    /// Lay verses out like this app does when they're copied, instead of in the output format.
+    #[serde(deserialize_with = "parsed")]
+    pub copy_style: Option<CopyStyle>,
+
+    /// The digits for chapter and verse numbers; otherwise western ones.
+    #[serde(deserialize_with = "parsed")]
+    pub numerals: Option<Numerals>,
+
+    /// The command to page long tables through, or `none`; otherwise $PAGER, `bat` or `less -R`,
+    /// whichever is installed.
+    pub pager: Option<String>,
+
+    /// The widest a table gets, however wide the terminal; otherwise 100 columns.
+    pub width: Option<u16>,
+
+    /// Whether to color output; otherwise it's colored unless NO_COLOR is set, which also beats this.
+    pub color: Option<bool>,
+
+    /// Where qr codes and links to passages go; otherwise Biblia.
+    #[serde(deserialize_with = "parsed")]
+    pub provider: Option<ReferenceProvider>,
+
+    /// Where to keep the index and everything else; see [`data_dir`].
+    pub data_dir: Option<PathBuf>,
+
+    /// How verses and the search index are stored; otherwise a tantivy index.
+    #[serde(deserialize_with = "parsed")]
+    pub backend: Option<Backend>,
+
+    /// Keep only where each verse is in the index, reading the embedded translations' verses from
+    /// the binary instead; otherwise the index keeps them all.
+    pub lean_index: bool,
+
+    /// Tint chapter headings by the section of the Bible their book is in, like --tint-books.
+    pub tint_books: bool,
+
+    pub remote: RemoteSection,
+    pub sword: SwordSection,
+    pub search: SearchSection,
+    pub index: IndexSection,
+    pub expand: ExpandSection,
+    pub palette: PaletteSection,
+    pub audio: AudioSection,
+}
+
+/// Where the Bible keeps its verses and search index.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Backend {
+    /// a tantivy index directory
+    #[default]
+    Tantivy,
+
+    /// a single SQLite file with an FTS5 index; needs the `sqlite` feature
+    Sqlite,
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <Self as ValueEnum>::from_str(s, true)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct RemoteSection {
+    pub esv_key: Option<String>,
+    pub api_bible_key: Option<String>,
+    pub niv_bible_id: Option<String>,
+    pub cache_ttl_days: Option<u64>,
+    pub cache_max_mb: Option<u64>,
+    pub max_retries: Option<u32>,
+    pub requests_per_minute: Option<u32>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SwordSection {
+    pub libraries: Vec<PathBuf>,
+}
+
+#[cfg(feature = "sword")]
+impl SwordSection {
+    /// The configured SWORD libraries, or else wherever SWORD itself would look.
+    pub fn libraries(&self) -> Vec<PathBuf> {
+        if !self.libraries.is_empty() {
+            return self.libraries.clone();
+        }
+
+        let mut libraries: Vec<PathBuf> = std::env::var_os("SWORD_PATH")
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+        libraries.extend(directories::BaseDirs::new().map(|dirs| dirs.home_dir().join(".sword")));
+        libraries.push(PathBuf::from("/usr/share/sword"));
+        libraries
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SearchSection {
+    /// Shorten each verse in the results to about this many characters.
+    pub truncate: Option<usize>,
+}
+
+/// How the search index is built, e.g. to fit a Raspberry Pi's memory or use a desktop's cores.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct IndexSection {
+    /// Memory for building the index, in megabytes, at least 15 per thread; otherwise 500.
+    pub heap_mb: Option<usize>,
+
+    /// Threads to build it with; otherwise one per core, up to eight.
+    pub threads: Option<usize>,
+
+    /// Whether to merge the index's segments while writing; otherwise they're merged.
+    #[serde(deserialize_with = "parsed")]
+    pub merge: Option<Merge>,
+}
+
+impl IndexSection {
+    /// These settings, with the lean setting from the top level, as the library takes them.
+    /// tantivy won't write with less than 15 MB a thread, and says so only once it's asked to.
+    pub fn options(&self, lean: bool) -> Result<fiat_lux::IndexOptions> {
+        if self.threads == Some(0) {
+            return Err(Error::IndexSetting(String::from(
+                "threads has to be at least 1",
+            )));
+        }
+        if let Some(heap_mb) = self.heap_mb {
+            let threads = self.threads.unwrap_or(1);
+            let least = (threads * 15_000_000).div_ceil(0x100000);
+            if heap_mb < least {
+                return Err(Error::IndexSetting(match threads {
+                    1 => format!("heap_mb has to be at least {least}"),
+                    _ => format!("heap_mb has to be at least {least} for {threads} threads"),
+                }));
+            }
+        }
+
+        Ok(fiat_lux::IndexOptions {
+            lean,
+            heap_bytes: self.heap_mb.map(|mb| mb * 0x100000),
+            threads: self.threads,
+            merge: self.merge.unwrap_or_default().into(),
+        })
+    }
+}
+
+/// Whether the index writer merges segments as it goes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Merge {
+    /// merge segments of similar size in the background
+    #[default]
+    Log,
+
+    /// never merge, which spares memory and disk while building
+    None,
+}
+
+impl From<Merge> for fiat_lux::MergePolicy {
+    fn from(merge: Merge) -> Self {
+        match merge {
+            Merge::Log => fiat_lux::MergePolicy::Log,
+            Merge::None => fiat_lux::MergePolicy::None,
+        }
+    }
+}
+
+impl FromStr for Merge {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <Self as ValueEnum>::from_str(s, true)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ExpandSection {
+    /// What each `{{reference}}` becomes, e.g. "{text} ({reference}, {translation})".
+    pub template: Option<String>,
+}
+
+/// The colors chapter headings are tinted with, by the section of the Bible their book is in:
+/// crossterm's color names, like `dark_cyan`, or `#rrggbb`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PaletteSection {
+    #[serde(deserialize_with = "color")]
+    pub torah: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub history: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub wisdom: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub prophets: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub gospels: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub epistles: Option<Color>,
+}
+
+impl PaletteSection {
+    /// The configured color for a section, or else one of the terminal's own.
+    pub fn color(&self, genre: Genre) -> Color {
+        let (configured, default) = match genre {
+            Genre::Torah => (self.torah, Color::DarkYellow),
+            Genre::History => (self.history, Color::DarkCyan),
+            Genre::Wisdom => (self.wisdom, Color::DarkMagenta),
+            Genre::Prophets => (self.prophets, Color::DarkRed),
+            Genre::Gospels => (self.gospels, Color::DarkGreen),
+            Genre::Epistles => (self.epistles, Color::Blue),
+        };
+        configured.unwrap_or(default)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AudioSection {
+    /// A url (or file path) template for each translation's recordings, keyed by translation.
+    pub urls: HashMap<String, String>,
+}
+
+#[cfg(feature = "audio")]
+impl AudioSection {
+    pub fn url_for(&self, translation: fiat_lux::Translation) -> Option<&str> {
+        let name = translation.to_string();
+        self.urls
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(&name))
+            .map(|(_, url)| url.as_str())
+    }
+}
+
+impl Config {
+    pub fn width(&self) -> u16 {
+        self.width.unwrap_or(100)
+    }
+
+    pub fn load() -> Result<Self> {
+        let Some(path) = config_path() else {
+            return Ok(Config::default());
+        };
+
+        let mut config: Config = match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Config::default(),
+            Err(e) => return Err(e.into()),
+        };
+        config.read_env()?;
+        Ok(config)
+    }
+
+    /// Settings from the environment, which beat the ones in the file, so a script or container
+    /// can get by without one.
+    fn read_env(&mut self) -> Result<()> {
+        if let Some(name) = var("FIAT_LUX_TRANSLATION") {
+            let translation = name.parse().map_err(|e: fiat_lux::ParseTranslationError| {
+                Error::Env("FIAT_LUX_TRANSLATION", e.to_string())
+            })?;
+            self.translation = Some(translation);
+        }
+
+        if let Some(command) = var("FIAT_LUX_PAGER") {
+            self.pager = Some(command);
+        }
+
+        if let Some(path) = var("FIAT_LUX_DATA_DIR") {
+            self.data_dir = Some(PathBuf::from(path));
+        }
+
+        // https://no-color.org
+        if var("NO_COLOR").is_some() {
+            self.color = Some(false);
+        }
+
+        Ok(())
+    }
+}
+
+/// Read a setting written the same way it would be on the command line.
+fn parsed<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let text = String::deserialize(deserializer)?;
+    text.parse().map(Some).map_err(serde::de::Error::custom)
+}
+
+/// Read a color by name, e.g. `dark_cyan`, or as `#rrggbb`.
+fn color<'de, D>(deserializer: D) -> Result<Option<Color>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let text = String::deserialize(deserializer)?;
+    let rgb = text
+        .strip_prefix('#')
+        .filter(|hex| hex.len() == 6)
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok());
+    match rgb {
+        Some(rgb) => Ok(Some(Color::Rgb {
+            r: (rgb >> 16) as u8,
+            g: (rgb >> 8) as u8,
+            b: rgb as u8,
+        })),
+        None => Color::try_from(text.as_str())
+            .map(Some)
+            .map_err(|_| serde::de::Error::custom(format!("unknown color: {text}"))),
+    }
+}
+
+/// An environment variable, unless it's missing or empty.
+fn var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+pub fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("org", "Hack Commons", "Bible-App")
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    let dirs = project_dirs()?;
+    match PROFILE.get() {
+        Some(profile) => Some(profile_dir(dirs.config_dir(), profile).join("config.toml")),
+        None => Some(dirs.config_dir().join("config.toml")),
+    }
+}
+
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Use a profile's own config, history, notes and plans for the rest of the process. Names are
+/// directory names, so they can't have slashes in them.
+pub fn set_profile(name: &str) -> Result<()> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(Error::BadProfile(name.to_string()));
+    }
+    let _ = PROFILE.set(name.to_string());
+    Ok(())
+}
+
+fn profile_dir(parent: &Path, profile: &str) -> PathBuf {
+    parent.join("profiles").join(profile)
+}
+
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Keep everything in `path` rather than the usual place, for the rest of the process.
+pub fn set_data_dir(path: PathBuf) {
+    let _ = DATA_DIR.set(path);
+}
+
+/// Where the index, history, notes and the rest are kept: wherever `--data-dir`,
+/// `FIAT_LUX_DATA_DIR` or the config file says (in that order), or else the usual place for the
+/// platform.
+pub fn data_dir() -> Option<PathBuf> {
+    DATA_DIR
+        .get()
+        .cloned()
+        .or_else(|| project_dirs().map(|dirs| dirs.data_dir().to_path_buf()))
+}
+
+/// Where history, notes, plans and the like are kept: the data directory, or with `--profile`, a
+/// directory of the profile's own inside it. The index and added translations are shared.
+pub fn state_dir() -> Option<PathBuf> {
+    let data_dir = data_dir()?;
+    match PROFILE.get() {
+        Some(profile) => Some(profile_dir(&data_dir, profile)),
+        None => Some(data_dir),
+    }
+}
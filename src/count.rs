@@ -0,0 +1,81 @@
+use clap::Parser;
+use comfy_table::{Attribute, Cell, CellAlignment, Table};
+use fiat_lux::{TermCount, Translation};
+
+use crate::Result;
+
+#[derive(Clone, Debug, Parser)]
+pub struct CountArgs {
+    /// a word or phrase
+    phrase: String,
+
+    /// translations to count in, comma-separated (default: the selected translation)
+    #[clap(short, long, value_delimiter = ',')]
+    translations: Vec<Translation>,
+
+    /// count in every embedded translation
+    #[clap(long, conflicts_with = "translations")]
+    all: bool,
+}
+
+pub fn count(args: &CountArgs, translation: Translation) -> Result<()> {
+    let translations = if args.all {
+        Translation::ALL.to_vec()
+    } else if args.translations.is_empty() {
+        vec![translation]
+    } else {
+        args.translations.clone()
+    };
+
+    let bible = crate::open_bible()?;
+    if let [translation] = translations[..] {
+        let TermCount {
+            occurrences,
+            verses,
+            books,
+        } = bible.count(&args.phrase, translation)?;
+        println!(
+            "\"{}\" ({translation}): {occurrences} {} in {verses} {} across {books} {}",
+            args.phrase,
+            plural(occurrences, "occurrence"),
+            plural(verses, "verse"),
+            plural(books, "book"),
+        );
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::NOTHING);
+    table.set_header(
+        ["", "occurrences", "verses", "books"]
+            .into_iter()
+            .map(|heading| Cell::new(heading).add_attribute(Attribute::Bold)),
+    );
+
+    for translation in translations {
+        let count = bible.count(&args.phrase, translation)?;
+        table.add_row(vec![
+            translation.to_string(),
+            count.occurrences.to_string(),
+            count.verses.to_string(),
+            count.books.to_string(),
+        ]);
+    }
+
+    for idx in 1..4 {
+        if let Some(column) = table.column_mut(idx) {
+            column.set_cell_alignment(CellAlignment::Right);
+        }
+    }
+
+    println!("{table}");
+    Ok(())
+}
+
+pub fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        word.to_string()
+    } else {
+        format!("{word}s")
+    }
+}
@@ -0,0 +1,38 @@
+use clap::Parser;
+use fiat_lux::{Gloss, Text, Translation};
+
+use crate::{Args, Error, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct DefineArgs {
+    word: String,
+
+    /// maximum number of verses to list
+    #[clap(short, long, default_value_t = 10)]
+    limit: usize,
+}
+
+pub fn define(define_args: &DefineArgs, args: &Args) -> Result<()> {
+    let gloss = Gloss::find(&define_args.word)
+        .ok_or_else(|| Error::UnknownWord(define_args.word.clone()))?;
+
+    println!("{gloss}");
+
+    // The glossary is the KJV's, so that's where the examples come from, whatever translation is
+    // selected.
+    let bible = crate::open_bible()?;
+    let mut texts: Vec<Text> = Vec::new();
+    for form in gloss.forms() {
+        texts.extend(bible.search(&format!("\"{form}\""), Translation::Kjv, define_args.limit)?);
+    }
+    texts.sort();
+    texts.dedup();
+    texts.truncate(define_args.limit);
+
+    if !texts.is_empty() {
+        println!();
+        crate::format_texts(&texts, args);
+    }
+
+    Ok(())
+}
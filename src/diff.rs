@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use clap::Parser;
+use comfy_table::{Attribute, Cell, CellAlignment, ContentArrangement, Table};
+use crossterm::style::Stylize;
+use fiat_lux::{Book, Change, PartialLocation, Translation};
+
+use crate::Result;
+
+#[derive(Clone, Debug, Parser)]
+pub struct DiffArgs {
+    book: Book,
+    location: Option<PartialLocation>,
+
+    /// the two translations to compare
+    #[clap(short, long, value_delimiter = ',', num_args = 2, default_values_t = [Translation::Kjv, Translation::Asv])]
+    translations: Vec<Translation>,
+}
+
+pub fn diff(args: &DiffArgs) -> Result<()> {
+    let (left, right) = (args.translations[0], args.translations[1]);
+    let bible = crate::open_bible()?;
+
+    let mut rows: BTreeMap<(Book, u16, u16), (String, String)> = BTreeMap::new();
+    for text in bible.lookup(args.book, args.location, left)? {
+        rows.entry((text.book, text.chapter, text.verse))
+            .or_default()
+            .0 = text.content;
+    }
+    for text in bible.lookup(args.book, args.location, right)? {
+        rows.entry((text.book, text.chapter, text.verse))
+            .or_default()
+            .1 = text.content;
+    }
+
+    let width = terminal_size::terminal_size().map_or(100, |(terminal_size::Width(w), _)| w);
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::DynamicFullWidth);
+    table.load_preset(comfy_table::presets::NOTHING);
+    table.set_width(width);
+    table.set_header(vec![
+        Cell::new(""),
+        Cell::new(left).add_attribute(Attribute::Bold),
+        Cell::new(right).add_attribute(Attribute::Bold),
+    ]);
+
+    let mut current = None;
+    for ((book, chapter, verse), (a, b)) in &rows {
+        if current != Some((*book, *chapter)) {
+            current = Some((*book, *chapter));
+            table.add_row(vec![
+                Cell::new(""),
+                Cell::new(format!("\n{book} {chapter}")).add_attribute(Attribute::Bold),
+            ]);
+        }
+
+        let (a, b) = highlight(a, b);
+        table.add_row(vec![format!("{verse:4}"), a, b]);
+    }
+
+    table
+        .column_mut(0)
+        .unwrap()
+        .set_cell_alignment(CellAlignment::Right);
+
+    println!("{table}");
+    Ok(())
+}
+
+/// Render both sides of a diff: words only on the left in red, words only on the right in green.
+fn highlight(left: &str, right: &str) -> (String, String) {
+    let mut a = Vec::new();
+    let mut b = Vec::new();
+
+    for change in fiat_lux::diff_words(left, right) {
+        match change {
+            Change::Same(word) => {
+                a.push(word.to_string());
+                b.push(word.to_string());
+            }
+            Change::Removed(word) => a.push(word.red().bold().to_string()),
+            Change::Added(word) => b.push(word.green().bold().to_string()),
+        }
+    }
+
+    (a.join(" "), b.join(" "))
+}
@@ -0,0 +1,372 @@
+//! `flux doctor`: whether everything flux leans on is where it should be, and what to do about it
+//! where it isn't.
+
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    path::Path,
+};
+
+use crossterm::style::Stylize;
+use fiat_lux::Translation;
+
+use crate::{config, Error, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Status {
+    Ok,
+    /// Works, but not as well as it could.
+    Warn,
+    /// Doesn't work.
+    Fail,
+}
+
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warn,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+pub fn doctor() -> Result<()> {
+    let config = config::Config::load();
+    let mut checks = vec![check_config(&config)];
+    let config = config.unwrap_or_default();
+
+    let data_dir = config::data_dir();
+    checks.push(check_data_dir(data_dir.as_deref()));
+    if let Some(data_dir) = &data_dir {
+        checks.push(check_index(data_dir, &config));
+    }
+    checks.push(check_pager(&config));
+    checks.extend(check_terminal(&config));
+    checks.extend(check_translations(data_dir.as_deref(), &config));
+
+    for check in &checks {
+        let status = match check.status {
+            Status::Ok => "ok".green(),
+            Status::Warn => "warn".yellow(),
+            Status::Fail => "FAIL".red().bold(),
+        };
+        println!("{status:<4}  {:<14}{}", check.name, check.detail);
+        if let Some(fix) = &check.fix {
+            println!("{:20}{fix}", "");
+        }
+    }
+
+    match checks
+        .iter()
+        .filter(|check| check.status == Status::Fail)
+        .count()
+    {
+        0 => Ok(()),
+        failed => Err(Error::Unhealthy(failed)),
+    }
+}
+
+fn check_config(config: &Result<config::Config>) -> Check {
+    let path = config::config_path();
+    let shown = path
+        .as_deref()
+        .map_or(String::from("nowhere to look for one"), |path| {
+            path.display().to_string()
+        });
+
+    match config {
+        Err(e) => Check::fail("config", e.to_string(), format!("fix or remove {shown}")),
+        Ok(_) if !path.as_deref().is_some_and(Path::is_file) => {
+            Check::ok("config", format!("none at {shown}; using the defaults"))
+        }
+        Ok(config) => {
+            if let Err(e) = config.index.options(config.lean_index) {
+                return Check::fail("config", e.to_string(), format!("fix [index] in {shown}"));
+            }
+            if config.backend == Some(config::Backend::Sqlite) && !cfg!(feature = "sqlite") {
+                return Check::fail(
+                    "config",
+                    Error::NoSqlite.to_string(),
+                    format!("take `backend = \"sqlite\"` out of {shown}, or rebuild"),
+                );
+            }
+            Check::ok("config", shown)
+        }
+    }
+}
+
+fn check_data_dir(data_dir: Option<&Path>) -> Check {
+    let Some(data_dir) = data_dir else {
+        return Check::fail(
+            "data dir",
+            "there's no home directory to keep one in",
+            "set data_dir in the config or FIAT_LUX_DATA_DIR, or pass --no-persist",
+        );
+    };
+
+    // Writing something is the only sure way to know it can be written.
+    let probe = data_dir.join(".doctor");
+    let writable = fs::create_dir_all(data_dir)
+        .and_then(|()| fs::write(&probe, b""))
+        .and_then(|()| fs::remove_file(&probe));
+    match writable {
+        Ok(()) => Check::ok("data dir", data_dir.display().to_string()),
+        Err(e) => Check::fail(
+            "data dir",
+            format!("{} isn't writable: {e}", data_dir.display()),
+            "fix its permissions, point data_dir in the config somewhere else, or pass \
+             --no-persist to keep the index in memory",
+        ),
+    }
+}
+
+#[cfg(feature = "index")]
+fn check_index(data_dir: &Path, config: &config::Config) -> Check {
+    if config.backend == Some(config::Backend::Sqlite) {
+        let path = data_dir.join("bible.sqlite");
+        return match path.is_file() {
+            true => Check::ok("index", format!("sqlite, {}", path.display())),
+            false => Check::ok(
+                "index",
+                "sqlite, not built yet; it's built when first needed",
+            ),
+        };
+    }
+
+    match fiat_lux::IndexStatus::read(data_dir) {
+        Err(e) => Check::fail("index", e.to_string(), "flux index rebuild"),
+        Ok(None) => Check::ok(
+            "index",
+            "not built yet; it's built the first time it's needed",
+        ),
+        Ok(Some(status)) if !status.up_to_date => Check::warn(
+            "index",
+            "out of date; it's rebuilt the next time it's opened",
+            "flux index rebuild, to get it over with",
+        ),
+        Ok(Some(status)) => {
+            let missing: Vec<_> = Translation::ALL
+                .into_iter()
+                .filter(|translation| {
+                    !status
+                        .translations
+                        .iter()
+                        .any(|(indexed, _)| indexed == translation)
+                })
+                .collect();
+            match missing.first() {
+                Some(translation) => Check::warn(
+                    "index",
+                    format!("{translation} isn't indexed, so searches in it find nothing"),
+                    format!(
+                        "flux index reindex-translation {}",
+                        translation.to_string().to_lowercase()
+                    ),
+                ),
+                None => Check::ok(
+                    "index",
+                    format!(
+                        "{} verses, {:.1} MB",
+                        status.documents,
+                        status.bytes as f64 / 0x100000 as f64
+                    ),
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "index"))]
+fn check_index(_data_dir: &Path, _config: &config::Config) -> Check {
+    Check::ok("index", "not in this build; searches scan the text")
+}
+
+#[cfg(feature = "pager")]
+fn check_pager(config: &config::Config) -> Check {
+    let asked = config.pager.as_deref();
+    if asked.is_some_and(|command| command.trim() == "none") {
+        return Check::ok("pager", "off");
+    }
+
+    match crate::paging::choose(asked) {
+        None => Check::warn(
+            "pager",
+            "none of $PAGER, bat or less is installed, so long passages aren't paged",
+            "install bat or less, or set pager in the config to one you have",
+        ),
+        Some(command) => match asked {
+            Some(asked) if asked != command => Check::warn(
+                "pager",
+                format!("{asked} isn't installed, so {command} is used instead"),
+                "install it, or set pager in the config to one you have",
+            ),
+            _ => Check::ok("pager", command),
+        },
+    }
+}
+
+#[cfg(not(feature = "pager"))]
+fn check_pager(_config: &config::Config) -> Check {
+    Check::ok("pager", "not in this build")
+}
+
+fn check_terminal(config: &config::Config) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    checks.push(if !io::stdout().is_terminal() {
+        Check::ok(
+            "terminal",
+            "not a terminal; output is plain, and never paged",
+        )
+    } else {
+        match terminal_size::terminal_size() {
+            Some((terminal_size::Width(w), terminal_size::Height(h))) => {
+                Check::ok("terminal", format!("{w}x{h}"))
+            }
+            None => Check::warn(
+                "terminal",
+                "can't tell how big the terminal is, so tables are 100 columns",
+                "set width in the config to fit",
+            ),
+        }
+    });
+
+    let dumb = std::env::var("TERM").is_ok_and(|term| term == "dumb");
+    checks.push(match config.color {
+        Some(false) => Check::ok("color", "off, by NO_COLOR or the config"),
+        _ if dumb => Check::warn(
+            "color",
+            "TERM is dumb, so colors may come out as escape codes",
+            "set color = false in the config, or NO_COLOR=1",
+        ),
+        _ => Check::ok("color", "on"),
+    });
+
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()));
+    let utf8 = |locale: &str| {
+        let locale = locale.to_lowercase();
+        locale.contains("utf-8") || locale.contains("utf8")
+    };
+    checks.push(match locale {
+        // Windows terminals don't set a locale, and cope with Unicode anyway.
+        None if cfg!(windows) => Check::ok("unicode", "assumed"),
+        Some(locale) if utf8(&locale) => Check::ok("unicode", locale),
+        locale => Check::warn(
+            "unicode",
+            format!(
+                "the locale ({}) isn't UTF-8, so marks like ✎ and † may not show",
+                locale.as_deref().unwrap_or("unset")
+            ),
+            "set LANG to a UTF-8 locale, e.g. en_US.UTF-8",
+        ),
+    });
+
+    checks
+}
+
+fn check_translations(data_dir: Option<&Path>, config: &config::Config) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let built_in: Vec<_> = Translation::ALL.iter().map(ToString::to_string).collect();
+    checks.push(Check::ok("built in", built_in.join(", ")));
+
+    let added = match data_dir.map(fiat_lux::added_translations).transpose() {
+        Ok(added) => added.unwrap_or_default(),
+        Err(e) => {
+            checks.push(Check::fail(
+                "added",
+                format!("can't read them: {e}"),
+                "check the permissions on the translations directory in the data dir",
+            ));
+            Vec::new()
+        }
+    };
+    for (translation, verses) in added {
+        let name = translation.to_string().to_lowercase();
+        checks.push(match verses {
+            0 => Check::fail(
+                "added",
+                format!("{translation} has no verses"),
+                format!(
+                    "flux translation add {name} <dat file>, or flux translation remove {name}"
+                ),
+            ),
+            verses => Check::ok("added", format!("{translation}, {verses} verses")),
+        });
+    }
+
+    #[cfg(feature = "sword")]
+    {
+        let libraries: Vec<_> = config
+            .sword
+            .libraries()
+            .into_iter()
+            .filter(|library| library.join("mods.d").is_dir())
+            .collect();
+        checks.push(match libraries.is_empty() {
+            true => Check::ok("sword", "no libraries found"),
+            false => {
+                let shown: Vec<_> = libraries
+                    .iter()
+                    .map(|library| library.display().to_string())
+                    .collect();
+                Check::ok("sword", shown.join(", "))
+            }
+        });
+    }
+
+    #[cfg(feature = "remote")]
+    {
+        let remote = &config.remote;
+        checks.push(match remote.esv_key {
+            Some(_) => Check::ok("ESV", "api key set"),
+            None => Check::ok("ESV", "no api key; set esv_key under [remote] to read it"),
+        });
+        checks.push(match (&remote.api_bible_key, &remote.niv_bible_id) {
+            (Some(_), Some(_)) => Check::ok("NIV", "api key and bible id set"),
+            (None, None) => Check::ok(
+                "NIV",
+                "no api key; set api_bible_key and niv_bible_id under [remote] to read it",
+            ),
+            _ => Check::warn(
+                "NIV",
+                "only one of api_bible_key and niv_bible_id is set",
+                "set both under [remote]",
+            ),
+        });
+    }
+
+    #[cfg(not(feature = "sword"))]
+    let _ = config;
+
+    checks
+}
@@ -0,0 +1,362 @@
+//! EPUB books for e-readers: a zip holding an xhtml file per book of the Bible, a table of contents
+//! (both the EPUB 3 nav page and the older ncx, which some readers still want) and the package
+//! file tying them together.
+
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+};
+
+use fiat_lux::{Date, Text, Translation};
+use flate2::{write::DeflateEncoder, Compression, Crc};
+
+use crate::format::{chapters, group_reference, isolate};
+
+/// What goes in the book, a Bible book at a time, and how it's titled.
+pub struct Epub<'a> {
+    /// The passage, or the whole Bible, without the translation, which is added.
+    pub title: String,
+    pub translation: Translation,
+    /// Each Bible book's verses, in order.
+    pub books: Vec<&'a [Text]>,
+    /// Whether these are whole chapters, headed by number, or a few verses headed by reference.
+    pub whole_chapters: bool,
+}
+
+impl Epub<'_> {
+    /// Write the whole thing as a zip, with `verse_number` for the markup before each verse.
+    pub fn write(&self, out: impl Write, verse_number: impl Fn(u16) -> String) -> io::Result<()> {
+        let mut zip = Zip::new(out);
+        // The mimetype has to come first, uncompressed, so the file can be recognized by its
+        // opening bytes.
+        zip.add("mimetype", b"application/epub+zip", false)?;
+        zip.add("META-INF/container.xml", CONTAINER.as_bytes(), true)?;
+        zip.add("OEBPS/content.opf", self.package().as_bytes(), true)?;
+        zip.add("OEBPS/nav.xhtml", self.nav().as_bytes(), true)?;
+        zip.add("OEBPS/toc.ncx", self.ncx().as_bytes(), true)?;
+        zip.add("OEBPS/style.css", STYLE.as_bytes(), true)?;
+        for texts in &self.books {
+            let name = format!("OEBPS/{}.xhtml", file_id(texts));
+            zip.add(&name, self.book(texts, &verse_number).as_bytes(), true)?;
+        }
+        zip.finish()
+    }
+
+    fn title(&self) -> String {
+        escape(&format!("{} ({})", self.title, self.translation))
+    }
+
+    /// Something unique to this passage in this translation, like urn:fiat-lux:kjv:john-3-16.
+    fn identifier(&self) -> String {
+        let lower = self.title.to_lowercase();
+        let words: Vec<_> = lower
+            .split(|u: char| !u.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+        format!(
+            "urn:fiat-lux:{}:{}",
+            self.translation.to_string().to_lowercase(),
+            words.join("-")
+        )
+    }
+
+    fn package(&self) -> String {
+        let Date { year, month, day } = Date::today();
+        let mut manifest = String::new();
+        let mut spine = String::new();
+        for texts in &self.books {
+            let id = file_id(texts);
+            writeln!(
+                manifest,
+                r#"    <item id="{id}" href="{id}.xhtml" media-type="application/xhtml+xml"/>"#
+            )
+            .unwrap();
+            writeln!(spine, r#"    <itemref idref="{id}"/>"#).unwrap();
+        }
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="id">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="id">{identifier}</dc:identifier>
+    <dc:title>{title}</dc:title>
+    <dc:language>en</dc:language>
+    <meta property="dcterms:modified">{year:04}-{month:02}-{day:02}T00:00:00Z</meta>
+  </metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="ncx" href="toc.ncx" media-type="application/x-dtbncx+xml"/>
+    <item id="style" href="style.css" media-type="text/css"/>
+{manifest}  </manifest>
+  <spine toc="ncx">
+    <itemref idref="nav"/>
+{spine}  </spine>
+</package>
+"#,
+            identifier = self.identifier(),
+            title = self.title(),
+        )
+    }
+
+    /// The table of contents: each book, and under it each chapter.
+    fn nav(&self) -> String {
+        let mut items = String::new();
+        for texts in &self.books {
+            let id = file_id(texts);
+            writeln!(
+                items,
+                r#"      <li><a href="{id}.xhtml">{}</a>"#,
+                texts[0].book
+            )
+            .unwrap();
+            if self.whole_chapters && texts[0].book.chapters() > 1 {
+                items.push_str("        <ol>\n");
+                for chapter in chapters(texts) {
+                    let number = chapter[0].chapter;
+                    writeln!(
+                        items,
+                        r#"          <li><a href="{id}.xhtml#c{number}">{number}</a></li>"#
+                    )
+                    .unwrap();
+                }
+                items.push_str("        </ol>\n");
+            }
+            items.push_str("      </li>\n");
+        }
+
+        format!(
+            r#"{XHTML_START}
+<head>
+  <title>Contents</title>
+  <link rel="stylesheet" type="text/css" href="style.css"/>
+</head>
+<body>
+  <h1>{title}</h1>
+  <nav epub:type="toc" id="toc">
+    <h2>Contents</h2>
+    <ol>
+{items}    </ol>
+  </nav>
+</body>
+</html>
+"#,
+            title = self.title(),
+        )
+    }
+
+    /// The EPUB 2 table of contents, by book.
+    fn ncx(&self) -> String {
+        let mut points = String::new();
+        for (idx, texts) in self.books.iter().enumerate() {
+            let id = file_id(texts);
+            writeln!(
+                points,
+                r#"    <navPoint id="nav-{id}" playOrder="{}">
+      <navLabel><text>{}</text></navLabel>
+      <content src="{id}.xhtml"/>
+    </navPoint>"#,
+                idx + 1,
+                texts[0].book,
+            )
+            .unwrap();
+        }
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<ncx xmlns="http://www.daisy.org/z3986/2005/ncx/" version="2005-1">
+  <head>
+    <meta name="dtb:uid" content="{identifier}"/>
+  </head>
+  <docTitle><text>{title}</text></docTitle>
+  <navMap>
+{points}  </navMap>
+</ncx>
+"#,
+            identifier = self.identifier(),
+            title = self.title(),
+        )
+    }
+
+    fn book(&self, texts: &[Text], verse_number: &impl Fn(u16) -> String) -> String {
+        let mut body = String::new();
+        let book = texts[0].book;
+        writeln!(body, "  <h1>{book}</h1>").unwrap();
+        for chapter in chapters(texts) {
+            let number = chapter[0].chapter;
+            if !self.whole_chapters {
+                writeln!(body, "  <h2>{}</h2>", escape(&group_reference(chapter))).unwrap();
+            } else if book.chapters() > 1 {
+                writeln!(body, r#"  <h2 id="c{number}">Chapter {number}</h2>"#).unwrap();
+            }
+            for text in chapter {
+                let number = verse_number(text.verse);
+                let space = if number.is_empty() { "" } else { " " };
+                writeln!(
+                    body,
+                    "  <p>{number}{space}{}</p>",
+                    escape(&isolate(&text.content))
+                )
+                .unwrap();
+            }
+        }
+
+        format!(
+            r#"{XHTML_START}
+<head>
+  <title>{book}</title>
+  <link rel="stylesheet" type="text/css" href="style.css"/>
+</head>
+<body>
+<section epub:type="chapter">
+{body}</section>
+</body>
+</html>
+"#
+        )
+    }
+}
+
+const XHTML_START: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" lang="en" xml:lang="en">"#;
+
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+const STYLE: &str = "h1, h2 { text-align: center; }\n\
+                     p { margin: 0 0 0.4em; text-indent: 0; }\n\
+                     sup { font-size: 0.6em; }\n";
+
+/// The name of a book's file, and its id in the package: b01 for Genesis through b66.
+fn file_id(texts: &[Text]) -> String {
+    format!("b{:02}", texts[0].book as u8)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Just enough of a zip writer for an EPUB: files stored or deflated, one after another, then
+/// the central directory listing them.
+struct Zip<W> {
+    out: W,
+    offset: u32,
+    entries: Vec<u8>,
+    count: u16,
+    date: u16,
+}
+
+impl<W: Write> Zip<W> {
+    fn new(out: W) -> Self {
+        let today = Date::today();
+        // DOS dates count years from 1980.
+        let date = ((today.year - 1980).max(0) as u16) << 9
+            | u16::from(today.month) << 5
+            | u16::from(today.day);
+        Self {
+            out,
+            offset: 0,
+            entries: Vec::new(),
+            count: 0,
+            date,
+        }
+    }
+
+    fn add(&mut self, name: &str, data: &[u8], deflate: bool) -> io::Result<()> {
+        let mut crc = Crc::new();
+        crc.update(data);
+        let (method, body) = if deflate {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+            encoder.write_all(data)?;
+            (8u16, encoder.finish()?)
+        } else {
+            (0u16, data.to_vec())
+        };
+
+        // Fields common to the local header and the central directory's entry: version needed,
+        // flags, method, time, date, crc, sizes and the name's length.
+        let mut common = Vec::new();
+        common.extend(20u16.to_le_bytes());
+        common.extend(0u16.to_le_bytes());
+        common.extend(method.to_le_bytes());
+        common.extend(0u16.to_le_bytes());
+        common.extend(self.date.to_le_bytes());
+        common.extend(crc.sum().to_le_bytes());
+        common.extend((body.len() as u32).to_le_bytes());
+        common.extend((data.len() as u32).to_le_bytes());
+        common.extend((name.len() as u16).to_le_bytes());
+        common.extend(0u16.to_le_bytes());
+
+        let mut header = 0x0403_4b50u32.to_le_bytes().to_vec();
+        header.extend(&common);
+        header.extend(name.as_bytes());
+        self.out.write_all(&header)?;
+        self.out.write_all(&body)?;
+
+        self.entries.extend(0x0201_4b50u32.to_le_bytes());
+        self.entries.extend(20u16.to_le_bytes());
+        self.entries.extend(&common);
+        // No comment, disk 0, no attributes, then where the local header is.
+        self.entries.extend([0; 10]);
+        self.entries.extend(self.offset.to_le_bytes());
+        self.entries.extend(name.as_bytes());
+
+        self.offset += (header.len() + body.len()) as u32;
+        self.count += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        self.out.write_all(&self.entries)?;
+        let mut end = 0x0605_4b50u32.to_le_bytes().to_vec();
+        end.extend([0; 4]);
+        end.extend(self.count.to_le_bytes());
+        end.extend(self.count.to_le_bytes());
+        end.extend((self.entries.len() as u32).to_le_bytes());
+        end.extend(self.offset.to_le_bytes());
+        end.extend(0u16.to_le_bytes());
+        self.out.write_all(&end)?;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fiat_lux::{Book, Text, Translation};
+
+    use super::Epub;
+
+    #[test]
+    fn container() {
+        let texts = [Text {
+            book: Book::John,
+            chapter: 11,
+            verse: 35,
+            content: String::from("Jesus wept."),
+        }];
+        let epub = Epub {
+            title: String::from("John 11:35"),
+            translation: Translation::Kjv,
+            books: vec![&texts],
+            whole_chapters: false,
+        };
+        assert_eq!("urn:fiat-lux:kjv:john-11-35", epub.identifier());
+
+        let mut buf = Vec::new();
+        epub.write(&mut buf, |verse| verse.to_string()).unwrap();
+        // A stored mimetype first, then the central directory's end at the very end.
+        assert_eq!(b"PK\x03\x04", &buf[..4]);
+        assert_eq!(b"mimetypeapplication/epub+zip", &buf[30..58]);
+        let end = &buf[buf.len() - 22..];
+        assert_eq!(b"PK\x05\x06", &end[..4]);
+        assert_eq!(7, u16::from_le_bytes([end[10], end[11]]));
+    }
+}
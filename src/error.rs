@@ -1,18 +1,4 @@
-use std::io;
-
-pub trait AbbrevStr: AsRef<str> + Into<String> {
-    fn get(self, limit: usize) -> String {
-        let full = self.as_ref();
-
-        if full.len() > limit {
-            full[..limit].to_string() + "..."
-        } else {
-            self.into()
-        }
-    }
-}
-
-impl<T: AsRef<str> + Into<String>> AbbrevStr for T {}
+use std::{io, path::PathBuf};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -20,14 +6,113 @@ pub enum Error {
     IO(#[from] io::Error),
 
     #[error(transparent)]
-    Tantivy(#[from] tantivy::error::TantivyError),
+    Library(#[from] fiat_lux::Error),
+
+    #[error(transparent)]
+    Passage(#[from] fiat_lux::ParsePassageError),
+
+    #[error(transparent)]
+    Translation(#[from] fiat_lux::ParseTranslationError),
+
+    #[error("bad config file: {0}")]
+    Config(#[from] toml::de::Error),
+
+    #[error("bad {0}: {1}")]
+    Env(&'static str, String),
+
+    #[error("'{0}' can't be a profile name")]
+    BadProfile(String),
 
     #[error(transparent)]
-    TantivyDir(#[from] tantivy::directory::error::OpenDirectoryError),
+    Qr(#[from] qrcode::types::QrError),
 
     #[error(transparent)]
-    TantivyRead(#[from] tantivy::directory::error::OpenReadError),
+    Image(#[from] image::ImageError),
 
     #[error(transparent)]
-    TantivyQuery(#[from] tantivy::query::QueryParserError),
+    Readline(#[from] rustyline::error::ReadlineError),
+
+    #[error("{}: {source}", path.display())]
+    Usfm {
+        path: PathBuf,
+        source: fiat_lux::ParseUsfmError,
+    },
+
+    #[error("can't tell what to write to {}; give it a .json or .csv extension", .0.display())]
+    UnknownExport(PathBuf),
+
+    #[error("no .usfm files found in {}", .0.display())]
+    NoUsfm(PathBuf),
+
+    #[error("unknown place '{0}'")]
+    UnknownPlace(String),
+
+    #[error("no topic called '{0}'; `flux random --topics` lists them")]
+    UnknownTopic(String),
+
+    #[error("nothing on that topic in {0} words or fewer")]
+    NothingShortEnough(usize),
+
+    #[error("'{0}' isn't in the glossary")]
+    UnknownWord(String),
+
+    #[error("'{0}' isn't in the genealogy")]
+    UnknownPerson(String),
+
+    #[error("no ancient unit found in '{0}'")]
+    UnknownUnit(String),
+
+    #[error("{0} has no chapter {1}")]
+    UnknownChapter(fiat_lux::Book, u16),
+
+    #[error("no reading plan called '{0}'; `flux plan list` shows them all")]
+    UnknownPlan(String),
+
+    #[error("no reading plan started; try `flux plan start year`")]
+    NoPlan,
+
+    #[error("bad index setting: {0}")]
+    IndexSetting(String),
+
+    #[error("this build has no sqlite backend; it needs the `sqlite` feature")]
+    NoSqlite,
+
+    #[error("{0} of the checks failed")]
+    Unhealthy(usize),
+
+    #[error("the quotation differs from the text in {0} places")]
+    Misquoted(usize),
+
+    #[error("{0} of the quotations don't match the text")]
+    Misquotations(usize),
+
+    #[error("couldn't open {0}")]
+    Open(String),
+
+    #[error("no {0} to open links with; --ref prints the link instead")]
+    NoOpener(&'static str),
+
+    #[error("nothing looked up yet")]
+    NoHistory,
+
+    #[error("there's nothing after {0}")]
+    NothingAfter(fiat_lux::Passage),
+
+    #[error("there's nothing before {0}")]
+    NothingBefore(fiat_lux::Passage),
+
+    #[cfg(feature = "audio")]
+    #[error("no recordings configured for {0}; add a url under [audio.urls] in the config file")]
+    NoAudio(fiat_lux::Translation),
+
+    #[cfg(feature = "audio")]
+    #[error("unable to play audio: {0}")]
+    Audio(String),
+}
+
+#[cfg(feature = "audio")]
+impl Error {
+    pub fn audio(e: impl std::fmt::Display) -> Self {
+        Error::Audio(e.to_string())
+    }
 }
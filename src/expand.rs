@@ -0,0 +1,95 @@
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+};
+
+use clap::Parser;
+use fiat_lux::{Passage, Translation};
+
+use crate::{format::quote_passage, Args, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct ExpandArgs {
+    /// what each reference becomes, with {text}, {reference} and {translation} filled in
+    /// (default: "{text}")
+    #[clap(long)]
+    template: Option<String>,
+}
+
+/// Copy stdin to stdout, swapping each `{{reference}}` for the passage, laid out by the template.
+pub fn expand(expand_args: &ExpandArgs, translation: Translation, args: &Args) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let template = expand_args
+        .template
+        .as_deref()
+        .or(args.config.expand.template.as_deref())
+        .unwrap_or("{text}");
+
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    let mut seen: HashMap<String, Option<String>> = HashMap::new();
+    let output = expand_text(&input, |reference| {
+        seen.entry(reference.to_string())
+            .or_insert_with(|| {
+                // A bad reference is left as it is, so it's easy to spot in what comes out.
+                let passage: Passage = match reference.parse() {
+                    Ok(passage) => passage,
+                    Err(e) => {
+                        eprintln!("{reference}: {e}; leaving it be");
+                        return None;
+                    }
+                };
+                let (text, reference) = quote_passage(&bible, passage, translation)?;
+                Some(
+                    template
+                        .replace("{text}", &text)
+                        .replace("{reference}", &reference)
+                        .replace("{translation}", &translation.to_string()),
+                )
+            })
+            .clone()
+    });
+
+    io::stdout().lock().write_all(output.as_bytes())?;
+    Ok(())
+}
+
+/// Replace each `{{...}}` in `text` with whatever `lookup` makes of what's between the braces,
+/// or leave it where `lookup` has nothing.
+pub(crate) fn expand_text(text: &str, mut lookup: impl FnMut(&str) -> Option<String>) -> String {
+    let mut buf = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+
+        let token = &rest[start..start + 2 + len + 2];
+        buf.push_str(&rest[..start]);
+        match lookup(token[2..token.len() - 2].trim()) {
+            Some(expanded) => buf.push_str(&expanded),
+            None => buf.push_str(token),
+        }
+        rest = &rest[start + token.len()..];
+    }
+    buf.push_str(rest);
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand_text;
+
+    #[test]
+    fn expanding() {
+        let text = "Remember {{ John 11:35 }}, and {{nowhere 1:1}}. {{unclosed";
+        let expanded = expand_text(text, |reference| {
+            (reference == "John 11:35").then(|| String::from("Jesus wept."))
+        });
+        assert_eq!(
+            "Remember Jesus wept., and {{nowhere 1:1}}. {{unclosed",
+            expanded
+        );
+    }
+}
@@ -0,0 +1,553 @@
+use std::{
+    fmt::Write as _,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use fiat_lux::{Book, PartialLocation, Passage, Text, Translation};
+use serde_json::json;
+
+use crate::{
+    epub::Epub,
+    format::{chapters, group_reference, isolate, Numerals, VerseNumbers},
+    Error, Result,
+};
+
+#[derive(Clone, Debug, Parser)]
+#[clap(args_conflicts_with_subcommands(true))]
+pub struct ExportArgs {
+    #[clap(subcommand)]
+    command: Option<ExportCommand>,
+
+    #[clap(flatten)]
+    files: FilesArgs,
+}
+
+/// Plain passages written out to a file per chapter, or all to one.
+#[derive(Clone, Debug, Parser)]
+struct FilesArgs {
+    /// the book to export (default: the whole translation)
+    book: Option<Book>,
+    location: Option<PartialLocation>,
+
+    /// what to write
+    #[clap(long, value_enum, default_value_t)]
+    to: FileFormat,
+
+    /// how verse numbers are set off from the text
+    #[clap(long, value_enum, default_value_t)]
+    verse_numbers: VerseNumbers,
+
+    /// write everything to one file instead of a file per chapter
+    #[clap(long)]
+    single: bool,
+
+    /// a directory for the chapters, or with --single or --to epub the file (default: stdout)
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+enum FileFormat {
+    #[default]
+    Md,
+    Txt,
+    Html,
+    Json,
+
+    /// an e-book, with a chapter per book of the bible
+    Epub,
+}
+
+impl FileFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            FileFormat::Md => "md",
+            FileFormat::Txt => "txt",
+            FileFormat::Html => "html",
+            FileFormat::Json => "json",
+            FileFormat::Epub => "epub",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum ExportCommand {
+    /// markdown slides for marp or reveal.js
+    Slides(SlidesArgs),
+
+    /// an opensong scripture set (xml)
+    #[clap(name = "opensong")]
+    OpenSong(SlidesArgs),
+
+    /// plain text slides for import into propresenter
+    #[clap(name = "propresenter")]
+    ProPresenter(SlidesArgs),
+}
+
+#[derive(Clone, Debug, Parser)]
+struct SlidesArgs {
+    book: Book,
+    location: Option<PartialLocation>,
+
+    /// verses per slide
+    #[clap(short, long, default_value_t = 1)]
+    verses: usize,
+
+    /// output file (default: stdout)
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+}
+
+/// `verse_numbers` is how they were asked to look everywhere, which beats `--verse-numbers`.
+pub fn export(
+    args: &ExportArgs,
+    translation: Translation,
+    numerals: Numerals,
+    verse_numbers: Option<VerseNumbers>,
+) -> Result<()> {
+    match &args.command {
+        Some(ExportCommand::Slides(args)) => slides(args, translation, numerals, verse_numbers),
+        Some(ExportCommand::OpenSong(args)) => open_song(args, translation),
+        Some(ExportCommand::ProPresenter(args)) => pro_presenter(args, translation),
+        None => files(&args.files, translation, numerals, verse_numbers),
+    }
+}
+
+fn files(
+    args: &FilesArgs,
+    translation: Translation,
+    numerals: Numerals,
+    verse_numbers: Option<VerseNumbers>,
+) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let texts = match args.book {
+        Some(book) => bible.lookup(book, args.location, translation)?,
+        None => {
+            let mut texts = Vec::new();
+            for book in (1..=66).map(Book::from_u8) {
+                texts.extend(bible.lookup(book, None, translation)?);
+            }
+            texts
+        }
+    };
+    let chapters: Vec<_> = chapters(&texts).collect();
+    let file = ChapterFile {
+        format: args.to,
+        verse_numbers: verse_numbers.unwrap_or(args.verse_numbers),
+        numerals,
+        translation,
+        // A verse or two gets its reference as a heading; a whole chapter just the chapter.
+        whole_chapters: args
+            .location
+            .is_none_or(|location| location.verse.is_none()),
+    };
+
+    let title = match (args.book, args.location) {
+        (None, _) => String::from("The Holy Bible"),
+        (Some(book), None) => book.to_string(),
+        (Some(book), location) => Passage { book, location }.to_string(),
+    };
+
+    if let FileFormat::Epub = args.to {
+        let epub = Epub {
+            title,
+            translation,
+            books: texts.chunk_by(|a, b| a.book == b.book).collect(),
+            whole_chapters: file.whole_chapters,
+        };
+        let mut out = open_output(args.output.as_deref())?;
+        epub.write(&mut out, |verse| file.verse_number(verse))?;
+        return Ok(());
+    }
+
+    let dir = match &args.output {
+        Some(dir) if !args.single => dir,
+        output => {
+            let mut out = open_output(output.as_deref())?;
+            out.write_all(file.render(&title, &chapters).as_bytes())?;
+            out.flush()?;
+            return Ok(());
+        }
+    };
+
+    fs::create_dir_all(dir)?;
+    for chapter in &chapters {
+        let (book, number) = (chapter[0].book, chapter[0].chapter);
+        let name = format!("{book} {number}.{}", args.to.extension());
+        fs::write(
+            dir.join(name),
+            file.render(&file.heading(chapter), &[chapter]),
+        )?;
+    }
+    eprintln!("wrote {} files to {}", chapters.len(), dir.display());
+    Ok(())
+}
+
+/// How to lay out the chapters going into one file.
+struct ChapterFile {
+    format: FileFormat,
+    verse_numbers: VerseNumbers,
+    numerals: Numerals,
+    translation: Translation,
+    whole_chapters: bool,
+}
+
+impl ChapterFile {
+    fn heading(&self, chapter: &[Text]) -> String {
+        if self.whole_chapters {
+            format!("{} {}", chapter[0].book, chapter[0].chapter)
+        } else {
+            group_reference(chapter)
+        }
+    }
+
+    /// A file titled `title`, with a heading of its own for each chapter if there's more than one.
+    fn render(&self, title: &str, chapters: &[&[Text]]) -> String {
+        let translation = self.translation;
+        if let FileFormat::Json = self.format {
+            let verses: Vec<_> = chapters
+                .iter()
+                .flat_map(|chapter| chapter.iter())
+                .map(|text| {
+                    json!({
+                        "book": text.book.to_string(),
+                        "chapter": text.chapter,
+                        "verse": text.verse,
+                        "text": text.content,
+                    })
+                })
+                .collect();
+            let document = json!({
+                "reference": title,
+                "translation": translation.to_string(),
+                "verses": verses,
+            });
+            return serde_json::to_string_pretty(&document).unwrap() + "\n";
+        }
+
+        let mut buf = String::new();
+        let subheadings = chapters.len() > 1;
+        match self.format {
+            FileFormat::Md => writeln!(buf, "# {title} ({translation})"),
+            FileFormat::Txt => writeln!(buf, "{title} ({translation})"),
+            _ => writeln!(
+                buf,
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+                 <title>{title} ({translation})</title>\n</head>\n<body>\n\
+                 <h1>{title} ({translation})</h1>",
+                title = escape_xml(title),
+            ),
+        }
+        .unwrap();
+
+        for chapter in chapters {
+            if subheadings {
+                let heading = self.heading(chapter);
+                match self.format {
+                    FileFormat::Md => write!(buf, "\n## {heading}\n"),
+                    FileFormat::Txt => write!(buf, "\n{heading}\n"),
+                    _ => writeln!(buf, "<h2>{}</h2>", escape_xml(&heading)),
+                }
+                .unwrap();
+            }
+
+            for text in *chapter {
+                let number = self.verse_number(text.verse);
+                let space = if number.is_empty() { "" } else { " " };
+                match self.format {
+                    FileFormat::Md => write!(buf, "\n{number}{space}{}\n", isolate(&text.content)),
+                    FileFormat::Txt => writeln!(buf, "{number}{space}{}", isolate(&text.content)),
+                    _ => writeln!(
+                        buf,
+                        "<p>{number}{space}{}</p>",
+                        escape_xml(&isolate(&text.content))
+                    ),
+                }
+                .unwrap();
+            }
+        }
+
+        if let FileFormat::Html = self.format {
+            buf.push_str("</body>\n</html>\n");
+        }
+        buf
+    }
+
+    fn verse_number(&self, verse: u16) -> String {
+        let html = matches!(self.format, FileFormat::Html | FileFormat::Epub);
+        match self.verse_numbers {
+            VerseNumbers::Superscript if html => {
+                format!("<sup>{}</sup>", self.numerals.format(verse))
+            }
+            numbers => numbers.format(self.numerals, verse),
+        }
+    }
+}
+
+impl SlidesArgs {
+    fn passage(&self) -> Passage {
+        Passage {
+            book: self.book,
+            location: self.location,
+        }
+    }
+}
+
+fn slides(
+    args: &SlidesArgs,
+    translation: Translation,
+    numerals: Numerals,
+    verse_numbers: Option<VerseNumbers>,
+) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let texts = bible.lookup(args.book, args.location, translation)?;
+    let mut out = open_output(args.output.as_deref())?;
+
+    // Marp and reveal.js both render html, so superscript numbers can be a real <sup>.
+    let verse_number = |verse| match verse_numbers.unwrap_or_default() {
+        VerseNumbers::Superscript => format!("<sup>{}</sup>", numerals.format(verse)),
+        numbers => numbers.format(numerals, verse),
+    };
+
+    // Marp wants its front matter; reveal.js ignores it, and both split slides on a bare ---.
+    writeln!(out, "---\nmarp: true\n---\n")?;
+    writeln!(out, "# {}", args.passage())?;
+    writeln!(out, "\n{translation}")?;
+
+    for group in texts.chunks(args.verses.max(1)) {
+        writeln!(out, "\n---\n")?;
+        writeln!(out, "## {}", group_reference(group))?;
+        for text in group {
+            let number = verse_number(text.verse);
+            writeln!(out)?;
+            if group.len() > 1 && !number.is_empty() {
+                write!(out, "{number} ")?;
+            }
+            writeln!(out, "{}", text.content)?;
+        }
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// OpenSong keeps scripture in a set containing a single "scripture" slide group.
+fn open_song(args: &SlidesArgs, translation: Translation) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let texts = bible.lookup(args.book, args.location, translation)?;
+    let mut out = open_output(args.output.as_deref())?;
+    let name = escape_xml(&args.passage().to_string());
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(out, r#"<set name="{name}">"#)?;
+    writeln!(out, "  <slide_groups>")?;
+    writeln!(
+        out,
+        r#"    <slide_group type="scripture" name="{name}" translation="{translation}" print="true">"#
+    )?;
+    writeln!(out, "      <title>{name}</title>")?;
+    writeln!(out, "      <slides>")?;
+
+    for group in texts.chunks(args.verses.max(1)) {
+        let body: Vec<_> = group
+            .iter()
+            .map(|text| format!("{} {}", text.verse, escape_xml(&text.content)))
+            .collect();
+        writeln!(out, "        <slide>")?;
+        writeln!(out, "          <body>{}</body>", body.join("\n"))?;
+        writeln!(out, "        </slide>")?;
+    }
+
+    writeln!(out, "      </slides>")?;
+    writeln!(out, "      <subtitle/>")?;
+    writeln!(out, "      <notes/>")?;
+    writeln!(out, "    </slide_group>")?;
+    writeln!(out, "  </slide_groups>")?;
+    writeln!(out, "</set>")?;
+
+    out.flush()?;
+    Ok(())
+}
+
+/// ProPresenter's text import makes a new slide at each blank line, so that's all this is.
+fn pro_presenter(args: &SlidesArgs, translation: Translation) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let texts = bible.lookup(args.book, args.location, translation)?;
+    let mut out = open_output(args.output.as_deref())?;
+
+    for (idx, group) in texts.chunks(args.verses.max(1)).enumerate() {
+        if idx > 0 {
+            writeln!(out)?;
+        }
+
+        for text in group {
+            writeln!(out, "{}", text.content)?;
+        }
+        writeln!(out, "{} ({translation})", group_reference(group))?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// Write every hit from `flux search --export` to `path`, as json or csv by its extension: the
+/// rank, score, reference and text of each, best first, for a notebook or spreadsheet to pick up.
+pub fn search_results(path: &Path, hits: &[(f32, Text)], translation: Translation) -> Result<()> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    let mut out = BufWriter::new(match extension.as_deref() {
+        Some("json" | "csv") => File::create(path)?,
+        _ => return Err(Error::UnknownExport(path.to_path_buf())),
+    });
+
+    if extension.as_deref() == Some("json") {
+        let hits: Vec<_> = hits
+            .iter()
+            .enumerate()
+            .map(|(idx, (score, text))| {
+                json!({
+                    "rank": idx + 1,
+                    "score": score,
+                    "reference": format!("{} {}:{}", text.book, text.chapter, text.verse),
+                    "book": text.book.to_string(),
+                    "chapter": text.chapter,
+                    "verse": text.verse,
+                    "translation": translation.to_string(),
+                    "text": text.content,
+                })
+            })
+            .collect();
+        serde_json::to_writer_pretty(&mut out, &hits).map_err(io::Error::other)?;
+        writeln!(out)?;
+    } else {
+        write_csv(&mut out, hits, translation)?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+fn write_csv(
+    out: &mut impl Write,
+    hits: &[(f32, Text)],
+    translation: Translation,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "rank,score,reference,book,chapter,verse,translation,text"
+    )?;
+    for (idx, (score, text)) in hits.iter().enumerate() {
+        writeln!(
+            out,
+            "{},{score},{},{},{},{},{},{}",
+            idx + 1,
+            csv_field(&format!("{} {}:{}", text.book, text.chapter, text.verse)),
+            csv_field(&text.book.to_string()),
+            text.chapter,
+            text.verse,
+            csv_field(&translation.to_string()),
+            csv_field(&text.content),
+        )?;
+    }
+    Ok(())
+}
+
+/// A field quoted if it needs to be, with any quotes inside doubled.
+pub fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len());
+    for u in s.chars() {
+        match u {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            '"' => buf.push_str("&quot;"),
+            '\'' => buf.push_str("&apos;"),
+            u => buf.push(u),
+        }
+    }
+    buf
+}
+
+/// A writer for the given path, or stdout if there isn't one.
+pub fn open_output(path: Option<&Path>) -> io::Result<Box<dyn Write>> {
+    Ok(match path {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(io::stdout().lock()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fiat_lux::{Book, Text, Translation};
+
+    use super::{write_csv, ChapterFile, FileFormat, VerseNumbers};
+    use crate::format::Numerals;
+
+    #[test]
+    fn csv_rows() {
+        let hits = [(
+            2.5,
+            Text {
+                book: Book::John,
+                chapter: 11,
+                verse: 35,
+                content: String::from("Jesus wept, \"and\" so on."),
+            },
+        )];
+        let mut buf = Vec::new();
+        write_csv(&mut buf, &hits, Translation::Kjv).unwrap();
+        assert_eq!(
+            "rank,score,reference,book,chapter,verse,translation,text\n\
+             1,2.5,John 11:35,John,11,35,KJV,\"Jesus wept, \"\"and\"\" so on.\"\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn chapter_files() {
+        let text = |chapter, verse, content: &str| Text {
+            book: Book::Ruth,
+            chapter,
+            verse,
+            content: content.to_string(),
+        };
+        let (one, two) = (
+            [
+                text(1, 1, "Now it came to pass"),
+                text(1, 2, "And the name"),
+            ],
+            [text(2, 1, "And Naomi")],
+        );
+        let mut file = ChapterFile {
+            format: FileFormat::Md,
+            verse_numbers: VerseNumbers::Superscript,
+            numerals: Numerals::Western,
+            translation: Translation::Kjv,
+            whole_chapters: true,
+        };
+        assert_eq!(
+            "# Ruth 1 (KJV)\n\n¹ Now it came to pass\n\n² And the name\n",
+            file.render("Ruth 1", &[&one])
+        );
+
+        file.format = FileFormat::Txt;
+        file.verse_numbers = VerseNumbers::Bracket;
+        assert_eq!(
+            "Ruth (KJV)\n\nRuth 1\n[1] Now it came to pass\n[2] And the name\n\nRuth 2\n[1] And Naomi\n",
+            file.render("Ruth", &[&one, &two])
+        );
+    }
+}
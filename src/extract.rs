@@ -0,0 +1,133 @@
+//! `flux extract`: the scripture references in a piece of prose, such as sermon notes, listed or
+//! with their text quoted under them.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Read, Write},
+    path::PathBuf,
+};
+
+use clap::Parser;
+use fiat_lux::{Passage, Translation};
+
+use crate::{format::quote_passage, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct ExtractArgs {
+    /// the file to read, or - for stdin
+    path: PathBuf,
+
+    /// copy the text out with each passage quoted under the line which refers to it
+    #[clap(long)]
+    expand: bool,
+}
+
+pub fn extract(extract_args: &ExtractArgs, translation: Translation) -> Result<()> {
+    let text = if extract_args.path.as_os_str() == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        fs::read_to_string(&extract_args.path)?
+    };
+
+    if !extract_args.expand {
+        let mut stdout = io::stdout().lock();
+        for (line, passage) in references(&text) {
+            writeln!(stdout, "{line:>5}  {passage}")?;
+        }
+        return Ok(());
+    }
+
+    let bible = crate::open_bible()?;
+    let mut seen: HashMap<String, Option<String>> = HashMap::new();
+    let output = quote_beneath(&text, |passage| {
+        seen.entry(passage.to_string())
+            .or_insert_with(|| {
+                let (text, reference) = quote_passage(&bible, passage, translation)?;
+                Some(format!("{text} ({reference}, {translation})"))
+            })
+            .clone()
+    });
+
+    io::stdout().lock().write_all(output.as_bytes())?;
+    Ok(())
+}
+
+/// Each reference in `text`, with the line it's on, counting from one.
+fn references(text: &str) -> Vec<(usize, Passage)> {
+    text.lines()
+        .enumerate()
+        .flat_map(|(idx, line)| {
+            Passage::find_all(line)
+                .into_iter()
+                .map(move |(_, passage)| (idx + 1, passage))
+        })
+        .collect()
+}
+
+/// Copy `text`, following each line which refers to a passage with a block quote of whatever
+/// `lookup` makes of it. A reference `lookup` has nothing for gets no quote.
+fn quote_beneath(text: &str, mut lookup: impl FnMut(Passage) -> Option<String>) -> String {
+    let mut buf = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        buf.push_str(line);
+        let quotes: Vec<_> = Passage::find_all(line)
+            .into_iter()
+            .filter_map(|(_, passage)| lookup(passage))
+            .collect();
+        if quotes.is_empty() {
+            continue;
+        }
+
+        // The last line mightn't end in a newline, and a quote can't share a line.
+        if !line.ends_with('\n') {
+            buf.push('\n');
+        }
+        for quote in quotes {
+            buf.push_str("> ");
+            buf.push_str(&quote);
+            buf.push('\n');
+        }
+    }
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{quote_beneath, references};
+
+    #[test]
+    fn extracting() {
+        let text = "Love is patient (1 Cor 13:4-7).\nNothing here.\nSee John 11:35 and Job 1";
+        let found: Vec<_> = references(text)
+            .into_iter()
+            .map(|(line, passage)| (line, passage.to_string()))
+            .collect();
+        assert_eq!(
+            vec![
+                (1, String::from("1 Corinthians 13:4-7")),
+                (3, String::from("John 11:35")),
+                (3, String::from("Job 1")),
+            ],
+            found
+        );
+
+        let expanded = quote_beneath(text, |passage| {
+            (passage.to_string() == "John 11:35").then(|| String::from("Jesus wept."))
+        });
+        assert_eq!(
+            "Love is patient (1 Cor 13:4-7).\nNothing here.\nSee John 11:35 and Job 1\n> Jesus wept.\n",
+            expanded
+        );
+
+        // Ordinary sentences which happen to hold something like a reference aren't quoted.
+        let prose = "I am 40 years old.\nThe shop is 3 miles away.\nAct 2 of the play.\n";
+        assert!(references(prose).is_empty());
+        assert_eq!(
+            prose,
+            quote_beneath(prose, |_| Some(String::from("quoted")))
+        );
+    }
+}
@@ -0,0 +1,930 @@
+use std::{borrow::Cow, fmt::Write, ops::Range};
+
+use clap::ValueEnum;
+use crossterm::style::Stylize;
+use fiat_lux::{Bible, Passage, Text, Translation};
+
+use crate::config::PaletteSection;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// verse-per-row table for reading in the terminal
+    #[default]
+    Table,
+
+    /// blockquote with superscript verse numbers
+    Markdown,
+
+    /// quote blocks split into messages of at most 2000 characters
+    Discord,
+
+    /// quote blocks split into messages of at most 4000 characters
+    Slack,
+
+    /// plain text lines of at most --line-bytes bytes, for irc bots
+    Irc,
+
+    /// RIS citation records, for Zotero and other reference managers
+    Ris,
+
+    /// BibTeX entries
+    Bibtex,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <Self as ValueEnum>::from_str(s, true)
+    }
+}
+
+/// Ways desktop Bible software lays out verses copied into a document.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CopyStyle {
+    /// “text” (John 3:16-17 KJV), like Logos
+    Logos,
+
+    /// John 3:16-17 (KJV) followed by numbered verses, like Accordance
+    Accordance,
+}
+
+impl std::str::FromStr for CopyStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <Self as ValueEnum>::from_str(s, true)
+    }
+}
+
+/// Format verses the way a desktop Bible app copies them, a paragraph per chapter:
+///
+/// ```text
+/// “For God so loved the world... For God sent not his Son...” (John 3:16-17 KJV)
+///
+/// John 3:16-17 (KJV)
+/// 16 For God so loved the world... 17 For God sent not his Son...
+/// ```
+pub fn copy(
+    texts: &[Text],
+    translation: Translation,
+    style: CopyStyle,
+    verse_numbers: VerseNumbers,
+) -> String {
+    let mut buf = String::new();
+    for (idx, chapter) in chapters(texts).enumerate() {
+        if idx > 0 {
+            buf.push('\n');
+        }
+
+        let reference = group_reference(chapter);
+        match style {
+            CopyStyle::Logos => {
+                let content: Vec<_> = chapter.iter().map(|text| text.content.as_str()).collect();
+                writeln!(buf, "“{}” ({reference} {translation})", content.join(" ")).unwrap();
+            }
+            CopyStyle::Accordance => {
+                let verses: Vec<_> = chapter
+                    .iter()
+                    .map(|text| {
+                        let number = verse_numbers.prefix(Numerals::Western, text.verse);
+                        format!("{number}{}", text.content)
+                    })
+                    .collect();
+                writeln!(buf, "{reference} ({translation})\n{}", verses.join(" ")).unwrap();
+            }
+        }
+    }
+    buf
+}
+
+/// Format verses as a markdown blockquote per chapter, e.g.
+///
+/// ```text
+/// > **John 3:16-17** (KJV)
+/// >
+/// > ¹⁶ For God so loved the world... ¹⁷ For God sent not his Son...
+/// ```
+pub fn markdown(
+    texts: &[Text],
+    translation: Translation,
+    numerals: Numerals,
+    verse_numbers: VerseNumbers,
+) -> String {
+    let mut buf = String::new();
+
+    for (idx, chapter) in chapters(texts).enumerate() {
+        if idx > 0 {
+            buf.push('\n');
+        }
+
+        let reference = group_reference(chapter);
+        writeln!(buf, "> **{reference}** ({translation})\n>").unwrap();
+
+        buf.push('>');
+        for text in chapter {
+            let number = verse_numbers.prefix(numerals, text.verse);
+            write!(buf, " {number}{}", isolate(&text.content)).unwrap();
+        }
+        buf.push('\n');
+    }
+
+    buf
+}
+
+/// The knobs which differ between chat platforms.
+#[derive(Clone, Copy, Debug)]
+pub struct ChatStyle {
+    /// The markup for bold text: ** on Discord, * on Slack.
+    pub bold: &'static str,
+
+    /// Maximum length of a single message, in characters.
+    pub limit: usize,
+}
+
+impl ChatStyle {
+    pub const DISCORD: ChatStyle = ChatStyle {
+        bold: "**",
+        limit: 2000,
+    };
+
+    pub const SLACK: ChatStyle = ChatStyle {
+        bold: "*",
+        limit: 4000,
+    };
+}
+
+/// Format verses as one or more chat messages, each of which looks like:
+///
+/// ```text
+/// **John 3:16-17** (KJV)
+/// > ¹⁶ For God so loved the world...
+/// > ¹⁷ For God sent not his Son...
+/// ```
+///
+/// Messages are split between verses where possible (and between words where not) so that none
+/// exceeds the platform's limit. Messages never contain blank lines, so a blank line marks the
+/// boundary between one message and the next.
+pub fn chat(
+    texts: &[Text],
+    translation: Translation,
+    style: ChatStyle,
+    verse_numbers: VerseNumbers,
+) -> String {
+    let ChatStyle { bold, limit } = style;
+    let reference = group_reference(texts);
+    let header = format!("{bold}{reference}{bold} ({translation})");
+    let continued = format!("{bold}{reference}{bold} ({translation}, cont.)");
+
+    // Each line is "> " plus a verse, unless the verse is too long to fit in a message at all,
+    // in which case it gets chopped into as many lines as it takes.
+    let room = limit.saturating_sub(continued.chars().count() + 3).max(1);
+    let single_chapter = chapters(texts).count() <= 1;
+    let mut lines = Vec::new();
+    for text in texts {
+        // Search results can be scattered all over, in which case a bare verse number won't do.
+        let line = if single_chapter {
+            let number = verse_numbers.prefix(Numerals::Western, text.verse);
+            format!("{number}{}", text.content)
+        } else {
+            let label = format!("{} {}:{}", text.book, text.chapter, text.verse);
+            format!("{bold}{label}{bold} {}", text.content)
+        };
+        lines.extend(
+            split_words(&line, room)
+                .into_iter()
+                .map(|line| format!("> {line}")),
+        );
+    }
+
+    let mut messages: Vec<String> = Vec::new();
+    let mut current = header;
+    for line in lines {
+        if current.chars().count() + 1 + line.chars().count() > limit {
+            messages.push(current);
+            current = continued.clone();
+        }
+        current.push('\n');
+        current.push_str(&line);
+    }
+    messages.push(current);
+
+    messages.join("\n\n") + "\n"
+}
+
+/// Marks a line which continues on the next (and the line which continues it).
+const CONTINUED: &str = "...";
+
+/// Format verses as plain text lines for irc, e.g.
+///
+/// ```text
+/// John 3:16-17 (KJV)
+/// 16 For God so loved the world, that he gave his only begotten Son, that whosoever ...
+/// ... believeth in him should not perish, but have everlasting life.
+/// 17 For God sent not his Son into the world...
+/// ```
+///
+/// The server truncates anything past 512 bytes, *including* the bot's own prefix, so the limit
+/// is in bytes rather than characters and long verses are hard-split across lines with a
+/// continuation marker at each break.
+pub fn irc(
+    texts: &[Text],
+    translation: Translation,
+    line_bytes: usize,
+    verse_numbers: VerseNumbers,
+) -> String {
+    let mut buf = String::new();
+    let reference = group_reference(texts);
+    let header = format!("{reference} ({translation})");
+    for piece in split_bytes(&header, line_bytes) {
+        buf.push_str(&piece);
+        buf.push('\n');
+    }
+
+    let single_chapter = chapters(texts).count() <= 1;
+    for text in texts {
+        let line = if single_chapter {
+            let number = verse_numbers.prefix(Numerals::Western, text.verse);
+            format!("{number}{}", text.content)
+        } else {
+            format!(
+                "{} {}:{} {}",
+                text.book, text.chapter, text.verse, text.content
+            )
+        };
+
+        let line = if line.len() <= line_bytes {
+            vec![line]
+        } else {
+            // Leave room for a marker at both ends.
+            let room = line_bytes.saturating_sub(2 * (CONTINUED.len() + 1)).max(1);
+            let pieces = split_bytes(&line, room);
+            let last = pieces.len() - 1;
+            pieces
+                .into_iter()
+                .enumerate()
+                .map(|(idx, piece)| match (idx, idx == last) {
+                    (0, _) => format!("{piece} {CONTINUED}"),
+                    (_, true) => format!("{CONTINUED} {piece}"),
+                    _ => format!("{CONTINUED} {piece} {CONTINUED}"),
+                })
+                .collect()
+        };
+
+        for line in line {
+            buf.push_str(&line);
+            buf.push('\n');
+        }
+    }
+
+    buf
+}
+
+/// Run each chapter's verses together into flowing paragraphs, wrapped to `width`, with the verse
+/// numbers dimmed inline the way a printed Bible sets them:
+///
+/// ```text
+/// John 3
+/// ¹⁶ For God so loved the world, that he gave his only begotten Son, that whosoever believeth in
+/// him should not perish, but have everlasting life. ¹⁷ For God sent not his Son into the world...
+/// ```
+///
+/// A gap in the verses, as between the passages of a reading plan, starts a new paragraph. With a
+/// palette, each chapter's heading is tinted by the section of the Bible its book is in. With
+/// `poetry`, the poetic books are set a verse at a time, in the lines [`poetic_lines`] finds.
+pub fn paragraphs(
+    texts: &[Text],
+    width: usize,
+    numerals: Numerals,
+    verse_numbers: VerseNumbers,
+    palette: Option<&PaletteSection>,
+    poetry: bool,
+) -> String {
+    let mut buf = String::new();
+    for chapter in chapters(texts) {
+        let first = &chapter[0];
+        let heading = format!("{} {}", first.book, numerals.format(first.chapter)).bold();
+        let heading = match palette {
+            Some(palette) => heading.with(palette.color(first.book.genre())),
+            None => heading,
+        };
+        writeln!(buf, "\n{heading}").unwrap();
+
+        for (idx, run) in chapter.chunk_by(|a, b| b.verse == a.verse + 1).enumerate() {
+            if idx > 0 {
+                buf.push('\n');
+            }
+
+            // A no-break space keeps each number on the line with its verse's first word.
+            let verses: Vec<_> = run
+                .iter()
+                .map(|text| match verse_numbers {
+                    VerseNumbers::None => isolate(&text.content).into_owned(),
+                    numbers => {
+                        let number = numbers.format(numerals, text.verse).dim();
+                        format!("{number}\u{a0}{}", isolate(&text.content))
+                    }
+                })
+                .collect();
+
+            if !poetry || !first.book.is_poetry() {
+                for line in textwrap::wrap(&verses.join(" "), width) {
+                    buf.push_str(&line);
+                    buf.push('\n');
+                }
+                continue;
+            }
+
+            // Each line after a verse's first is indented, and a line too long for the terminal
+            // carries on further in still.
+            for verse in &verses {
+                for (idx, line) in poetic_lines(verse).into_iter().enumerate() {
+                    let indent = if idx == 0 { "" } else { "  " };
+                    let options = textwrap::Options::new(width)
+                        .initial_indent(indent)
+                        .subsequent_indent("    ");
+                    for line in textwrap::wrap(line, options) {
+                        buf.push_str(&line);
+                        buf.push('\n');
+                    }
+                }
+            }
+        }
+    }
+    buf
+}
+
+/// Split a verse of poetry into lines after each colon and semicolon, which in the KJV mostly fall
+/// where one half of a couplet answers the other:
+///
+/// ```text
+/// The LORD is my shepherd;
+/// I shall not want.
+/// ```
+///
+/// Colors and other escape sequences, whose semicolons separate their parameters, are left whole.
+pub fn poetic_lines(content: &str) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut escape = false;
+    for (idx, c) in content.char_indices() {
+        match c {
+            '\x1b' => escape = true,
+            c if escape => escape = !c.is_ascii_alphabetic(),
+            ':' | ';' => {
+                lines.push(content[start..=idx].trim());
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    lines.push(content[start..].trim());
+    lines.retain(|line| !line.is_empty());
+    lines
+}
+
+/// Format verses as one RIS record per chapter, e.g.
+///
+/// ```text
+/// TY  - BOOK
+/// TI  - King James Version
+/// PB  - Robert Barker
+/// PY  - 1611
+/// SE  - John 3:16-17
+/// N1  - For God so loved the world... For God sent not his Son...
+/// ER  -
+/// ```
+pub fn ris(texts: &[Text], translation: Translation) -> String {
+    let mut buf = String::new();
+
+    for chapter in chapters(texts) {
+        let record = Citation::new(chapter, translation);
+        writeln!(buf, "TY  - BOOK").unwrap();
+        writeln!(buf, "TI  - {}", record.title).unwrap();
+        if let Some(edition) = translation.edition() {
+            writeln!(buf, "PB  - {}", edition.publisher).unwrap();
+            writeln!(buf, "PY  - {}", edition.year).unwrap();
+        }
+        writeln!(buf, "SE  - {}", record.reference).unwrap();
+        writeln!(buf, "N1  - {}", record.quote).unwrap();
+        writeln!(buf, "ER  - \n").unwrap();
+    }
+
+    buf
+}
+
+/// Format verses as one BibTeX entry per chapter, e.g.
+///
+/// ```text
+/// @book{kjv-john-3-16-17,
+///   title = {King James Version},
+///   publisher = {Robert Barker},
+///   year = {1611},
+///   note = {John 3:16-17},
+///   annote = {For God so loved the world... For God sent not his Son...},
+/// }
+/// ```
+pub fn bibtex(texts: &[Text], translation: Translation) -> String {
+    let mut buf = String::new();
+
+    for (idx, chapter) in chapters(texts).enumerate() {
+        if idx > 0 {
+            buf.push('\n');
+        }
+
+        let record = Citation::new(chapter, translation);
+        let key = format!("{translation} {}", record.reference)
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+
+        writeln!(buf, "@book{{{key},").unwrap();
+        writeln!(buf, "  title = {{{}}},", escape_tex(&record.title)).unwrap();
+        if let Some(edition) = translation.edition() {
+            writeln!(buf, "  publisher = {{{}}},", escape_tex(edition.publisher)).unwrap();
+            writeln!(buf, "  year = {{{}}},", edition.year).unwrap();
+        }
+        writeln!(buf, "  note = {{{}}},", escape_tex(&record.reference)).unwrap();
+        writeln!(buf, "  annote = {{{}}},", escape_tex(&record.quote)).unwrap();
+        buf.push_str("}\n");
+    }
+
+    buf
+}
+
+/// What goes into a citation of a run of verses, whatever the format.
+struct Citation {
+    title: String,
+    reference: String,
+    quote: String,
+}
+
+impl Citation {
+    fn new(texts: &[Text], translation: Translation) -> Self {
+        let title = match translation.edition() {
+            Some(edition) => edition.title.to_string(),
+            None => translation.to_string(),
+        };
+
+        // Neither format has any business with line breaks inside a field.
+        let quote = texts
+            .iter()
+            .map(|text| {
+                text.content
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Citation {
+            title,
+            reference: group_reference(texts),
+            quote,
+        }
+    }
+}
+
+/// Escape the characters TeX treats specially.
+fn escape_tex(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                buf.push('\\');
+                buf.push(c);
+            }
+            '~' => buf.push_str("\\textasciitilde{}"),
+            '^' => buf.push_str("\\textasciicircum{}"),
+            '\\' => buf.push_str("\\textbackslash{}"),
+            _ => buf.push(c),
+        }
+    }
+    buf
+}
+
+/// Break a string into pieces of at most `limit` characters, between words where possible.
+pub fn split_words(s: &str, limit: usize) -> Vec<String> {
+    split_words_by(s, limit, |_| 1)
+}
+
+/// Break a string into pieces of at most `limit` bytes, between words where possible.
+fn split_bytes(s: &str, limit: usize) -> Vec<String> {
+    split_words_by(s, limit, char::len_utf8)
+}
+
+/// Break a string into pieces no longer than `limit`, where `weight` is the length of each char.
+fn split_words_by(s: &str, limit: usize, weight: impl Fn(char) -> usize) -> Vec<String> {
+    let len = |s: &str| s.chars().map(&weight).sum::<usize>();
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+
+    for word in s.split_whitespace() {
+        let mut word = word;
+
+        // A single word longer than the limit has to be broken wherever it falls.
+        while len(word) > limit {
+            if !current.is_empty() {
+                pieces.push(std::mem::take(&mut current));
+            }
+            let mut total = 0;
+            let idx = word
+                .char_indices()
+                .find(|&(_, c)| {
+                    total += weight(c);
+                    total > limit
+                })
+                .map_or(word.len(), |(i, _)| i);
+            // Always make progress, even if the first char alone is over the limit.
+            let idx = if idx == 0 {
+                word.chars().next().map_or(word.len(), char::len_utf8)
+            } else {
+                idx
+            };
+            pieces.push(word[..idx].to_string());
+            word = &word[idx..];
+        }
+
+        if !current.is_empty() && len(&current) + 1 + len(word) > limit {
+            pieces.push(std::mem::take(&mut current));
+        }
+
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+
+    pieces
+}
+
+/// How verse numbers are set off from the text, for the formats which show them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum VerseNumbers {
+    /// ¹⁶ (or <sup> in html)
+    #[default]
+    Superscript,
+
+    /// [16]
+    Bracket,
+
+    /// 16
+    Plain,
+
+    /// leave them out
+    None,
+}
+
+impl VerseNumbers {
+    /// The verse number as it's written, which is nothing at all for `None`.
+    pub fn format(self, numerals: Numerals, verse: u16) -> String {
+        match self {
+            VerseNumbers::Superscript => numerals.marker(verse),
+            VerseNumbers::Bracket => format!("[{}]", numerals.format(verse)),
+            VerseNumbers::Plain => numerals.format(verse),
+            VerseNumbers::None => String::new(),
+        }
+    }
+
+    /// The verse number with a space to go before the verse, or nothing for `None`.
+    pub fn prefix(self, numerals: Numerals, verse: u16) -> String {
+        match self {
+            VerseNumbers::None => String::new(),
+            numbers => numbers.format(numerals, verse) + " ",
+        }
+    }
+}
+
+/// The digits to write chapter and verse numbers in, for reading a translation in a script
+/// which has its own.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Numerals {
+    /// 0123456789
+    #[default]
+    Western,
+
+    /// ٠١٢٣٤٥٦٧٨٩, as in Arabic
+    Arabic,
+
+    /// ۰۱۲۳۴۵۶۷۸۹, as in Persian and Urdu
+    Persian,
+
+    /// ०१२३४५६७८९, as in Hindi and Marathi
+    Devanagari,
+}
+
+impl Numerals {
+    pub fn format(self, n: u16) -> String {
+        let zero = match self {
+            Numerals::Western => return n.to_string(),
+            Numerals::Arabic => 0x0660,
+            Numerals::Persian => 0x06f0,
+            Numerals::Devanagari => 0x0966,
+        };
+        n.to_string()
+            .bytes()
+            .filter_map(|u| char::from_u32(zero + u32::from(u - b'0')))
+            .collect()
+    }
+
+    /// A verse number to run in with the text: superscript, where there are superscript digits
+    /// to be had, or else just the number.
+    pub fn marker(self, n: u16) -> String {
+        match self {
+            Numerals::Western => superscript(n),
+            numerals => numerals.format(n),
+        }
+    }
+}
+
+impl std::str::FromStr for Numerals {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <Self as ValueEnum>::from_str(s, true)
+    }
+}
+
+/// Wrap text written right to left in a bidi isolate, so that a terminal or editor lays it out
+/// on its own instead of letting it drag the verse number or reference beside it out of order.
+/// Anything else comes back as it was.
+pub fn isolate(s: &str) -> Cow<'_, str> {
+    const RLI: char = '\u{2067}';
+    const PDI: char = '\u{2069}';
+
+    if s.chars().any(is_rtl) {
+        Cow::Owned(format!("{RLI}{s}{PDI}"))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+/// Whether a character belongs to a script written right to left: Hebrew, Arabic, Syriac and
+/// their neighbors, along with their presentation forms.
+fn is_rtl(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0590}'..='\u{08ff}' | '\u{fb1d}'..='\u{fdff}' | '\u{fe70}'..='\u{fefe}'
+    )
+}
+
+/// Split verses into runs belonging to the same chapter.
+pub fn chapters(texts: &[Text]) -> impl Iterator<Item = &[Text]> {
+    texts.chunk_by(|a, b| a.book == b.book && a.chapter == b.chapter)
+}
+
+/// Render a number in unicode superscript digits, e.g. ¹⁶.
+pub fn superscript(n: u16) -> String {
+    const DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+    n.to_string()
+        .bytes()
+        .map(|u| DIGITS[usize::from(u - b'0')])
+        .collect()
+}
+
+/// Cut a verse down to about `max` characters, at word boundaries, with an ellipsis wherever
+/// something was left out. The cut keeps the start of the verse unless that would lose the first
+/// of `ranges` (the words a search matched), in which case it starts a little before that.
+/// Returns the shortened verse along with whichever ranges are still in it, moved to match.
+pub fn truncate(content: &str, ranges: &[Range<usize>], max: usize) -> (String, Vec<Range<usize>>) {
+    if content.chars().count() <= max {
+        return (content.to_string(), ranges.to_vec());
+    }
+
+    let chars_before = |idx: usize| content[..idx].chars().count();
+    let byte = |n: usize| {
+        content
+            .char_indices()
+            .nth(n)
+            .map_or(content.len(), |(idx, _)| idx)
+    };
+
+    let mut start = 0;
+    if let Some(first) = ranges.first().filter(|first| chars_before(first.end) > max) {
+        // A quarter of the room goes to the words leading up to the match, or more if the match
+        // is near the end and there's room to spare.
+        let total = content.chars().count();
+        let lead = chars_before(first.start)
+            .saturating_sub(max / 4)
+            .min(total - max);
+        let lead = byte(lead);
+        start = content[lead..first.start]
+            .find(' ')
+            .map_or(first.start, |space| lead + space + 1);
+    }
+
+    let mut end = byte(chars_before(start) + max);
+    // Back up to the end of a word, unless the cut already falls at one.
+    if end < content.len() && !content[end..].starts_with(' ') {
+        if let Some(space) = content[start..end].rfind(' ').filter(|&space| space > 0) {
+            end = start + space;
+        }
+    }
+
+    let prefix = if start > 0 { "…" } else { "" };
+    let kept = content[start..end].trim_end_matches([' ', ',', ';', ':']);
+    let suffix = if end < content.len() { "…" } else { "" };
+
+    let ranges = ranges
+        .iter()
+        .filter(|range| range.start >= start && range.end <= start + kept.len())
+        .map(|range| range.start - start + prefix.len()..range.end - start + prefix.len())
+        .collect();
+    (format!("{prefix}{kept}{suffix}"), ranges)
+}
+
+/// A passage's text, its verses run together, and the reference it covers, for filling in a
+/// document. A passage that can't be had is reported and comes back `None`, so the caller can
+/// leave the document as it was.
+pub fn quote_passage(
+    bible: &Bible,
+    passage: Passage,
+    translation: Translation,
+) -> Option<(String, String)> {
+    match bible.lookup(passage.book, passage.location, translation) {
+        Ok(texts) if !texts.is_empty() => {
+            let content: Vec<_> = texts.iter().map(|text| text.content.as_str()).collect();
+            Some((content.join(" "), group_reference(&texts)))
+        }
+        Ok(_) => {
+            eprintln!("{passage} isn't in the {translation}; leaving it be");
+            None
+        }
+        Err(e) => {
+            eprintln!("{passage}: {e}; leaving it be");
+            None
+        }
+    }
+}
+
+/// The reference covering a run of verses, e.g. "Psalms 23:1-2".
+pub fn group_reference(texts: &[Text]) -> String {
+    let (Some(first), Some(last)) = (texts.first(), texts.last()) else {
+        return String::new();
+    };
+
+    if first.book != last.book {
+        format!(
+            "{} {}:{} - {} {}:{}",
+            first.book, first.chapter, first.verse, last.book, last.chapter, last.verse
+        )
+    } else if first.chapter != last.chapter {
+        format!(
+            "{} {}:{}-{}:{}",
+            first.book, first.chapter, first.verse, last.chapter, last.verse
+        )
+    } else if first.verse != last.verse {
+        format!(
+            "{} {}:{}-{}",
+            first.book, first.chapter, first.verse, last.verse
+        )
+    } else {
+        format!("{} {}:{}", first.book, first.chapter, first.verse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fiat_lux::{Book, Text, Translation};
+
+    #[test]
+    fn numerals_and_isolates() {
+        use super::{isolate, Numerals};
+
+        assert_eq!("316", Numerals::Western.format(316));
+        assert_eq!("٣١٦", Numerals::Arabic.format(316));
+        assert_eq!("۳۱۶", Numerals::Persian.format(316));
+        assert_eq!("३१६", Numerals::Devanagari.format(316));
+        assert_eq!("¹⁶", Numerals::Western.marker(16));
+        assert_eq!("١٦", Numerals::Arabic.marker(16));
+
+        assert_eq!("Jesus wept.", isolate("Jesus wept."));
+        assert_eq!("\u{2067}בְּרֵאשִׁית\u{2069}", isolate("בְּרֵאשִׁית"));
+    }
+
+    #[test]
+    fn truncate() {
+        let content = "And it came to pass, when the LORD would take up Elijah into heaven by a \
+            whirlwind, that Elijah went with Elisha from Gilgal.";
+
+        let (short, _) = super::truncate(content, &[], 40);
+        assert_eq!("And it came to pass, when the LORD would…", short);
+
+        // The match is past the cut, so the window moves to take it in, still highlighted.
+        let gilgal: Vec<_> = content
+            .match_indices("Gilgal")
+            .map(|(start, word)| start..start + word.len())
+            .collect();
+        let (short, ranges) = super::truncate(content, &gilgal, 40);
+        assert_eq!("…Elijah went with Elisha from Gilgal.", short);
+        assert_eq!("Gilgal", &short[ranges[0].clone()]);
+
+        let (short, _) = super::truncate("Jesus wept.", &[], 40);
+        assert_eq!("Jesus wept.", short);
+    }
+
+    #[test]
+    fn irc_lines_fit() {
+        let texts = [Text {
+            book: Book::John,
+            chapter: 3,
+            verse: 16,
+            content: "For God so loved the world, that he gave his only begotten Son".into(),
+        }];
+
+        let output = super::irc(&texts, Translation::Kjv, 30, super::VerseNumbers::Plain);
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines[0], "John 3:16 (KJV)");
+        assert_eq!(lines[1], "16 For God so loved ...");
+        assert!(lines[2].starts_with("... "));
+        assert!(lines.iter().all(|line| line.len() <= 30));
+        assert!(lines.last().unwrap().ends_with(" Son"));
+    }
+
+    #[test]
+    fn paragraphs() {
+        let text = |verse, content: &str| Text {
+            book: Book::John,
+            chapter: 11,
+            verse,
+            content: content.into(),
+        };
+        let texts = [
+            text(
+                33,
+                "When Jesus therefore saw her weeping, he groaned in the spirit.",
+            ),
+            text(34, "And said, Where have ye laid him?"),
+            text(35, "Jesus wept."),
+            text(43, "He cried with a loud voice, Lazarus, come forth."),
+        ];
+
+        let output = super::paragraphs(
+            &texts,
+            40,
+            super::Numerals::Western,
+            super::VerseNumbers::None,
+            None,
+            false,
+        );
+        let paragraphs: Vec<_> = output
+            .lines()
+            .skip(2)
+            .collect::<Vec<_>>()
+            .split(|line| line.is_empty())
+            .map(|lines| lines.join(" "))
+            .collect();
+        assert_eq!(
+            vec![
+                "When Jesus therefore saw her weeping, he groaned in the spirit. And said, Where \
+                have ye laid him? Jesus wept.",
+                "He cried with a loud voice, Lazarus, come forth.",
+            ],
+            paragraphs
+        );
+        assert!(output.lines().all(|line| line.chars().count() <= 40));
+    }
+
+    #[test]
+    fn poetic_lines() {
+        assert_eq!(
+            vec!["The LORD is my shepherd;", "I shall not want."],
+            super::poetic_lines("The LORD is my shepherd; I shall not want.")
+        );
+
+        // The semicolon in a color isn't a line break.
+        let colored =
+            "He maketh me to lie down in \x1b[38;5;1mgreen\x1b[39m pastures: he leadeth me";
+        assert_eq!(
+            vec![
+                "He maketh me to lie down in \x1b[38;5;1mgreen\x1b[39m pastures:",
+                "he leadeth me"
+            ],
+            super::poetic_lines(colored)
+        );
+    }
+
+    #[test]
+    fn bibtex_entry() {
+        let texts = [Text {
+            book: Book::John,
+            chapter: 11,
+            verse: 35,
+            content: "Jesus wept.".into(),
+        }];
+
+        assert_eq!(
+            "@book{asv-john-11-35,\n  title = {American Standard Version},\n  \
+            publisher = {Thomas Nelson \\& Sons},\n  year = {1901},\n  note = {John 11:35},\n  \
+            annote = {Jesus wept.},\n}\n",
+            super::bibtex(&texts, Translation::Asv)
+        );
+    }
+}
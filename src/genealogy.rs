@@ -0,0 +1,55 @@
+use clap::Parser;
+use crossterm::style::Stylize;
+use fiat_lux::Person;
+
+use crate::{Error, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct GenealogyArgs {
+    name: String,
+
+    /// how many generations of descendants to show
+    #[clap(short, long, default_value_t = 3)]
+    generations: usize,
+}
+
+pub fn genealogy(args: &GenealogyArgs) -> Result<()> {
+    let people = Person::find(&args.name);
+    if people.is_empty() {
+        return Err(Error::UnknownPerson(args.name.clone()));
+    }
+
+    // Joseph, Jacob and a few others turn up twice, so show each of them.
+    for (idx, person) in people.iter().enumerate() {
+        if idx > 0 {
+            println!();
+        }
+
+        for ancestor in person.ancestors() {
+            println!("{:24}{}", ancestor.name, ancestor.reference.dark_grey());
+        }
+
+        let name = format!("{:24}", person.name);
+        println!("{}{}", name.bold(), person.reference.dark_grey());
+        print_descendants(person, "", args.generations);
+    }
+
+    Ok(())
+}
+
+fn print_descendants(person: &Person, indent: &str, generations: usize) {
+    if generations == 0 {
+        return;
+    }
+
+    let children = person.children();
+    for (idx, child) in children.iter().enumerate() {
+        let last = idx + 1 == children.len();
+        let branch = if last { "└─ " } else { "├─ " };
+        let label = format!("{indent}{branch}{}", child.name);
+        println!("{label:24}{}", child.reference.dark_grey());
+
+        let indent = format!("{indent}{}", if last { "   " } else { "│  " });
+        print_descendants(child, &indent, generations - 1);
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt::Write;
+
+use fiat_lux::Book;
+
+/// Marks from a single matching verse up to as many as the busiest chapter has.
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// A chapter without a match.
+const EMPTY: char = '·';
+
+/// One row for each book with a match, giving its total and a mark for each chapter, as tall as
+/// that chapter's share of the busiest one anywhere. A book with more chapters than fit in `width`
+/// (the Psalms, usually) gets a few chapters to a mark, showing the busiest of them.
+pub fn heatmap(counts: &[(Book, u16, u64)], width: usize) -> String {
+    let mut books: Vec<(Book, u64)> = Vec::new();
+    for &(book, _, count) in counts {
+        match books.last_mut() {
+            Some((last, total)) if *last == book => *total += count,
+            _ => books.push((book, count)),
+        }
+    }
+
+    let label = books
+        .iter()
+        .map(|(book, _)| book.to_string().len())
+        .max()
+        .unwrap_or_default();
+    let digits = books
+        .iter()
+        .map(|(_, total)| total.to_string().len())
+        .max()
+        .unwrap_or_default();
+    let room = width.saturating_sub(label + digits + 4).max(10);
+    let most = counts.iter().map(|&(_, _, count)| count).max().unwrap_or(1);
+
+    let mut buf = String::new();
+    for &(book, total) in &books {
+        let hits: Vec<_> = counts
+            .iter()
+            .filter(|&&(b, _, _)| b == book)
+            .map(|&(_, chapter, count)| (chapter, count))
+            .collect();
+
+        // Other versifications now and then run a chapter past the KJV's.
+        let last = hits
+            .iter()
+            .map(|&(chapter, _)| chapter)
+            .max()
+            .unwrap_or_default()
+            .max(book.chapters());
+        let per = usize::from(last).div_ceil(room).max(1) as u16;
+
+        let mut marks = String::new();
+        for first in (1..=last).step_by(usize::from(per)) {
+            let count = hits
+                .iter()
+                .filter(|&&(chapter, _)| (first..first + per).contains(&chapter))
+                .map(|&(_, count)| count)
+                .max()
+                .unwrap_or_default();
+            marks.push(match count {
+                0 => EMPTY,
+                count => LEVELS[((count * 8).div_ceil(most) - 1) as usize],
+            });
+        }
+
+        let name = book.to_string();
+        let _ = writeln!(buf, "{name:label$}  {total:>digits$}  {marks}");
+    }
+
+    if !books.is_empty() {
+        let _ = writeln!(
+            buf,
+            "\none mark per chapter (a few, in long books); {} is {most} matching verses",
+            LEVELS[7]
+        );
+    }
+
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use fiat_lux::Book;
+
+    use super::heatmap;
+
+    #[test]
+    fn heatmap_rows() {
+        let counts = [
+            (Book::Ruth, 1, 1),
+            (Book::Ruth, 4, 8),
+            (Book::Psalms, 150, 2),
+            (Book::Jude, 1, 4),
+        ];
+        let map = heatmap(&counts, 80);
+        let rows: Vec<_> = map.lines().collect();
+        assert_eq!("Ruth    9  ▁··█", rows[0]);
+        assert_eq!("Jude    4  ▄", rows[2]);
+
+        // 150 psalms in 69 columns is three to a mark.
+        assert_eq!(50, rows[1].chars().skip(11).count());
+        assert!(rows[1].ends_with('▂'));
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use fiat_lux::{Book, Date, PartialLocation, Passage, Translation};
+
+use crate::{config::state_dir, Args, Error, Result};
+
+/// How many lookups and searches to remember; older ones fall off the front.
+const MAX_ENTRIES: usize = 1000;
+
+/// Something you looked up or searched for, and when.
+#[derive(Clone, Debug)]
+struct Entry {
+    /// Seconds since 1970-01-01, UTC.
+    time: u64,
+    translation: Translation,
+    kind: Kind,
+}
+
+#[derive(Clone, Debug)]
+enum Kind {
+    Lookup(Passage),
+    Search(String),
+}
+
+/// Your lookups and searches, oldest first, one per line: the time, the translation, "lookup" or
+/// "search", and the passage or query, separated by tabs.
+#[derive(Debug, Default)]
+struct History {
+    entries: Vec<Entry>,
+}
+
+impl History {
+    fn path() -> Option<PathBuf> {
+        state_dir().map(|dir| dir.join("history.dat"))
+    }
+
+    fn load() -> Result<Self> {
+        let text = match Self::path().map(fs::read_to_string) {
+            Some(Ok(text)) => text,
+            Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => return Ok(Self::default()),
+        };
+
+        let entries = text
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(4, '\t');
+                let time = fields.next()?.parse().ok()?;
+                let translation = fields.next()?.parse().ok()?;
+                let kind = match (fields.next()?, fields.next()?) {
+                    ("lookup", passage) => Kind::Lookup(passage.parse().ok()?),
+                    ("search", query) => Kind::Search(query.to_string()),
+                    _ => return None,
+                };
+                Some(Entry {
+                    time,
+                    translation,
+                    kind,
+                })
+            })
+            .collect();
+
+        Ok(Self { entries })
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+
+        let mut text = String::new();
+        for entry in &self.entries {
+            let (kind, what) = match &entry.kind {
+                Kind::Lookup(passage) => ("lookup", passage.to_string()),
+                Kind::Search(query) => ("search", query.replace(['\t', '\n'], " ")),
+            };
+            text.push_str(&format!(
+                "{}\t{}\t{kind}\t{what}\n",
+                entry.time, entry.translation
+            ));
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(fs::write(path, text)?)
+    }
+
+    fn last_lookup(&self) -> Option<(Passage, Translation)> {
+        self.entries
+            .iter()
+            .rev()
+            .find_map(|entry| match entry.kind {
+                Kind::Lookup(passage) => Some((passage, entry.translation)),
+                Kind::Search(_) => None,
+            })
+    }
+}
+
+fn record(kind: Kind, translation: Translation) -> Result<()> {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+
+    let mut history = History::load()?;
+    history.entries.push(Entry {
+        time,
+        translation,
+        kind,
+    });
+    let excess = history.entries.len().saturating_sub(MAX_ENTRIES);
+    history.entries.drain(..excess);
+    history.save()
+}
+
+/// Remember a passage you looked up. History is a convenience, so failing to write it shouldn't
+/// fail the lookup; the error is dropped.
+pub fn record_lookup(passage: Passage, translation: Translation) {
+    let _ = record(Kind::Lookup(passage), translation);
+}
+
+/// Remember a search, on the same terms as a lookup.
+pub fn record_search(query: &str, translation: Translation) {
+    let _ = record(Kind::Search(query.to_string()), translation);
+}
+
+/// The last passage you looked up and the translation you read it in, if there's any history to
+/// go on.
+#[cfg(feature = "tui")]
+pub fn last_lookup() -> Option<(Passage, Translation)> {
+    History::load().ok()?.last_lookup()
+}
+
+/// List the most recent lookups and searches, newest first.
+pub fn history(limit: usize) -> Result<()> {
+    let history = History::load()?;
+    for entry in history.entries.iter().rev().take(limit) {
+        let date = Date::from_days((entry.time / 86_400) as i64);
+        let minutes = entry.time % 86_400 / 60;
+        let what = match &entry.kind {
+            Kind::Lookup(passage) => passage.to_string(),
+            Kind::Search(query) => format!("search \"{query}\""),
+        };
+        println!(
+            "{}-{:02}-{:02} {:02}:{:02}  {:<4} {what}",
+            date.year,
+            date.month,
+            date.day,
+            minutes / 60,
+            minutes % 60,
+            entry.translation.to_string()
+        );
+    }
+    Ok(())
+}
+
+/// Show the last passage you looked up again, in the translation you read it in unless another
+/// is asked for.
+pub fn last(args: &Args) -> Result<()> {
+    let (passage, translation) = History::load()?.last_lookup().ok_or(Error::NoHistory)?;
+    let translation = args.translation.chosen().unwrap_or(translation);
+    crate::show_passage(
+        &crate::open_bible()?,
+        passage.book,
+        passage.location,
+        translation,
+        args,
+    )
+}
+
+/// Show the chapter after (or before) the last passage you looked up.
+pub fn step(args: &Args, forward: bool) -> Result<()> {
+    let (passage, translation) = History::load()?.last_lookup().ok_or(Error::NoHistory)?;
+    let translation = args.translation.chosen().unwrap_or(translation);
+    let stepped = adjacent(passage, forward).ok_or(if forward {
+        Error::NothingAfter(passage)
+    } else {
+        Error::NothingBefore(passage)
+    })?;
+
+    crate::show_passage(
+        &crate::open_bible()?,
+        stepped.book,
+        stepped.location,
+        translation,
+        args,
+    )?;
+    record_lookup(stepped, translation);
+    Ok(())
+}
+
+/// The chapter after or before the one a passage is in, running on into the next book (or back
+/// into the last chapter of the one before). From a whole book, it's the next or previous book.
+pub fn adjacent(passage: Passage, forward: bool) -> Option<Passage> {
+    let number = passage.book as u8;
+    let book = |number: u8| (1..=66).contains(&number).then(|| Book::from_u8(number));
+    let whole_chapter = |book: Book, chapter| Passage {
+        book,
+        location: Some(PartialLocation {
+            chapter,
+            verse: None,
+            end: None,
+        }),
+    };
+
+    let Some(location) = passage.location else {
+        let number = if forward { number + 1 } else { number - 1 };
+        return book(number).map(|book| Passage {
+            book,
+            location: None,
+        });
+    };
+
+    let chapter = location.chapter;
+    if forward {
+        if chapter < passage.book.chapters() {
+            Some(whole_chapter(passage.book, chapter + 1))
+        } else {
+            book(number + 1).map(|book| whole_chapter(book, 1))
+        }
+    } else if chapter > 1 {
+        Some(whole_chapter(passage.book, chapter - 1))
+    } else {
+        book(number - 1).map(|book| whole_chapter(book, book.chapters()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::adjacent;
+    use fiat_lux::Passage;
+
+    #[test]
+    fn stepping() {
+        let next = |s: &str| adjacent(s.parse::<Passage>().unwrap(), true).map(|p| p.to_string());
+        let prev = |s: &str| adjacent(s.parse::<Passage>().unwrap(), false).map(|p| p.to_string());
+
+        assert_eq!(Some("John 4"), next("John 3:16").as_deref());
+        assert_eq!(Some("Acts 1"), next("John 21").as_deref());
+        assert_eq!(Some("John 21"), prev("Acts 1:8").as_deref());
+        assert_eq!(Some("Exodus"), next("Genesis").as_deref());
+        assert_eq!(None, next("Revelation 22"));
+        assert_eq!(None, prev("Genesis 1"));
+        assert_eq!(None, prev("Genesis"));
+    }
+}
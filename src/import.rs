@@ -0,0 +1,319 @@
+//! Reading the highlights and notes kept by other apps, so they can come along into `flux note`.
+
+use clap::ValueEnum;
+use fiat_lux::{Bible, Book, PartialLocation, Passage, Translation};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Source {
+    /// the highlights and notes csv from YouVersion's data download
+    Youversion,
+
+    /// a Kindle's "My Clippings.txt"
+    Kindle,
+}
+
+/// What an import came to: the notes to add, and a short description of each entry which couldn't
+/// be matched to a passage.
+#[derive(Debug, Default)]
+pub struct Imported {
+    pub notes: Vec<(Passage, String)>,
+    pub unplaced: Vec<String>,
+}
+
+impl Imported {
+    fn unplaced(&mut self, text: &str) {
+        let words: Vec<_> = text.split_whitespace().take(8).collect();
+        self.unplaced.push(words.join(" "));
+    }
+}
+
+/// YouVersion's export is a csv with a row per highlight, bookmark or note. Only a few of the
+/// columns matter here, and they're found by name: the verses, as usfm references like
+/// "JHN.3.16+JHN.3.17", the note (empty for a highlight) and the highlight's color.
+pub fn youversion(text: &str) -> Imported {
+    let mut imported = Imported::default();
+    let mut rows = csv(text).into_iter();
+    let Some(header) = rows.next() else {
+        return imported;
+    };
+
+    let column = |names: &[&str]| {
+        header.iter().position(|name| {
+            let name = name.trim().to_lowercase();
+            names.iter().any(|&wanted| name.contains(wanted))
+        })
+    };
+    let Some(reference) = column(&["reference", "usfm", "verse"]) else {
+        return imported;
+    };
+    let content = column(&["content", "note", "text"]);
+    let color = column(&["color", "colour"]);
+
+    for row in rows {
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|column| row.get(column))
+                .map_or("", |field| field.trim())
+        };
+
+        let reference = field(Some(reference));
+        let passages = usfm_references(reference);
+        if passages.is_empty() {
+            imported.unplaced(reference);
+            continue;
+        }
+
+        let note = match (field(content), field(color)) {
+            ("", "") => String::from("highlighted"),
+            ("", color) => format!("highlighted ({color})"),
+            (note, _) => note.to_string(),
+        };
+        for passage in passages {
+            imported.notes.push((passage, note.clone()));
+        }
+    }
+
+    imported
+}
+
+/// A Kindle keeps every highlight, note and bookmark from every book in one file, each entry
+/// ending in a line of equals signs:
+///
+/// ```text
+/// The Holy Bible (King James Version)
+/// - Your Highlight on Location 80412-80413 | Added on Sunday, 1 March 2020 09:12:44
+///
+/// For God so loved the world, that he gave his only begotten Son,
+/// ==========
+/// ```
+///
+/// Highlights don't say where in the Bible they are, so `locate` finds them from their words. A
+/// note goes with the highlight it was made on, if any, or otherwise with whatever reference it
+/// mentions. Bookmarks are left out.
+pub fn kindle(text: &str, mut locate: impl FnMut(&str) -> Option<Passage>) -> Imported {
+    let mut imported = Imported::default();
+    // The last highlight placed, and the Kindle locations it covers.
+    let mut last: Option<(Passage, u32, u32)> = None;
+
+    for entry in text.split("==========") {
+        let mut lines = entry
+            .lines()
+            .map(|line| line.trim_matches(['\u{feff}', ' ']));
+        let Some(meta) = lines.by_ref().filter(|line| !line.is_empty()).nth(1) else {
+            continue;
+        };
+        let content: Vec<_> = lines.filter(|line| !line.is_empty()).collect();
+        let content = content.join(" ");
+        let (start, end) = kindle_location(meta).unwrap_or_default();
+
+        if meta.contains("Your Highlight") {
+            match Passage::find_all(&content)
+                .first()
+                .map(|&(_, passage)| passage)
+                .or_else(|| locate(&content))
+            {
+                Some(passage) => {
+                    imported
+                        .notes
+                        .push((passage, format!("highlighted: \"{content}\"")));
+                    last = Some((passage, start, end));
+                }
+                None => imported.unplaced(&content),
+            }
+        } else if meta.contains("Your Note") {
+            let on_highlight = last
+                .filter(|&(_, from, to)| (from..=to).contains(&start))
+                .map(|(passage, _, _)| passage);
+            match on_highlight.or_else(|| {
+                Passage::find_all(&content)
+                    .first()
+                    .map(|&(_, passage)| passage)
+            }) {
+                Some(passage) => imported.notes.push((passage, content)),
+                None => imported.unplaced(&content),
+            }
+        }
+    }
+
+    imported
+}
+
+/// The verse or verses some highlighted words come from, found by searching for their first few
+/// words and then their last few. Much shorter than that and a phrase could be from anywhere.
+pub fn locate(bible: &Bible, content: &str, translation: Translation) -> Option<Passage> {
+    const PHRASE: usize = 6;
+
+    let words: Vec<_> = content
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .collect();
+    if words.len() < 4 {
+        return None;
+    }
+
+    let find = |words: &[&str]| {
+        let query = format!("\"{}\"", words.join(" "));
+        bible
+            .search(&query, translation, 1)
+            .ok()?
+            .into_iter()
+            .next()
+    };
+    let first = find(&words[..words.len().min(PHRASE)])?;
+    let last = (words.len() > PHRASE)
+        .then(|| find(&words[words.len() - PHRASE..]))
+        .flatten()
+        .filter(|last| {
+            (last.book, last.chapter) == (first.book, first.chapter) && last.verse > first.verse
+        });
+
+    Some(Passage {
+        book: first.book,
+        location: Some(PartialLocation {
+            chapter: first.chapter,
+            verse: Some(first.verse),
+            end: last.map(|last| last.verse),
+        }),
+    })
+}
+
+/// The Kindle locations in a line like "- Your Highlight on page 3 | Location 80412-80413 | ...".
+fn kindle_location(meta: &str) -> Option<(u32, u32)> {
+    let rest = &meta[meta.find("Location ")? + "Location ".len()..];
+    let range = rest
+        .split(|c: char| !c.is_ascii_digit() && c != '-')
+        .next()?;
+    let (start, end) = range.split_once('-').unwrap_or((range, range));
+    let start = start.parse().ok()?;
+    Some((start, end.parse().unwrap_or(start)))
+}
+
+/// Passages from usfm references like "JHN.3.16" joined with '+', as YouVersion writes them.
+/// Verses in a row in the same chapter come back as a single passage. Plain references like
+/// "John 3:16" are taken too.
+fn usfm_references(text: &str) -> Vec<Passage> {
+    let mut passages: Vec<Passage> = Vec::new();
+    for reference in text.split(['+', ',']).map(str::trim) {
+        let mut parts = reference.split('.');
+        let book = parts.next().and_then(Book::from_usfm_code);
+        let chapter = parts.next().and_then(|part| part.parse().ok());
+        let verse = parts.next().and_then(|part| part.parse().ok());
+        let passage = match (book, chapter) {
+            (Some(book), Some(chapter)) => Passage {
+                book,
+                location: Some(PartialLocation {
+                    chapter,
+                    verse,
+                    end: None,
+                }),
+            },
+            _ => match reference.parse() {
+                Ok(passage) => passage,
+                Err(_) => continue,
+            },
+        };
+
+        // Extend the last passage where this picks up right after it.
+        if let Some(last) = passages.last_mut() {
+            if let (Some(location), Some(next)) = (&mut last.location, passage.location) {
+                let end = location.end.or(location.verse);
+                if last.book == passage.book
+                    && location.chapter == next.chapter
+                    && next.end.is_none()
+                    && end
+                        .zip(next.verse)
+                        .is_some_and(|(end, next)| next == end + 1)
+                {
+                    location.end = next.verse;
+                    continue;
+                }
+            }
+        }
+        passages.push(passage);
+    }
+    passages
+}
+
+/// Rows of comma-separated fields, which may be quoted to hold commas, quotes ("") or newlines.
+fn csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => (),
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use fiat_lux::Passage;
+
+    use super::{kindle, youversion};
+
+    #[test]
+    fn importing() {
+        let passage = |s: &str| s.parse::<Passage>().unwrap();
+
+        let csv = "Date,Reference,Color,Content\n\
+            2020-03-01,JHN.3.16+JHN.3.17,ffff00,\n\
+            2020-03-02,ROM.8.28,,\"all things, \"\"together\"\"\"\n\
+            2020-03-03,???,,\n";
+        let imported = youversion(csv);
+        assert_eq!(
+            vec![
+                (
+                    passage("john 3:16-17"),
+                    String::from("highlighted (ffff00)")
+                ),
+                (
+                    passage("romans 8:28"),
+                    String::from("all things, \"together\"")
+                ),
+            ],
+            imported.notes
+        );
+        assert_eq!(vec!["???"], imported.unplaced);
+
+        let clippings = "\u{feff}The Holy Bible (KJV)\r\n\
+            - Your Highlight on Location 100-102 | Added on Sunday, 1 March 2020\r\n\r\n\
+            Jesus wept.\r\n==========\r\n\
+            The Holy Bible (KJV)\r\n\
+            - Your Note on Location 102 | Added on Sunday, 1 March 2020\r\n\r\n\
+            the shortest verse\r\n==========\r\n\
+            The Holy Bible (KJV)\r\n\
+            - Your Bookmark on Location 300 | Added on Sunday, 1 March 2020\r\n\r\n\
+            ==========\r\n";
+        let imported = kindle(clippings, |_| Some(passage("john 11:35")));
+        assert_eq!(
+            vec![
+                (
+                    passage("john 11:35"),
+                    String::from("highlighted: \"Jesus wept.\"")
+                ),
+                (passage("john 11:35"), String::from("the shortest verse")),
+            ],
+            imported.notes
+        );
+    }
+}
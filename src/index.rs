@@ -0,0 +1,138 @@
+#[cfg(feature = "index")]
+use std::{io, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+#[cfg(feature = "index")]
+use fiat_lux::IndexStatus;
+use fiat_lux::Translation;
+
+use crate::{config, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct IndexArgs {
+    #[clap(subcommand)]
+    command: IndexCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum IndexCommand {
+    /// throw the search index away and build it again
+    Rebuild {
+        /// memory to build it with, in megabytes, at least 15 per thread (default: 500)
+        #[clap(long, value_name = "MB")]
+        heap_mb: Option<usize>,
+
+        /// threads to build it with (default: one per core, up to eight)
+        #[clap(long)]
+        threads: Option<usize>,
+
+        /// whether to merge segments while building
+        #[clap(long, value_enum)]
+        merge: Option<config::Merge>,
+    },
+
+    /// where the index is, how big, and what's in it
+    Status,
+
+    /// delete the search index; it's built again the next time it's needed
+    Clear,
+
+    /// take one translation out of the index, until it's reindexed or the index is rebuilt
+    RemoveTranslation { name: Translation },
+
+    /// index one translation again from its text, leaving the rest alone
+    ReindexTranslation { name: Translation },
+}
+
+#[cfg(not(feature = "index"))]
+pub fn index(_args: &IndexArgs) -> Result<()> {
+    Err(fiat_lux::Error::NoIndex.into())
+}
+
+#[cfg(feature = "index")]
+pub fn index(args: &IndexArgs) -> Result<()> {
+    let data_dir = data_dir()?;
+    match args.command {
+        IndexCommand::Rebuild { .. } => {
+            fiat_lux::clear_index(&data_dir)?;
+            println!("rebuilding the index; this takes a little while");
+            crate::open_bible()?;
+            status(IndexStatus::read(&data_dir)?);
+        }
+
+        IndexCommand::Status => status(IndexStatus::read(&data_dir)?),
+
+        IndexCommand::Clear => {
+            if fiat_lux::clear_index(&data_dir)? {
+                println!("deleted the index in {}", data_dir.display());
+            } else {
+                println!("there's no index in {}", data_dir.display());
+            }
+        }
+
+        IndexCommand::RemoveTranslation { name } => {
+            crate::open_bible()?.unindex_translation(name)?;
+            println!(
+                "removed {name} from the index; `flux index reindex-translation {}` puts it back",
+                name.to_string().to_lowercase()
+            );
+        }
+
+        IndexCommand::ReindexTranslation { name } => {
+            let count = crate::open_bible()?.reindex_translation(name)?;
+            println!("indexed {count} verses of {name}");
+        }
+    }
+
+    Ok(())
+}
+
+impl IndexArgs {
+    /// Settings given to `flux index rebuild`, which beat the configured ones for that build.
+    pub fn override_options(&self, section: &mut config::IndexSection) {
+        if let IndexCommand::Rebuild {
+            heap_mb,
+            threads,
+            merge,
+        } = self.command
+        {
+            section.heap_mb = heap_mb.or(section.heap_mb);
+            section.threads = threads.or(section.threads);
+            section.merge = merge.or(section.merge);
+        }
+    }
+}
+
+#[cfg(feature = "index")]
+fn data_dir() -> Result<PathBuf> {
+    config::data_dir().ok_or_else(|| io::Error::other("unable to find a data directory").into())
+}
+
+#[cfg(feature = "index")]
+fn status(status: Option<IndexStatus>) {
+    let Some(status) = status else {
+        println!("there's no index yet; it's built the first time it's needed");
+        return;
+    };
+
+    println!("{}", status.path.display());
+    println!(
+        "  size          {:.1} MB",
+        status.bytes as f64 / 0x100000 as f64
+    );
+    println!("  documents     {}", status.documents);
+    match status.schema_version {
+        Some(version) => println!("  schema        version {version}"),
+        None => println!("  schema        unknown"),
+    }
+    if status.lean {
+        println!("  lean; verses are read from the binary");
+    }
+    if !status.up_to_date {
+        println!("  out of date; it's rebuilt the next time it's opened");
+    }
+    println!("  translations");
+    for (translation, verses) in &status.translations {
+        println!("    {:<10}  {verses} verses", translation.to_string());
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    fs,
+    io::{self, Cursor},
+    path::PathBuf,
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use clap::Parser;
+use fiat_lux::{Book, Translation};
+use rodio::{Decoder, OutputStream, Sink};
+
+use crate::{
+    config::{state_dir, Config},
+    Error, Result,
+};
+
+#[derive(Clone, Debug, Parser)]
+pub struct ListenArgs {
+    book: Book,
+    chapter: u16,
+
+    /// start from the beginning rather than where you left off
+    #[clap(long)]
+    restart: bool,
+}
+
+pub fn listen(args: &ListenArgs, translation: Translation) -> Result<()> {
+    let config = Config::load()?;
+    let template = config
+        .audio
+        .url_for(translation)
+        .ok_or(Error::NoAudio(translation))?;
+    let source = expand(template, args.book, args.chapter);
+
+    let data = if source.starts_with("http://") || source.starts_with("https://") {
+        download(&source)?
+    } else {
+        fs::read(&source).map_err(|e| Error::audio(format!("{source}: {e}")))?
+    };
+
+    let decoder = Decoder::new(Cursor::new(data)).map_err(Error::audio)?;
+    let (_stream, handle) = OutputStream::try_default().map_err(Error::audio)?;
+    let sink = Sink::try_new(&handle).map_err(Error::audio)?;
+    sink.append(decoder);
+
+    let key = (translation.to_string(), args.book as u8, args.chapter);
+    let mut positions = Positions::load()?;
+    if let Some(position) = positions.get(&key).filter(|_| !args.restart) {
+        // Not every format can seek; if this one can't, starting over is the best we can do.
+        if sink.try_seek(position).is_ok() {
+            println!("resuming at {}", timestamp(position));
+        }
+    }
+
+    println!(
+        "{} {} ({translation}); press enter to stop",
+        args.book, args.chapter
+    );
+
+    let (stop, stopped) = mpsc::channel();
+    thread::spawn(move || {
+        let mut line = String::new();
+        let _ = io::stdin().read_line(&mut line);
+        let _ = stop.send(());
+    });
+
+    while !sink.empty() {
+        if stopped.recv_timeout(Duration::from_millis(250)).is_ok() {
+            let position = sink.get_pos();
+            sink.stop();
+            positions.set(key, position);
+            positions.save()?;
+            println!("stopped at {}", timestamp(position));
+            return Ok(());
+        }
+    }
+
+    positions.remove(&key);
+    positions.save()
+}
+
+/// Fill in a url template from the config file. `{book}` is the book's name, `{book_number}` its
+/// number (Genesis is 1), and `{chapter}` the chapter; numbers take a width for zero-padding, as
+/// in `{chapter:03}`.
+fn expand(template: &str, book: Book, chapter: u16) -> String {
+    let mut buf = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        buf.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+
+        let placeholder = &rest[start + 1..start + end];
+        let (name, width) = match placeholder.split_once(':') {
+            Some((name, width)) => (name, width.parse().unwrap_or(0)),
+            None => (placeholder, 0),
+        };
+
+        match name {
+            "book" => buf.push_str(&book.to_string()),
+            "book_number" => buf.push_str(&format!("{:0width$}", book as u8)),
+            "chapter" => buf.push_str(&format!("{chapter:0width$}")),
+            _ => buf.push_str(&rest[start..=start + end]),
+        }
+        rest = &rest[start + end + 1..];
+    }
+
+    buf.push_str(rest);
+    buf
+}
+
+fn download(url: &str) -> Result<Vec<u8>> {
+    let response = ureq::get(url).call().map_err(Error::audio)?;
+    let mut data = Vec::new();
+    io::Read::read_to_end(&mut response.into_reader(), &mut data)?;
+    Ok(data)
+}
+
+fn timestamp(position: Duration) -> String {
+    let seconds = position.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+type Key = (String, u8, u16);
+
+/// Where each unfinished chapter was stopped, one per line: translation, book number, chapter and
+/// seconds, separated by tabs.
+struct Positions {
+    entries: Vec<(Key, Duration)>,
+}
+
+impl Positions {
+    fn path() -> Option<PathBuf> {
+        state_dir().map(|dir| dir.join("listening.dat"))
+    }
+
+    fn load() -> Result<Self> {
+        let text = match Self::path().map(fs::read_to_string) {
+            Some(Ok(text)) => text,
+            Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => String::new(),
+        };
+
+        let entries = text
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                let translation = fields.next()?.to_string();
+                let book = fields.next()?.parse().ok()?;
+                let chapter = fields.next()?.parse().ok()?;
+                let seconds = fields.next()?.parse().ok()?;
+                Some(((translation, book, chapter), Duration::from_secs(seconds)))
+            })
+            .collect();
+
+        Ok(Self { entries })
+    }
+
+    fn get(&self, key: &Key) -> Option<Duration> {
+        self.entries
+            .iter()
+            .find(|(entry, _)| entry == key)
+            .map(|&(_, position)| position)
+    }
+
+    fn set(&mut self, key: Key, position: Duration) {
+        self.remove(&key);
+        self.entries.push((key, position));
+    }
+
+    fn remove(&mut self, key: &Key) {
+        self.entries.retain(|(entry, _)| entry != key);
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+
+        let mut text = String::new();
+        for ((translation, book, chapter), position) in &self.entries {
+            let seconds = position.as_secs();
+            text.push_str(&format!("{translation}\t{book}\t{chapter}\t{seconds}\n"));
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(fs::write(path, text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fiat_lux::Book;
+
+    #[test]
+    fn expand() {
+        assert_eq!(
+            "https://example.org/John/043_003.mp3",
+            super::expand(
+                "https://example.org/{book}/{book_number:03}_{chapter:03}.mp3",
+                Book::John,
+                3
+            )
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! A small lookup server for editor plugins.
+//!
+//! This isn't the language server protocol proper, just enough of its spirit for a plugin to show
+//! verses when you hover over references. Requests come in on stdin and responses go out on
+//! stdout, one json object per line:
+//!
+//! ```text
+//! {"id": 1, "method": "lookup", "params": {"reference": "John 3:16"}}
+//! {"id": 1, "result": {"reference": "John 3:16", "translation": "KJV", "text": "...", ...}}
+//! ```
+//!
+//! `lookup` takes a reference, `hover` a line of text and a position in it (in characters), and
+//! `references` some text, returning where each reference in it starts and ends. Any of them may
+//! name a translation; otherwise it's whichever the server was started with. A request which
+//! fails gets `{"id": ..., "error": {"message": "..."}}` instead.
+
+use std::io::{self, BufRead, Write};
+
+use fiat_lux::{Bible, Passage, Translation};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    format::{self, Numerals, VerseNumbers},
+    Result,
+};
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct LookupParams {
+    reference: String,
+    translation: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HoverParams {
+    line: String,
+    character: usize,
+    translation: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReferencesParams {
+    text: String,
+}
+
+pub fn lsp(translation: Translation) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let mut stdout = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => match respond(&bible, &request, translation) {
+                Ok(result) => json!({ "id": request.id, "result": result }),
+                Err(message) => json!({ "id": request.id, "error": { "message": message } }),
+            },
+            Err(e) => json!({ "id": null, "error": { "message": e.to_string() } }),
+        };
+
+        // Plugins wait on each response, so each has to go out as soon as it's written.
+        writeln!(stdout, "{response}")?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+fn respond(bible: &Bible, request: &Request, translation: Translation) -> Result<Value, String> {
+    fn params<'a, T: Deserialize<'a>>(params: &'a Value) -> Result<T, String> {
+        T::deserialize(params).map_err(|e| format!("bad params: {e}"))
+    }
+
+    match request.method.as_str() {
+        "lookup" => {
+            let params: LookupParams = params(&request.params)?;
+            let passage: Passage = params
+                .reference
+                .parse()
+                .map_err(|e: fiat_lux::ParsePassageError| e.to_string())?;
+            verses(bible, passage, pick(params.translation, translation)?)
+        }
+
+        "hover" => {
+            let params: HoverParams = params(&request.params)?;
+            let line = &params.line;
+            let at = line
+                .char_indices()
+                .nth(params.character)
+                .map_or(line.len(), |(idx, _)| idx);
+            let Some((range, passage)) = Passage::find_all(line)
+                .into_iter()
+                .find(|(range, _)| range.start <= at && at < range.end)
+            else {
+                return Ok(Value::Null);
+            };
+
+            let mut result = verses(bible, passage, pick(params.translation, translation)?)?;
+            result["range"] = characters(line, range);
+            Ok(result)
+        }
+
+        "references" => {
+            let params: ReferencesParams = params(&request.params)?;
+            let text = &params.text;
+            let references: Vec<_> = Passage::find_all(text)
+                .into_iter()
+                .map(|(range, passage)| {
+                    let mut reference = characters(text, range);
+                    reference["reference"] = json!(passage.to_string());
+                    reference
+                })
+                .collect();
+            Ok(json!(references))
+        }
+
+        method => Err(format!("unknown method '{method}'")),
+    }
+}
+
+/// The translation a request names, or failing that the server's.
+fn pick(name: Option<String>, translation: Translation) -> Result<Translation, String> {
+    name.map_or(Ok(translation), |name| {
+        name.parse()
+            .map_err(|e: fiat_lux::ParseTranslationError| e.to_string())
+    })
+}
+
+/// The text of a passage, as plain text and as markdown ready for a hover window.
+fn verses(bible: &Bible, passage: Passage, translation: Translation) -> Result<Value, String> {
+    let texts = bible
+        .lookup(passage.book, passage.location, translation)
+        .map_err(|e| e.to_string())?;
+    if texts.is_empty() {
+        return Err(format!("{passage} isn't in the {translation}"));
+    }
+
+    let text: Vec<_> = texts.iter().map(|text| text.content.as_str()).collect();
+    Ok(json!({
+        "reference": format::group_reference(&texts),
+        "translation": translation.to_string(),
+        "text": text.join(" "),
+        "markdown": format::markdown(
+            &texts,
+            translation,
+            Numerals::default(),
+            VerseNumbers::Superscript,
+        ),
+    }))
+}
+
+/// A byte range as a range of characters, which is what editors count in.
+fn characters(text: &str, range: std::ops::Range<usize>) -> Value {
+    let start = text[..range.start].chars().count();
+    let end = start + text[range].chars().count();
+    json!({ "start": start, "end": end })
+}
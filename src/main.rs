@@ -1,31 +1,103 @@
-mod book;
+mod audit;
+mod collocates;
+mod compare;
+mod config;
+mod count;
+mod define;
+mod diff;
+mod doctor;
+mod epub;
 mod error;
-mod location;
+mod expand;
+mod export;
+mod extract;
+mod format;
+mod genealogy;
+mod heatmap;
+mod history;
+mod import;
+mod index;
+#[cfg(feature = "audio")]
+mod listen;
+mod lsp;
+mod mcp;
+mod note;
+#[cfg(feature = "pager")]
+mod paging;
+mod pandoc;
+mod parallels;
+mod place;
+mod plan;
+mod preprocess;
+mod present;
+mod proof;
+mod qr;
+mod quote;
+mod random;
+mod repl;
+mod rsvp;
+mod serve;
+mod setup;
+mod stats;
+#[cfg(feature = "sword")]
+mod strongs;
+mod study;
+mod translation;
+#[cfg(feature = "tui")]
+mod tui;
+mod votd;
+mod watch;
 
 use std::{
     borrow::Cow,
-    cmp::{Ord, Ordering},
-    fmt::{self, Write},
-    io,
-    str::FromStr,
+    ops::Range,
+    sync::atomic::{AtomicBool, Ordering},
 };
 
-use book::Book;
-use clap::{Parser, Subcommand};
+use audit::AuditArgs;
+use clap::{parser::ValueSource, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
+use collocates::CollocatesArgs;
 use comfy_table::{Attribute, Cell, CellAlignment, ContentArrangement, Table};
-use directories::ProjectDirs;
-use error::{AbbrevStr, Error};
-use location::{Location, PartialLocation};
-use tantivy::{
-    collector::TopDocs,
-    directory::MmapDirectory,
-    query::{BooleanQuery, QueryParser, TermQuery},
-    schema::{Facet, Field, IndexRecordOption, Schema, Value},
-    Index, IndexWriter, ReloadPolicy, TantivyDocument as Document, Term,
+use compare::CompareArgs;
+use count::CountArgs;
+use crossterm::style::Stylize;
+use define::DefineArgs;
+use diff::DiffArgs;
+use error::Error;
+use expand::ExpandArgs;
+use export::ExportArgs;
+use extract::ExtractArgs;
+use fiat_lux::{
+    Bible, Book, CriticalNote, Gloss, PartialLocation, Reference, ReferenceLocator,
+    ReferenceProvider, Scope, Text, Translation,
 };
-
-static ASV_DAT: &str = include_str!("../resource/asv.dat");
-static KJV_DAT: &str = include_str!("../resource/kjv.dat");
+use format::{isolate, ChatStyle, CopyStyle, Numerals, OutputFormat, VerseNumbers};
+use genealogy::GenealogyArgs;
+use index::IndexArgs;
+#[cfg(feature = "audio")]
+use listen::ListenArgs;
+use note::{NoteArgs, Notes};
+use pandoc::PandocFilterArgs;
+use parallels::ParallelsArgs;
+use place::PlaceArgs;
+use plan::PlanArgs;
+use preprocess::PreprocessArgs;
+use present::PresentArgs;
+use proof::ProofArgs;
+use qr::QrArgs;
+use quote::CheckQuoteArgs;
+use random::RandomArgs;
+use rsvp::RsvpArgs;
+use serve::ServeArgs;
+use stats::StatsArgs;
+#[cfg(feature = "sword")]
+use strongs::StrongsArgs;
+use study::StudyArgs;
+use translation::TranslationCommandArgs;
+#[cfg(feature = "tui")]
+use tui::TuiArgs;
+use votd::VotdArgs;
+use watch::WatchArgs;
 
 type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -36,11 +108,184 @@ struct Args {
     book: Option<Book>,
     location: Option<PartialLocation>,
 
+    /// open the passage online, with the configured provider, instead of printing it
+    #[clap(long)]
+    open: bool,
+
+    /// print a link to the passage online instead of the passage itself
+    #[clap(long = "ref")]
+    reference: bool,
+
     #[clap(flatten)]
     translation: TranslationArgs,
 
+    /// output format
+    #[clap(short, long, global = true, value_enum, default_value_t)]
+    format: OutputFormat,
+
+    /// lay verses out the way a desktop bible app copies them, in place of the output format
+    #[clap(long, global = true, value_enum)]
+    copy_style: Option<CopyStyle>,
+
+    /// the digits for chapter and verse numbers, e.g. arabic (٣:١٦) for an Arabic translation
+    #[clap(long, global = true, value_enum)]
+    numerals: Option<Numerals>,
+
+    /// leave verse numbers out, e.g. to copy a quotation cleanly
+    #[clap(long, global = true)]
+    no_verse_numbers: bool,
+
+    /// set verse numbers in superscript, like ¹⁶
+    #[clap(long, global = true, conflicts_with = "no_verse_numbers")]
+    superscript_numbers: bool,
+
+    /// run each chapter's verses together as paragraphs, like a printed bible, not a row apiece
+    #[clap(long, global = true)]
+    paragraph: bool,
+
+    /// tint chapter headings by the kind of book: torah, history, wisdom, prophets, gospels or
+    /// epistles
+    #[clap(long, global = true)]
+    tint_books: bool,
+
+    /// set psalms and the other poetic books in lines, broken at each colon and semicolon
+    #[clap(long, global = true)]
+    poetry: bool,
+
+    /// follow key words, like lovingkindness or charity, with the hebrew or greek behind them
+    /// (hesed, agape), as a sword module tagged with strong's numbers has them
+    #[cfg(feature = "sword")]
+    #[clap(long, global = true)]
+    gloss_original: bool,
+
+    /// maximum bytes per line for irc output
+    #[clap(long, global = true, default_value_t = 400)]
+    line_bytes: usize,
+
+    /// where links to passages go: biblia, biblegateway or blueletterbible
+    #[clap(long, global = true)]
+    provider: Option<ReferenceProvider>,
+
+    /// mark verses which modern critical texts omit or bracket, with a note explaining why
+    #[clap(long, global = true)]
+    critical_notes: bool,
+
+    /// follow ancient weights, measures and money with modern equivalents
+    #[clap(long, global = true)]
+    annotate_units: bool,
+
+    /// modern spelling for archaic words and verbs, e.g. shows for sheweth; thee and thou stay
+    #[clap(long, global = true)]
+    modern_spelling: bool,
+
+    /// with --modern-spelling, make thee, thou and their verbs modern too
+    #[clap(long, global = true)]
+    modern_pronouns: bool,
+
+    /// footnote archaic words, e.g. besom or wist, with what they mean
+    #[clap(long, global = true)]
+    gloss: bool,
+
+    /// mark where new testament passages quote the old, with the source in a footnote
+    #[clap(long, global = true)]
+    mark_quotations: bool,
+
+    /// show your notes under the verses they're attached to, rather than just a ✎
+    #[clap(long, global = true)]
+    notes: bool,
+
+    /// keep the search index, history, notes and so on here instead of the usual place
+    #[clap(long, global = true, value_name = "PATH")]
+    data_dir: Option<std::path::PathBuf>,
+
+    /// build the search index in memory each time instead of keeping it on disk
+    #[clap(long, global = true)]
+    no_persist: bool,
+
+    /// how to store verses and the search index
+    #[clap(long, global = true, value_enum)]
+    backend: Option<config::Backend>,
+
+    /// never page long output
+    #[cfg(feature = "pager")]
+    #[clap(long, global = true)]
+    no_pager: bool,
+
+    /// page long output through this command, e.g. "less -R"; none turns paging off
+    #[cfg(feature = "pager")]
+    #[clap(
+        long,
+        global = true,
+        value_name = "COMMAND",
+        conflicts_with = "no_pager"
+    )]
+    pager: Option<String>,
+
+    /// use a separate config, history, notes and plans, e.g. for a study group
+    #[clap(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+
     #[clap(subcommand)]
     command: Option<Command>,
+
+    #[clap(skip)]
+    config: config::Config,
+}
+
+impl Args {
+    /// The translation asked for on the command line, or else the configured one.
+    fn translation(&self) -> Translation {
+        self.translation
+            .chosen()
+            .or(self.config.translation)
+            .unwrap_or(Translation::Kjv)
+    }
+
+    /// The digits asked for on the command line, or else the configured ones.
+    fn numerals(&self) -> Numerals {
+        self.numerals.or(self.config.numerals).unwrap_or_default()
+    }
+
+    /// How verse numbers were asked to look, if they were; otherwise each format has its own way.
+    fn verse_numbers(&self) -> Option<VerseNumbers> {
+        if self.no_verse_numbers {
+            Some(VerseNumbers::None)
+        } else if self.superscript_numbers {
+            Some(VerseNumbers::Superscript)
+        } else {
+            None
+        }
+    }
+
+    /// The colors to tint chapter headings with, if they're to be tinted.
+    fn palette(&self) -> Option<&config::PaletteSection> {
+        (self.tint_books || self.config.tint_books).then_some(&self.config.palette)
+    }
+
+    /// Whether --gloss-original was given.
+    #[cfg(feature = "sword")]
+    fn glosses_original(&self) -> bool {
+        self.gloss_original
+    }
+
+    /// Without SWORD modules to read, there's no --gloss-original to give.
+    #[cfg(not(feature = "sword"))]
+    fn glosses_original(&self) -> bool {
+        false
+    }
+
+    /// The site links go to, as given on the command line, or else as configured.
+    fn provider(&self) -> ReferenceProvider {
+        self.provider.or(self.config.provider).unwrap_or_default()
+    }
+
+    /// The output format, unless verses are being laid out in a copy style instead.
+    fn format(&self) -> Option<OutputFormat> {
+        match self.copy_style {
+            Some(_) => None,
+            None => Some(self.format),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Subcommand)]
@@ -48,224 +293,846 @@ enum Command {
     #[clap(alias = "s")]
     Search(SearchArgs),
 
+    /// print a qr code linking to a passage online
+    Qr(QrArgs),
+
+    /// show a passage full screen, one verse at a time
+    Present(PresentArgs),
+
+    /// browse the bible in a terminal interface, with a book list and search
+    #[cfg(feature = "tui")]
+    Tui(TuiArgs),
+
+    /// speed-read a passage, flashing one word at a time
+    Rsvp(RsvpArgs),
+
+    /// write a passage to a file in another format
+    Export(ExportArgs),
+
+    /// replace each {{reference}} in text on stdin with the passage, e.g. for a newsletter
+    Expand(ExpandArgs),
+
+    /// fill in each {{verse: Romans 8:28 KJV}} in a markdown file, leaving the rest as it was
+    Preprocess(PreprocessArgs),
+
+    /// list the references in a file, e.g. sermon notes, or quote each passage under its line
+    Extract(ExtractArgs),
+
+    /// a pandoc filter, footnoting each scripture reference in a document with its text
+    PandocFilter(PandocFilterArgs),
+
+    /// display the passages listed in a file, updating whenever it changes
+    Watch(WatchArgs),
+
+    /// look up passages and search at a prompt, keeping the index open between them
+    Repl,
+
+    /// answer lookups from an editor plugin, as lines of json on stdin and stdout
+    Lsp,
+
+    /// answer lookups and searches over http as json, for a web widget or a chat bot
+    Serve(ServeArgs),
+
+    /// offer lookup and search as tools to an ai assistant, over the model context protocol
+    Mcp,
+
+    /// show a passage in several translations side by side
+    #[clap(alias = "c")]
+    Compare(CompareArgs),
+
+    /// highlight the words which differ between two translations
+    Diff(DiffArgs),
+
+    /// check a quotation against the translation, word for word, e.g. for proofreading
+    CheckQuote(CheckQuoteArgs),
+
+    /// check every quotation in a document which has a reference beside it, like a spell-checker
+    Proof(ProofArgs),
+
+    /// manage user-supplied translations
+    Translation(TranslationCommandArgs),
+
+    /// rebuild, inspect or delete the search index
+    Index(IndexArgs),
+
+    /// choose a translation, colors, a pager and api keys, and write them to the config file
+    Setup,
+
+    /// check the config, data dir, index, pager, terminal and translations, suggesting fixes
+    Doctor,
+
+    /// list verses present in one translation but not another
+    AuditVersification(AuditArgs),
+
+    /// summarize a book in one line per chapter; so far only Ruth, Jonah, Mark, John and Acts
+    /// have an outline, and with no book this lists them
+    Outline { book: Option<Book> },
+
+    /// what an archaic word in the kjv means, e.g. besom, and the verses which use it
+    Define(DefineArgs),
+
+    /// describe a place and list the verses which mention it
+    Place(PlaceArgs),
+
+    /// trace a person's ancestors and descendants, e.g. David
+    Genealogy(GenealogyArgs),
+
+    /// count how often a word or phrase occurs, and in how many verses and books
+    Count(CountArgs),
+
+    /// word counts, reading time and the most frequent words in a passage
+    Stats(StatsArgs),
+
+    /// occurrences, distribution, concordance and nearby words for a word or phrase
+    Study(StudyArgs),
+
+    /// the words found most often near a word or phrase
+    Collocates(CollocatesArgs),
+
+    /// the english words a translation tagged with strong's numbers uses for one, e.g. G25
+    #[cfg(feature = "sword")]
+    Strongs(StrongsArgs),
+
+    /// keep your own notes on verses and passages
+    Note(NoteArgs),
+
+    /// today's verse, the same for everyone on a given day
+    Votd(VotdArgs),
+
+    /// a short passage picked at random, e.g. on comfort or hope, for sharing
+    Random(RandomArgs),
+
+    /// the reference to today's verse, colored, for a starship or oh-my-posh prompt segment
+    PromptSegment {
+        /// leave out the color, for prompts which style segments themselves
+        #[clap(long)]
+        plain: bool,
+    },
+
+    /// follow a daily reading plan
+    Plan(PlanArgs),
+
+    /// list your recent lookups and searches, newest first
+    History {
+        /// how many to list
+        #[clap(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// show the last passage you looked up again
+    Last,
+
+    /// show the chapter after the last one you looked up
+    Next,
+
+    /// show the chapter before the last one you looked up
+    Prev,
+
+    /// play a recording of a chapter, picking up where you left off
+    #[cfg(feature = "audio")]
+    Listen(ListenArgs),
+
+    /// list where the new testament quotes an old testament passage, or the reverse
+    Prophecy {
+        book: Book,
+        location: Option<PartialLocation>,
+    },
+
+    /// find groups of near-identical verses, like synoptic parallels and repeated refrains
+    Parallels(ParallelsArgs),
+
+    /// list the chapters which read most like a given one, e.g. parallel accounts
+    SimilarChapters {
+        book: Book,
+        chapter: u16,
+
+        /// how many chapters to list
+        #[clap(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+    },
+
+    /// convert an ancient measure, e.g. "40 cubits", to modern units
+    Convert { measure: String },
+
+    /// list the dates of the major feasts in a year (default: this year)
+    Calendar { year: Option<i32> },
+
     #[clap(hide(true))]
     Austin { location: Option<PartialLocation> },
 }
 
-#[derive(Clone, Debug, Parser)]
+#[derive(Clone, Debug, Default, Parser)]
 struct SearchArgs {
+    /// words to search for; +word must appear, -word must not, "a phrase" must appear exactly,
+    /// and a OR b matches either
     query: String,
     #[clap(short, long)]
     limit: Option<usize>,
+
+    /// only search these books (or ot, nt, gospels), comma-separated
+    #[clap(long = "in", value_delimiter = ',')]
+    scopes: Vec<Scope>,
+
+    /// show this many verses before and after each hit
+    #[clap(short = 'C', long, value_name = "N")]
+    context: Option<u16>,
+
+    /// count the hits in each book instead of listing them
+    #[clap(long, conflicts_with_all = ["limit", "context"])]
+    summary: bool,
+
+    /// chart where the hits fall in each book, chapter by chapter, instead of listing them
+    #[clap(long, conflicts_with_all = ["limit", "context", "summary"])]
+    heatmap: bool,
+
+    /// shorten each verse in the table to about N characters, keeping the matched words in view;
+    /// 0 shows them in full
+    #[clap(long, value_name = "N")]
+    truncate: Option<usize>,
+
+    /// also write every hit, not just the ones shown, with its score to a .json or .csv file
+    #[clap(long, value_name = "PATH")]
+    export: Option<std::path::PathBuf>,
+
+    /// show how the query is run and how each hit's score is worked out, instead of the verses
+    #[clap(long, conflicts_with_all = ["context", "summary", "heatmap"])]
+    explain: bool,
+
+    /// search your notes too, listing the verses and notes found together
+    #[clap(long, conflicts_with_all = ["context", "summary", "heatmap", "explain"])]
+    with_notes: bool,
 }
 
 #[derive(Clone, Copy, Debug, Parser)]
 #[clap(group(clap::ArgGroup::new("translation").required(false)))]
 struct TranslationArgs {
     /// King James Version
-    #[clap(long, group = "translation")]
+    #[clap(long, global = true, group = "translation")]
     kjv: bool,
 
     /// American Standard Version
-    #[clap(long, group = "translation")]
+    #[clap(long, global = true, group = "translation")]
     asv: bool,
-}
 
-#[derive(Clone, Copy, Debug)]
-#[repr(u8)]
-enum Translation {
-    Kjv = 1,
-    Asv = 2,
+    /// English Standard Version (remote; requires an api key)
+    #[clap(long, global = true, group = "translation")]
+    esv: bool,
+
+    /// New International Version (remote; requires an api key)
+    #[clap(long, global = true, group = "translation")]
+    niv: bool,
+
+    /// any translation by name, including ones added with `flux translation add`
+    #[clap(long = "translation", global = true, group = "translation")]
+    by_name: Option<Translation>,
 }
 
-impl Translation {
-    fn text(self) -> &'static str {
-        match self {
-            Translation::Kjv => KJV_DAT,
-            Translation::Asv => ASV_DAT,
+impl TranslationArgs {
+    /// The translation asked for on the command line, if any.
+    fn chosen(self) -> Option<Translation> {
+        if let Some(translation) = self.by_name {
+            Some(translation)
+        } else if self.kjv {
+            Some(Translation::Kjv)
+        } else if self.asv {
+            Some(Translation::Asv)
+        } else if self.esv {
+            Some(Translation::Esv)
+        } else if self.niv {
+            Some(Translation::Niv)
+        } else {
+            None
         }
     }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Chapter {
+    book: Book,
+    chapter: u16,
+}
 
-    fn facet(self) -> Facet {
-        Facet::from(&format!("/{self}"))
+impl From<&Text> for Chapter {
+    fn from(text: &Text) -> Self {
+        Chapter {
+            book: text.book,
+            chapter: text.chapter,
+        }
     }
 }
 
-impl FromStr for Translation {
-    type Err = ParseTranslationError;
+fn main() {
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if let Err(e) = configure(&mut args, &matches).and_then(|()| run(&args)) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_ascii_uppercase().as_str() {
-            "KJV" => Ok(Translation::Kjv),
-            "ASV" => Ok(Translation::Asv),
-            _ => Err(ParseTranslationError::new(s)),
+/// Read the config file, and fill in whatever wasn't given on the command line from it.
+fn configure(args: &mut Args, matches: &ArgMatches) -> Result<()> {
+    if let Some(profile) = &args.profile {
+        config::set_profile(profile)?;
+    }
+    if matches!(args.command, Some(Command::Setup)) || setup::first_run() {
+        setup::setup()?;
+    }
+    args.config = config::Config::load()?;
+    if matches.value_source("format") != Some(ValueSource::CommandLine) {
+        if let Some(format) = args.config.format {
+            args.format = format;
+        }
+    }
+    if args.copy_style.is_none() {
+        args.copy_style = args.config.copy_style;
+    }
+    if let Some(color) = args.config.color {
+        crossterm::style::force_color_output(color);
+    }
+    if let Some(path) = args.data_dir.clone().or(args.config.data_dir.clone()) {
+        config::set_data_dir(path);
+    }
+    #[cfg(feature = "pager")]
+    {
+        if args.no_pager {
+            PAGE.store(false, Ordering::Relaxed);
+        }
+        if let Some(command) = &args.pager {
+            args.config.pager = Some(command.clone());
         }
     }
+    if args.no_persist {
+        PERSIST.store(false, Ordering::Relaxed);
+    }
+    if args.backend.or(args.config.backend) == Some(config::Backend::Sqlite) {
+        if !cfg!(feature = "sqlite") {
+            return Err(Error::NoSqlite);
+        }
+        SQLITE.store(true, Ordering::Relaxed);
+    }
+    let mut section = args.config.index.clone();
+    if let Some(Command::Index(index_args)) = &args.command {
+        index_args.override_options(&mut section);
+    }
+    let _ = INDEX_OPTIONS.set(section.options(args.config.lean_index)?);
+    Ok(())
 }
 
-impl From<TranslationArgs> for Translation {
-    fn from(args: TranslationArgs) -> Self {
-        if args.asv {
-            Translation::Asv
-        } else {
-            Translation::Kjv
+fn run(args: &Args) -> Result<()> {
+    if let Some(command) = &args.command {
+        return dispatch(command, args);
+    }
+
+    let book = args.book.expect("unreachable");
+    let translation = args.translation();
+    let passage = fiat_lux::Passage {
+        book,
+        location: args.location,
+    };
+
+    // A link needs nothing looked up, so it works for remote translations without a key, too.
+    if args.open || args.reference {
+        let locator = ReferenceLocator {
+            book,
+            location: args.location,
+            translation,
+        };
+        let provider = args.provider();
+        let url = provider.url(&locator);
+        let range = args.location.is_some_and(|location| {
+            location
+                .verse
+                .zip(location.end)
+                .is_some_and(|(verse, end)| end > verse)
+        });
+        if range && !provider.links_ranges() {
+            eprintln!("{provider} can't link to a range of verses, so this goes to the first one");
         }
+        if args.reference {
+            println!("{url}");
+        }
+        if args.open {
+            open_url(&url)?;
+        }
+        history::record_lookup(passage, translation);
+        return Ok(());
     }
-}
 
-impl fmt::Display for Translation {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Translation::Kjv => f.write_str("KJV"),
-            Translation::Asv => f.write_str("ASV"),
+    // A plain lookup in one of the embedded translations comes straight from the text, so it
+    // needn't wait on the index (or build it, the first time). Marking quotations searches it.
+    match fiat_lux::lookup_embedded(book, args.location, translation) {
+        Some(texts) if !args.mark_quotations && !args.glosses_original() => {
+            show_texts(texts, Vec::new(), translation, args)?
         }
+        _ => show_passage(&open_bible()?, book, args.location, translation, args)?,
     }
+    history::record_lookup(passage, translation);
+    Ok(())
 }
 
-#[derive(Clone, Debug, thiserror::Error)]
-#[error("unknown translation '{text}'")]
-struct ParseTranslationError {
-    text: String,
-}
+/// Open a link in the default browser, with whatever the platform opens things with.
+fn open_url(url: &str) -> Result<()> {
+    let (program, args): (_, &[_]) = if cfg!(target_os = "macos") {
+        ("open", &[])
+    } else if cfg!(windows) {
+        // start treats its first quoted argument as a window title, hence the empty one.
+        ("cmd", &["/C", "start", ""])
+    } else {
+        ("xdg-open", &[])
+    };
 
-impl ParseTranslationError {
-    fn new(text: impl AbbrevStr) -> Self {
-        Self { text: text.get(7) }
+    let status = match std::process::Command::new(program)
+        .args(args)
+        .arg(url)
+        .status()
+    {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(Error::NoOpener(program)),
+        status => status?,
+    };
+    if !status.success() {
+        return Err(Error::Open(url.to_string()));
     }
+    Ok(())
 }
 
-#[derive(Clone, Debug)]
-struct Text {
-    // translation: Translation,
+/// Look up a passage and print it, with whatever marks and notes were asked for.
+fn show_passage(
+    bible: &Bible,
     book: Book,
-    chapter: u16,
-    verse: u16,
-    content: String,
-}
-
-impl Text {
-    fn from_document(document: Document, fields: &SearchFields) -> Self {
-        let location = document
-            .get_first(fields.location)
-            .unwrap()
-            .as_facet()
-            .unwrap()
-            .to_string();
-        let mut segments = location.trim_start_matches('/').split('/');
-
-        let book = segments.next().unwrap().parse::<u8>().unwrap().into();
-        let chapter = segments.next().unwrap().parse().unwrap();
-        let verse = segments.next().unwrap().parse().unwrap();
-
-        let content = document
-            .get_first(fields.content)
-            .unwrap()
-            .as_str()
-            .unwrap()
-            .into();
-
-        Self {
+    location: Option<PartialLocation>,
+    translation: Translation,
+    args: &Args,
+) -> Result<()> {
+    let mut texts = bible.lookup(book, location, translation)?;
+    let sources = mark_quotations(bible, &mut texts, translation, args)?;
+    #[cfg(feature = "sword")]
+    if args.gloss_original {
+        gloss_original(bible, &mut texts, translation)?;
+    }
+    show_texts(texts, sources, translation, args)
+}
+
+/// Print looked-up verses, with notes and the sources of any quotations marked in them.
+fn show_texts(
+    mut texts: Vec<Text>,
+    sources: Vec<String>,
+    translation: Translation,
+    args: &Args,
+) -> Result<()> {
+    let notes = annotate(&mut texts, args);
+    mark_notes(&mut texts, args)?;
+
+    if args.format() == Some(OutputFormat::Table) && texts.len() == 1 {
+        let Text {
             book,
             chapter,
             verse,
             content,
+            ..
+        } = texts.into_iter().next().unwrap();
+        let width = terminal_size::terminal_size()
+            .map_or(100, |(terminal_size::Width(w), _)| w)
+            .min(args.config.width());
+        let numerals = args.numerals();
+        let (chapter, verse) = (numerals.format(chapter), numerals.format(verse));
+        let heading = format!("{book} {chapter}:{verse}");
+        match args.palette() {
+            Some(palette) => println!("{}", heading.with(palette.color(book.genre()))),
+            None => println!("{heading}"),
+        }
+        if args.poetry && book.is_poetry() {
+            for (idx, line) in format::poetic_lines(&content).into_iter().enumerate() {
+                let indent = if idx == 0 { "" } else { "  " };
+                let options = textwrap::Options::new(usize::from(width))
+                    .initial_indent(indent)
+                    .subsequent_indent("    ");
+                for line in textwrap::wrap(line, options) {
+                    println!("{}", isolate(&line));
+                }
+            }
+        } else {
+            for line in textwrap::wrap(&content, usize::from(width)) {
+                println!("{}", isolate(&line));
+            }
         }
+    } else {
+        print_texts(&texts, translation, args);
     }
-}
-
-impl Eq for Text {}
 
-impl PartialEq for Text {
-    fn eq(&self, other: &Self) -> bool {
-        self.book == other.book && self.chapter == other.chapter && self.verse == other.verse
+    print_notes(&notes);
+    for source in sources {
+        println!("‡ {source}");
     }
+    Ok(())
 }
 
-impl Ord for Text {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match self.book.cmp(&other.book) {
-            Ordering::Equal => match self.chapter.cmp(&other.chapter) {
-                Ordering::Equal => self.verse.cmp(&other.verse),
-                ordering => ordering,
+/// Follow each English word which renders one of a list of Hebrew and Greek words with the
+/// original, as in "lovingkindness [hesed]". Which words those are comes from the passage's own
+/// SWORD module, if it's tagged with Strong's numbers, or else from CrossWire's KJV.
+#[cfg(feature = "sword")]
+fn gloss_original(bible: &Bible, texts: &mut [Text], translation: Translation) -> Result<()> {
+    for chapter in texts.chunk_by_mut(|a, b| a.book == b.book && a.chapter == b.chapter) {
+        let (book, location) = (
+            chapter[0].book,
+            PartialLocation {
+                chapter: chapter[0].chapter,
+                verse: None,
+                end: None,
             },
-            ordering => ordering,
+        );
+        let tags = match bible.tags(&translation.to_string(), book, Some(location)) {
+            Err(fiat_lux::Error::UnknownModule(_)) => bible.tags("KJV", book, Some(location)),
+            tags => tags,
+        };
+        // The passage is worth showing without its glosses.
+        let tags = match tags {
+            Err(fiat_lux::Error::UnknownModule(_)) => {
+                eprintln!(
+                    "--gloss-original needs a SWORD module tagged with strong's numbers, like \
+                    CrossWire's KJV; showing the passage without glosses"
+                );
+                return Ok(());
+            }
+            tags => tags?,
+        };
+
+        for text in chapter {
+            // Tags come in the order of the verse, so each word is looked for after the last.
+            let mut from = 0;
+            for tag in tags.iter().filter(|tag| tag.verse == text.verse) {
+                let Some(original) = fiat_lux::original_word(tag.strongs) else {
+                    continue;
+                };
+                let Some(end) = find_word(&text.content, from, &tag.word) else {
+                    continue;
+                };
+                let gloss = format!(" [{original}]");
+                text.content.insert_str(end, &gloss);
+                from = end + gloss.len();
+            }
         }
     }
+    Ok(())
 }
 
-impl PartialOrd for Text {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
+/// Where `word` first ends in `text` at or after `from`, as a whole word rather than part of one.
+#[cfg(feature = "sword")]
+fn find_word(text: &str, from: usize, word: &str) -> Option<usize> {
+    text[from..]
+        .match_indices(word)
+        .map(|(idx, _)| from + idx)
+        .find(|&start| {
+            !text[..start].ends_with(char::is_alphanumeric)
+                && !text[start + word.len()..].starts_with(char::is_alphanumeric)
+        })
+        .map(|start| start + word.len())
 }
 
-struct SearchFields {
-    translation: Field,
-    location: Field,
-    content: Field,
+/// Set off the words of each new testament verse which quote the old, returning a footnote for
+/// each quotation giving its source.
+fn mark_quotations(
+    bible: &Bible,
+    texts: &mut [Text],
+    translation: Translation,
+    args: &Args,
+) -> Result<Vec<String>> {
+    let mut sources = Vec::new();
+    if !args.mark_quotations {
+        return Ok(sources);
+    }
+
+    for text in texts.iter_mut().filter(|text| text.book >= Book::Matthew) {
+        let mut quotations = bible.quotations_in(text, translation)?;
+        if quotations.is_empty() {
+            continue;
+        }
+
+        for (_, quotation) in &quotations {
+            let source = format!(
+                "{} quotes {} ({})",
+                quotation.new, quotation.old, quotation.kind
+            );
+            if !sources.contains(&source) {
+                sources.push(source);
+            }
+        }
+
+        // Style from the back so the earlier ranges stay put; where two quotations overlap, the
+        // first one wins.
+        quotations.sort_by_key(|(span, _)| std::cmp::Reverse(span.start));
+        let mut end = text.content.len();
+        for (span, _) in quotations {
+            if span.end > end {
+                continue;
+            }
+
+            let quoted = &text.content[span.clone()];
+            let styled = match args.format() {
+                Some(OutputFormat::Table) => quoted.cyan().to_string(),
+                Some(OutputFormat::Markdown) => format!("*{quoted}*"),
+                _ => quoted.to_string(),
+            };
+            text.content.replace_range(span.clone(), &styled);
+            end = span.start;
+        }
+        text.content.push_str(" ‡");
+    }
+
+    Ok(sources)
 }
 
-impl SearchFields {
-    fn from_schema(schema: &Schema) -> Self {
-        Self {
-            translation: schema.get_field("translation").unwrap(),
-            location: schema.get_field("location").unwrap(),
-            content: schema.get_field("content").unwrap(),
+/// Whether the index is kept on disk between runs, which --no-persist turns off.
+static PERSIST: AtomicBool = AtomicBool::new(true);
+
+/// Whether verses and the index are kept in SQLite rather than tantivy, as --backend asks.
+static SQLITE: AtomicBool = AtomicBool::new(false);
+
+/// How to build and write to the index: lean or not, and the writer's memory, threads and merging.
+static INDEX_OPTIONS: std::sync::OnceLock<fiat_lux::IndexOptions> = std::sync::OnceLock::new();
+
+/// Open the index, along with any remote translations and SWORD modules the user has configured.
+fn open_bible() -> Result<Bible> {
+    let bible = match config::data_dir() {
+        Some(data_dir) if PERSIST.load(Ordering::Relaxed) => {
+            match open_backend(Some(data_dir.clone()), true) {
+                Ok(bible) => bible,
+                // A read-only data directory (a container, a live cd) is no reason to give up, though
+                // it means building the index every time.
+                Err(e) => {
+                    eprintln!(
+                        "unable to keep the index in {}: {e}; building it in memory",
+                        data_dir.display()
+                    );
+                    open_backend(Some(data_dir), false)?
+                }
+            }
         }
+        data_dir => open_backend(data_dir, false)?,
+    };
+
+    #[cfg(any(feature = "remote", feature = "sword"))]
+    let config = config::Config::load()?;
+
+    #[cfg(feature = "sword")]
+    let bible = bible.with_sword(&config.sword.libraries());
+
+    #[cfg(feature = "remote")]
+    let bible = bible.with_remote(fiat_lux::RemoteConfig {
+        esv_key: config.remote.esv_key,
+        api_bible_key: config.remote.api_bible_key,
+        niv_bible_id: config.remote.niv_bible_id,
+        cache_dir: config::project_dirs().map(|dirs| dirs.cache_dir().join("remote")),
+        cache_ttl: config
+            .remote
+            .cache_ttl_days
+            .map(|days| std::time::Duration::from_secs(days * 60 * 60 * 24)),
+        cache_max_bytes: config.remote.cache_max_mb.map(|mb| mb * 0x100000),
+        retry: config
+            .remote
+            .max_retries
+            .map_or_else(Default::default, |max_retries| fiat_lux::RetryPolicy {
+                max_retries,
+                ..Default::default()
+            }),
+        min_interval: config
+            .remote
+            .requests_per_minute
+            .filter(|&n| n > 0)
+            .map(|n| std::time::Duration::from_secs(60) / n)
+            .unwrap_or_default(),
+    });
+
+    Ok(bible)
+}
+
+/// Open whichever backend --backend picked, kept in `data_dir` if `on_disk`.
+fn open_backend(data_dir: Option<std::path::PathBuf>, on_disk: bool) -> fiat_lux::Result<Bible> {
+    #[cfg(feature = "sqlite")]
+    if SQLITE.load(Ordering::Relaxed) {
+        return match (data_dir, on_disk) {
+            (Some(data_dir), true) => Bible::open_sqlite(data_dir),
+            (data_dir, _) => Bible::open_sqlite_in_memory(data_dir),
+        };
+    }
+
+    let options = INDEX_OPTIONS.get().copied().unwrap_or_default();
+    match (data_dir, on_disk) {
+        (Some(data_dir), true) => Bible::open_with(data_dir, options),
+        (data_dir, _) => Bible::open_in_memory_with(data_dir, options),
     }
 }
 
-fn main() {
-    let args = Args::parse();
-    if let Err(e) = run(&args) {
-        eprintln!("{e}");
-        std::process::exit(1);
+fn print_texts(texts: &[Text], translation: Translation, args: &Args) {
+    let numbers = |default| args.verse_numbers().unwrap_or(default);
+    if let Some(style) = args.copy_style {
+        let copied = format::copy(texts, translation, style, numbers(VerseNumbers::Plain));
+        print!("{copied}");
+        return;
+    }
+
+    match args.format {
+        OutputFormat::Table => format_texts(texts, args),
+        OutputFormat::Markdown => {
+            let numbers = numbers(VerseNumbers::Superscript);
+            print!(
+                "{}",
+                format::markdown(texts, translation, args.numerals(), numbers)
+            )
+        }
+        OutputFormat::Discord => {
+            let numbers = numbers(VerseNumbers::Superscript);
+            print!(
+                "{}",
+                format::chat(texts, translation, ChatStyle::DISCORD, numbers)
+            )
+        }
+        OutputFormat::Slack => {
+            let numbers = numbers(VerseNumbers::Superscript);
+            print!(
+                "{}",
+                format::chat(texts, translation, ChatStyle::SLACK, numbers)
+            )
+        }
+        OutputFormat::Irc => {
+            let numbers = numbers(VerseNumbers::Plain);
+            print!(
+                "{}",
+                format::irc(texts, translation, args.line_bytes, numbers)
+            )
+        }
+        OutputFormat::Ris => print!("{}", format::ris(texts, translation)),
+        OutputFormat::Bibtex => print!("{}", format::bibtex(texts, translation)),
     }
 }
 
-fn run(args: &Args) -> Result<()> {
-    if let Some(command) = &args.command {
-        return dispatch(command, args.translation.into());
+/// Notes to print after a passage, each marked where it applies.
+#[derive(Default)]
+struct Footnotes {
+    critical: Vec<&'static CriticalNote>,
+    glosses: Vec<Gloss>,
+}
+
+/// Apply whichever annotations were asked for: modern spelling, modern units after ancient ones,
+/// a numbered gloss on each archaic word and a dagger on each disputed verse, returning the notes
+/// to print afterward.
+fn annotate(texts: &mut [Text], args: &Args) -> Footnotes {
+    if args.modern_spelling || args.modern_pronouns {
+        for text in texts.iter_mut() {
+            text.content = fiat_lux::modernize(&text.content, args.modern_pronouns);
+        }
     }
 
-    let book = args.book.expect("unreachable");
-    let (index, fields) = initialize_search()?;
-    let texts = search_by_book_and_location(
-        &index,
-        &fields,
-        book,
-        args.location,
-        args.translation.into(),
-    )?;
+    if args.annotate_units {
+        for text in texts.iter_mut() {
+            text.content = fiat_lux::annotate_units(&text.content);
+        }
+    }
 
-    if texts.len() == 1 {
-        let Text {
-            book,
-            chapter,
-            verse,
-            content,
-            ..
-        } = texts.into_iter().next().unwrap();
-        let width =
-            terminal_size::terminal_size().map_or(100, |(terminal_size::Width(w), _)| w.min(100));
-        let content = textwrap::fill(&content, usize::from(width));
-        println!("{book} {chapter}:{verse}\n{content}");
-    } else {
-        format_texts(&texts);
+    let mut notes = Footnotes::default();
+    if args.gloss {
+        for text in texts.iter_mut() {
+            let marks: Vec<_> = Gloss::find_in(&text.content)
+                .into_iter()
+                .map(|(range, gloss)| {
+                    let number = match notes.glosses.iter().position(|&seen| seen == gloss) {
+                        Some(idx) => idx + 1,
+                        None => {
+                            notes.glosses.push(gloss);
+                            notes.glosses.len()
+                        }
+                    };
+                    (range.end, format::superscript(number as u16))
+                })
+                .collect();
+            // Marking from the end keeps the earlier positions where they were.
+            for (end, mark) in marks.into_iter().rev() {
+                text.content.insert_str(end, &mark);
+            }
+        }
+    }
+
+    if !args.critical_notes {
+        return notes;
     }
 
+    for text in texts {
+        if let Some(note) = CriticalNote::find(text.book, text.chapter, text.verse) {
+            text.content.push_str(" †");
+            if !notes.critical.iter().any(|&seen| std::ptr::eq(seen, note)) {
+                notes.critical.push(note);
+            }
+        }
+    }
+
+    notes
+}
+
+/// Mark each verse you've left a note on, and with --notes, follow it with the note itself. A
+/// note on a whole passage goes with its first verse.
+fn mark_notes(texts: &mut [Text], args: &Args) -> Result<()> {
+    let notes = Notes::load()?;
+    let mut shown = Vec::new();
+    for text in texts {
+        let on = notes.on(text);
+        if on.is_empty() {
+            continue;
+        }
+
+        text.content.push_str(" ✎");
+        if !args.notes {
+            continue;
+        }
+
+        for note in on {
+            if shown.iter().any(|&seen| std::ptr::eq(seen, note)) {
+                continue;
+            }
+            shown.push(note);
+
+            let (_, note) = note;
+            match args.format() {
+                Some(OutputFormat::Table) => {
+                    text.content.push('\n');
+                    text.content.push_str(&note.as_str().italic().to_string());
+                }
+                _ => text.content.push_str(&format!(" [{note}]")),
+            }
+        }
+    }
     Ok(())
 }
 
-fn format_texts(texts: &[Text]) {
+fn print_notes(notes: &Footnotes) {
+    if !notes.critical.is_empty() || !notes.glosses.is_empty() {
+        println!();
+    }
+
+    for (idx, gloss) in notes.glosses.iter().enumerate() {
+        println!("{} {gloss}", format::superscript(idx as u16 + 1));
+    }
+
+    for note in &notes.critical {
+        println!("† {note}");
+    }
+}
+
+/// Whether long output goes through a pager. Setting one up hands stdout to it for the rest of the
+/// process, so anything which prints more than once (the repl) turns this off.
+#[cfg(feature = "pager")]
+static PAGE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(true);
+
+fn format_texts(texts: &[Text], args: &Args) {
     #[cfg(feature = "pager")]
     let width = {
         let (w, h) = terminal_size::terminal_size()
             .map(|(terminal_size::Width(w), terminal_size::Height(h))| (w, h))
             .unwrap_or((100, 20));
 
-        if texts.len() > h as usize {
-            pager::Pager::with_default_pager("bat").setup();
+        if texts.len() > h as usize && PAGE.load(std::sync::atomic::Ordering::Relaxed) {
+            if let Some(command) = paging::choose(args.config.pager.as_deref()) {
+                pager::Pager::with_pager(&command).setup();
+            }
         }
 
         w
@@ -279,107 +1146,217 @@ fn format_texts(texts: &[Text]) {
         w
     };
 
-    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-    struct Chapter {
-        book: Book,
-        chapter: u16,
-    }
-
-    impl Text {
-        fn chapter(&self) -> Chapter {
-            Chapter {
-                book: self.book,
-                chapter: self.chapter,
-            }
-        }
+    let width = width.min(args.config.width());
+    if args.paragraph {
+        let numbers = args.verse_numbers().unwrap_or(VerseNumbers::Superscript);
+        let text = format::paragraphs(
+            texts,
+            usize::from(width),
+            args.numerals(),
+            numbers,
+            args.palette(),
+            args.poetry,
+        );
+        print!("{text}");
+    } else {
+        println!("{}", build_table(texts, width, args));
     }
+}
 
+/// Lay out verses one per row, with a heading at the start of each chapter, and the verse numbers
+/// in a column of their own unless they're left out.
+fn build_table(texts: &[Text], width: u16, args: &Args) -> Table {
+    let numerals = args.numerals();
+    let numbers = args.verse_numbers().unwrap_or(VerseNumbers::Plain);
     let mut current: Option<Chapter> = None;
     let mut table = Table::new();
 
     table.set_content_arrangement(ContentArrangement::DynamicFullWidth);
     table.load_preset(comfy_table::presets::NOTHING);
-    table.set_width(width.min(100));
+    table.set_width(width);
 
     for text in texts {
         if current.is_none()
             || !current
-                .map(|chapter| chapter == text.chapter())
+                .map(|chapter| chapter == Chapter::from(text))
                 .unwrap_or_default()
         {
-            let next = text.chapter();
+            let next = Chapter::from(text);
             let Chapter { book, chapter } = next;
             current = Some(next);
-            table.add_row(vec![
-                Cell::new(""),
-                Cell::new(format!("\n{book} {chapter}")).add_attribute(Attribute::Bold),
-            ]);
+            let heading = format!("{book} {}", numerals.format(chapter));
+            let heading = match args.palette() {
+                Some(palette) => heading.with(palette.color(book.genre())).to_string(),
+                None => heading,
+            };
+            let heading = Cell::new(format!("\n{heading}")).add_attribute(Attribute::Bold);
+            match numbers {
+                VerseNumbers::None => table.add_row(vec![heading]),
+                _ => table.add_row(vec![Cell::new(""), heading]),
+            };
         }
 
-        let verse = text.verse;
-        let content = &text.content;
-        table.add_row(&[Cow::from(format!("{verse:4}")), Cow::from(content)]);
+        let content = if args.poetry && text.book.is_poetry() {
+            // No-break spaces, since the table drops plain ones from the start of a line.
+            let lines = format::poetic_lines(&text.content).join("\n\u{a0}\u{a0}");
+            Cow::Owned(isolate(&lines).into_owned())
+        } else {
+            isolate(&text.content)
+        };
+        match numbers {
+            VerseNumbers::None => table.add_row(&[content]),
+            numbers => {
+                let verse = numbers.format(numerals, text.verse);
+                table.add_row(&[Cow::from(format!("{verse:>4}")), content])
+            }
+        };
     }
 
-    table
+    // No rows means no columns, e.g. for a chapter missing from a partial translation.
+    if let Some(column) = table
         .column_mut(0)
-        .unwrap()
-        .set_cell_alignment(CellAlignment::Right);
-
-    println!("{table}");
-}
-
-fn search_by_book_and_location(
-    index: &Index,
-    fields: &SearchFields,
-    book: Book,
-    location: Option<PartialLocation>,
-    translation: Translation,
-) -> tantivy::Result<Vec<Text>> {
-    let mut buf = format!("/{}", book as u8);
-    if let Some(location) = &location {
-        let chapter = location.chapter;
-        write!(buf, "/{chapter}").unwrap();
-        if let Some(verse) = location.verse {
-            write!(buf, "/{verse}").unwrap()
-        }
+        .filter(|_| numbers != VerseNumbers::None)
+    {
+        column.set_cell_alignment(CellAlignment::Right);
     }
 
-    let location = TermQuery::new(
-        Term::from_facet(fields.location, &Facet::from(&buf)),
-        IndexRecordOption::Basic,
-    );
-    let translation = TermQuery::new(
-        Term::from_facet(fields.translation, &Facet::from(&format!("/{translation}"))),
-        IndexRecordOption::Basic,
-    );
-    let query = BooleanQuery::intersection(vec![Box::new(location), Box::new(translation)]);
-
-    let reader = index
-        .reader_builder()
-        .reload_policy(ReloadPolicy::Manual)
-        .try_into()?;
-    let searcher = reader.searcher();
-    // In this case, we don't actually want to limit the docs returned, and the number will be
-    // small in most cases, but I have no idea what collector to use or how, so...
-    let documents = searcher
-        .search(&query, &TopDocs::with_limit(10_000))?
-        .into_iter()
-        .map(|(_, candidate)| searcher.doc(candidate));
-
-    let mut texts = Vec::new();
-    for document in documents {
-        texts.push(Text::from_document(document?, fields));
-    }
-    texts.sort();
-    Ok(texts)
+    table
 }
 
-fn dispatch(command: &Command, translation: Translation) -> Result<()> {
+fn dispatch(command: &Command, args: &Args) -> Result<()> {
+    let translation = args.translation();
     match command {
         // It is not obvious to me that a search should be performed against a given translation
         // rather than all translations, but we can revisit this later.
-        Command::Search(args) => search(args, translation),
+        Command::Search(search_args) => search(&open_bible()?, search_args, translation, args),
+
+        Command::Qr(qr_args) => qr::qr(qr_args, translation, args.provider()),
+
+        Command::Present(args) => present::present(args, translation),
+
+        #[cfg(feature = "tui")]
+        Command::Tui(tui_args) => {
+            tui::tui(tui_args, args.translation.chosen(), args.config.translation)
+        }
+
+        Command::Rsvp(args) => rsvp::rsvp(args, translation),
+
+        Command::Export(export_args) => export::export(
+            export_args,
+            translation,
+            args.numerals(),
+            args.verse_numbers(),
+        ),
+
+        Command::Expand(expand_args) => expand::expand(expand_args, translation, args),
+
+        Command::Preprocess(args) => preprocess::preprocess(args, translation),
+
+        Command::Extract(args) => extract::extract(args, translation),
+
+        Command::PandocFilter(args) => pandoc::pandoc_filter(args, translation),
+
+        Command::Watch(watch_args) => watch::watch(watch_args, translation, args),
+
+        Command::Repl => repl::repl(translation, args),
+
+        Command::Lsp => lsp::lsp(translation),
+
+        Command::Serve(serve_args) => serve::serve(serve_args, translation),
+
+        Command::Mcp => mcp::mcp(translation),
+
+        Command::Compare(args) => compare::compare(args),
+
+        Command::Diff(args) => diff::diff(args),
+
+        Command::CheckQuote(args) => quote::check_quote(args, translation),
+
+        Command::Proof(args) => proof::proof(args, translation),
+
+        Command::Translation(args) => translation::translation(args),
+
+        Command::Index(args) => index::index(args),
+
+        Command::Doctor => doctor::doctor(),
+
+        // Already done by `configure`, before the config was read.
+        Command::Setup => Ok(()),
+
+        Command::AuditVersification(args) => audit::audit_versification(args),
+
+        Command::Place(place_args) => place::place(place_args, translation, args),
+        Command::Define(define_args) => define::define(define_args, args),
+
+        Command::Genealogy(args) => genealogy::genealogy(args),
+
+        Command::Count(args) => count::count(args, translation),
+
+        Command::Stats(args) => stats::stats(args, translation),
+
+        Command::Study(args) => study::study(args, translation),
+
+        Command::Collocates(args) => collocates::collocates(args, translation),
+
+        #[cfg(feature = "sword")]
+        Command::Strongs(args) => strongs::strongs(args),
+
+        Command::Note(args) => note::note(args, translation),
+
+        Command::Votd(votd_args) => votd::votd(votd_args, translation, args),
+
+        Command::Random(random_args) => random::random(random_args, translation, args),
+
+        Command::PromptSegment { plain } => {
+            votd::prompt_segment(*plain);
+            Ok(())
+        }
+
+        Command::Plan(plan_args) => plan::plan(plan_args, translation, args),
+
+        Command::History { limit } => history::history(*limit),
+
+        Command::Last => history::last(args),
+
+        Command::Next => history::step(args, true),
+
+        Command::Prev => history::step(args, false),
+
+        #[cfg(feature = "audio")]
+        Command::Listen(args) => listen::listen(args, translation),
+
+        Command::Prophecy { book, location } => {
+            prophecy(*book, *location);
+            Ok(())
+        }
+
+        Command::Parallels(args) => parallels::parallels(args, translation),
+
+        Command::SimilarChapters {
+            book,
+            chapter,
+            limit,
+        } => similar_chapters(*book, *chapter, *limit, translation),
+
+        Command::Convert { measure } => {
+            let converted = fiat_lux::Measure::parse(measure)
+                .ok_or_else(|| Error::UnknownUnit(measure.clone()))?;
+            println!("{} ≈ {converted}", measure.trim());
+            Ok(())
+        }
+
+        Command::Outline { book } => {
+            match book {
+                Some(book) => outline(*book),
+                None => outlined_books(),
+            }
+            Ok(())
+        }
+
+        Command::Calendar { year } => {
+            calendar(year.unwrap_or_else(|| fiat_lux::Date::today().year));
+            Ok(())
+        }
 
         // This code does not exist. Do not read this code.
         // Also don't watch this video:
@@ -389,6 +1366,7 @@ fn dispatch(command: &Command, translation: Translation) -> Result<()> {
                 let expected = PartialLocation {
                     chapter: 3,
                     verse: Some(16),
+                    end: None,
                 };
 
                 if location == &expected {
@@ -401,126 +1379,353 @@ fn dispatch(command: &Command, translation: Translation) -> Result<()> {
     }
 }
 
-fn search(args: &SearchArgs, translation: Translation) -> Result<()> {
-    let (index, fields) = initialize_search()?;
+/// Embolden the given byte ranges of a string.
+fn highlight(content: &str, ranges: &[Range<usize>]) -> String {
+    let mut buf = String::with_capacity(content.len());
+    let mut last = 0;
+    for range in ranges {
+        buf.push_str(&content[last..range.start]);
+        buf.push_str(&content[range.clone()].yellow().bold().to_string());
+        last = range.end;
+    }
+    buf.push_str(&content[last..]);
+    buf
+}
 
-    let reader = index
-        .reader_builder()
-        .reload_policy(ReloadPolicy::Manual)
-        .try_into()?;
-    let searcher = reader.searcher();
+fn outline(book: Book) {
+    let outline = fiat_lux::outline(book);
+    if outline.is_empty() {
+        println!("no outline for {book}; only these books have one:");
+        outlined_books();
+        return;
+    }
 
-    // This query parser constructs a query from the user's search string. We can break the search
-    // string into multiple strings at some point to make the cli less annoying, maybe? But for now
-    // the user provides a monolithic string.
+    println!("{book}");
+    for (chapter, summary) in outline {
+        println!("{chapter:4}  {summary}");
+    }
+}
 
-    let query_parser = QueryParser::for_index(&index, vec![fields.content]);
-    let query = query_parser.parse_query(&args.query)?;
+fn outlined_books() {
+    for book in fiat_lux::outlined_books() {
+        println!("{book}");
+    }
+}
 
-    // That gives us one search term. We need to make a second term for the facet referencing the
-    // correct translation.
+fn build_summary(counts: &[(Book, u64)]) -> Table {
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::NOTHING);
 
-    let translation_term = Term::from_facet(fields.translation, &translation.facet());
-    let term_query = TermQuery::new(translation_term, IndexRecordOption::Basic);
+    for (book, count) in counts {
+        table.add_row(vec![book.to_string(), count.to_string()]);
+    }
 
-    // Damned if I know the correct way to do this, but this seems to work, so....
+    let testament = |new: bool| -> u64 {
+        counts
+            .iter()
+            .filter(|(book, _)| (*book >= Book::Matthew) == new)
+            .map(|(_, count)| count)
+            .sum()
+    };
+    let (old, new) = (testament(false), testament(true));
+
+    table.add_row(vec![String::new(), String::new()]);
+    table.add_row(vec![String::from("Old Testament"), old.to_string()]);
+    table.add_row(vec![String::from("New Testament"), new.to_string()]);
+    table.add_row(vec![
+        Cell::new("Total").add_attribute(Attribute::Bold),
+        Cell::new(old + new).add_attribute(Attribute::Bold),
+    ]);
+
+    if let Some(column) = table.column_mut(1) {
+        column.set_cell_alignment(CellAlignment::Right);
+    }
 
-    let combined_query = BooleanQuery::intersection(vec![query, Box::new(term_query)]);
-    let mut texts: Vec<_> = searcher
-        .search(
-            &combined_query,
-            &TopDocs::with_limit(args.limit.unwrap_or(10)),
-        )?
-        .into_iter()
-        .filter_map(|(_, address)| searcher.doc(address).ok())
-        .map(|document| Text::from_document(document, &fields))
-        .collect();
+    table
+}
 
-    texts.sort();
-    format_texts(&texts);
+/// Surround each hit with the verses on either side of it (within its chapter). Hits close together
+/// share their context rather than repeating it.
+fn add_context(
+    bible: &Bible,
+    texts: &mut Vec<Text>,
+    context: u16,
+    translation: Translation,
+) -> Result<()> {
+    let mut surrounding = Vec::new();
+    for text in texts.iter() {
+        let location = PartialLocation {
+            chapter: text.chapter,
+            verse: Some(text.verse.saturating_sub(context).max(1)),
+            end: Some(text.verse.saturating_add(context)),
+        };
+        surrounding.extend(bible.lookup(text.book, Some(location), translation)?);
+    }
 
+    // The sort is stable, so each hit stays ahead of its (unhighlighted) copy from the lookups.
+    texts.extend(surrounding);
+    texts.sort();
+    texts.dedup();
     Ok(())
 }
 
-fn initialize_search() -> tantivy::Result<(Index, SearchFields)> {
-    // We want to store our data someplace sane, so we're gonna use the directories library to
-    // decide where all this data goes.
+fn prophecy(book: Book, location: Option<PartialLocation>) {
+    let passage = fiat_lux::Passage { book, location };
+    let mut quotations = fiat_lux::Quotation::find(&passage);
+    if quotations.is_empty() {
+        println!("no quotations of or in {passage}");
+        return;
+    }
 
-    let dirs = ProjectDirs::from("org", "Hack Commons", "Bible-App").ok_or_else(|| {
-        io::Error::new(
-            io::ErrorKind::Other,
-            "unable to initialize project directory",
-        )
-    })?;
+    // Put the side that was asked about first.
+    let old_testament = book < Book::Matthew;
+    if old_testament {
+        quotations.sort_by_key(|quotation| {
+            let location = quotation.old.location;
+            (
+                quotation.old.book,
+                location.map(|location| (location.chapter, location.verse)),
+            )
+        });
+    }
+    let pairs: Vec<_> = quotations
+        .iter()
+        .map(|quotation| {
+            let (from, to) = if old_testament {
+                (quotation.old, quotation.new)
+            } else {
+                (quotation.new, quotation.old)
+            };
+            (from.to_string(), to.to_string(), quotation.kind)
+        })
+        .collect();
 
-    // Well need to ensure the directory exists. That's easy, but I'm not sure how to know if
-    // there is an existing index in an existing directory. That seems important.
+    let arrow = if old_testament { "→" } else { "←" };
+    let width = pairs
+        .iter()
+        .map(|(from, ..)| from.len())
+        .max()
+        .unwrap_or_default();
+    let to_width = pairs
+        .iter()
+        .map(|(_, to, _)| to.len())
+        .max()
+        .unwrap_or_default();
+    for (from, to, kind) in pairs {
+        println!("{from:width$}  {arrow}  {to:to_width$}  {kind}");
+    }
+}
 
-    let index_path = dirs.data_dir().join("bible_idx");
-    if !index_path.exists() {
-        std::fs::create_dir_all(&index_path)?;
+fn similar_chapters(
+    book: Book,
+    chapter: u16,
+    limit: usize,
+    translation: Translation,
+) -> Result<()> {
+    if chapter == 0 || chapter > book.chapters() {
+        return Err(Error::UnknownChapter(book, chapter));
     }
 
-    let schema = build_schema();
-    let fields = SearchFields::from_schema(&schema);
+    let bible = open_bible()?;
+    let similar = bible.similar_chapters(book, chapter, translation, limit)?;
+    let labels: Vec<_> = similar
+        .iter()
+        .map(|(book, chapter, _)| format!("{book} {chapter}"))
+        .collect();
+    let width = labels.iter().map(String::len).max().unwrap_or_default();
+
+    println!("{book} {chapter} ({translation})");
+    for (label, (_, _, score)) in labels.iter().zip(&similar) {
+        println!("  {label:width$}  {score:.3}");
+    }
+    Ok(())
+}
 
-    let index_dir = MmapDirectory::open(&index_path)?;
-    if !tantivy::Index::exists(&index_dir)? {
-        let index = Index::create_in_dir(index_path, schema)?;
+fn calendar(year: i32) {
+    let feasts = fiat_lux::feasts(year);
+    let width = feasts
+        .iter()
+        .map(|feast| feast.name.len())
+        .max()
+        .unwrap_or_default();
+
+    println!("{year} (Jewish feasts begin at sundown the evening before)");
+    for feast in feasts {
+        println!(
+            "{}  {:width$}  {}",
+            feast.date,
+            feast.name,
+            feast.passages.join("; ")
+        );
+    }
+}
 
-        /// 500 megabytes
-        const ARENA_SIZE: usize = 0x100000 * 500;
-        write_index(Translation::Kjv, &fields, &mut index.writer(ARENA_SIZE)?)?;
-        write_index(Translation::Asv, &fields, &mut index.writer(ARENA_SIZE)?)?;
+/// Verses and notes matching a search, in one list. Each is scored by an index of its own, so a
+/// score counts as its share of the best in its own list when the two are merged.
+fn search_with_notes(
+    bible: &Bible,
+    search_args: &SearchArgs,
+    translation: Translation,
+    args: &Args,
+) -> Result<()> {
+    let query = &search_args.query;
+    let limit = search_args.limit.unwrap_or(10);
+    let mut verses = bible.search_scored(query, translation, &search_args.scopes)?;
+    verses.truncate(limit);
+    let notes = Notes::load()?;
+    let found = notes.search(query, &search_args.scopes, limit)?;
+
+    // Both lists come best first.
+    let best_verse = verses.first().map_or(1.0, |(score, _)| *score);
+    let best_note = found.first().map_or(1.0, |(score, _)| *score);
+
+    let mut rows: Vec<(f32, String, String, String)> = verses
+        .into_iter()
+        .map(|(score, text)| {
+            let reference = format!("{} {}:{}", text.book, text.chapter, text.verse);
+            (
+                score / best_verse,
+                translation.to_string(),
+                reference,
+                text.content,
+            )
+        })
+        .chain(found.into_iter().map(|(score, (passage, note))| {
+            (
+                score / best_note,
+                String::from("note"),
+                passage.to_string(),
+                note.clone(),
+            )
+        }))
+        .collect();
+    rows.sort_by(|a, b| b.0.total_cmp(&a.0));
+    rows.truncate(limit);
 
-        Ok((index, fields))
-    } else {
-        Ok((tantivy::Index::open(index_dir)?, fields))
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::DynamicFullWidth);
+    table.load_preset(comfy_table::presets::NOTHING);
+    table.set_width(
+        terminal_size::terminal_size()
+            .map_or(100, |(terminal_size::Width(w), _)| w)
+            .min(args.config.width()),
+    );
+    for (_, source, reference, content) in rows {
+        let ranges = bible.highlights(query, &content)?;
+        table.add_row(vec![
+            Cell::new(source).add_attribute(Attribute::Dim),
+            Cell::new(reference).add_attribute(Attribute::Bold),
+            Cell::new(highlight(&content, &ranges)),
+        ]);
+    }
+    println!("{table}");
+    history::record_search(query, translation);
+    Ok(())
+}
+
+fn print_explanation(explanation: &fiat_lux::Explanation) {
+    println!("{}", "query".bold());
+    print!("{}", explanation.query);
+    println!("\n{}", "facets".bold());
+    for facet in &explanation.facets {
+        println!("  {facet}");
+    }
+
+    println!("\n{}", "hits".bold());
+    if explanation.hits.is_empty() {
+        println!("  none");
+    }
+    for hit in &explanation.hits {
+        let text = &hit.text;
+        println!(
+            "  {} {}:{}  {:.4}  {}",
+            text.book, text.chapter, text.verse, hit.score, text.content
+        );
+        for line in hit.breakdown.lines() {
+            println!("    {line}");
+        }
     }
 }
 
-fn write_index(
+fn search(
+    bible: &Bible,
+    search_args: &SearchArgs,
     translation: Translation,
-    fields: &SearchFields,
-    writer: &mut IndexWriter,
-) -> tantivy::Result<()> {
-    use tantivy::doc;
+    args: &Args,
+) -> Result<()> {
+    if let Some(path) = &search_args.export {
+        let hits = bible.search_scored(&search_args.query, translation, &search_args.scopes)?;
+        export::search_results(path, &hits, translation)?;
+        eprintln!("wrote {} hits to {}", hits.len(), path.display());
+    }
 
-    for (id, text) in parse_verses_with_id(translation.text()) {
-        let Location {
-            book,
-            chapter,
-            verse,
-        } = Location::from_id(id);
+    if search_args.explain {
+        let limit = search_args.limit.unwrap_or(10);
+        let explanation =
+            bible.explain(&search_args.query, translation, &search_args.scopes, limit)?;
+        print_explanation(&explanation);
+        return Ok(());
+    }
+
+    if search_args.with_notes {
+        return search_with_notes(bible, search_args, translation, args);
+    }
 
-        let book = book as u8;
-        let location = Facet::from(&format!("/{book}/{chapter}/{verse}"));
-        let translation = Facet::from(&format!("/{translation}"));
+    if search_args.summary {
+        let counts = bible.count_by_book(&search_args.query, translation, &search_args.scopes)?;
+        println!("{}", build_summary(&counts));
+        return Ok(());
+    }
 
-        writer.add_document(doc!(
-            fields.translation => translation,
-            fields.location => location,
-            fields.content => text,
-        ))?;
+    if search_args.heatmap {
+        let counts =
+            bible.count_by_chapter(&search_args.query, translation, &search_args.scopes)?;
+        let width = terminal_size::terminal_size()
+            .map_or(100, |(terminal_size::Width(w), _)| w)
+            .min(args.config.width());
+        print!("{}", heatmap::heatmap(&counts, usize::from(width)));
+        return Ok(());
     }
 
-    writer.commit()?;
-    Ok(())
-}
+    let limit = search_args.limit.unwrap_or(10);
+    let mut texts = bible.search_in(&search_args.query, translation, &search_args.scopes, limit)?;
 
-fn build_schema() -> Schema {
-    use tantivy::schema;
+    // Only the table is for reading there and then; the other formats get pasted somewhere, so
+    // they keep every word.
+    let truncate = if args.format() == Some(OutputFormat::Table) {
+        search_args
+            .truncate
+            .or(args.config.search.truncate)
+            .filter(|&max| max > 0)
+    } else {
+        None
+    };
 
-    let facet_options = schema::INDEXED | schema::STORED;
+    if args.format() == Some(OutputFormat::Table) {
+        for text in &mut texts {
+            let ranges = bible.highlights(&search_args.query, &text.content)?;
+            let (content, ranges) = match truncate {
+                Some(max) => format::truncate(&text.content, &ranges, max),
+                None => (text.content.clone(), ranges),
+            };
+            text.content = highlight(&content, &ranges);
+        }
+    }
 
-    let mut builder = Schema::builder();
-    builder.add_facet_field("translation", facet_options.clone());
-    builder.add_facet_field("location", facet_options);
-    builder.add_text_field("content", schema::TEXT | schema::STORED);
-    builder.build()
-}
+    if let Some(context) = search_args.context {
+        let hits = texts.clone();
+        add_context(bible, &mut texts, context, translation)?;
+        if let Some(max) = truncate {
+            for text in texts.iter_mut().filter(|text| !hits.contains(text)) {
+                text.content = format::truncate(&text.content, &[], max).0;
+            }
+        }
+    }
 
-fn parse_verses_with_id(text: &str) -> impl Iterator<Item = (u64, &str)> {
-    text.lines()
-        .filter_map(|line| line[..8].parse::<u64>().ok().map(|id| (id, &line[9..])))
+    let notes = annotate(&mut texts, args);
+    mark_notes(&mut texts, args)?;
+    print_texts(&texts, translation, args);
+    print_notes(&notes);
+    history::record_search(&search_args.query, translation);
+    Ok(())
 }
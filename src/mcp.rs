@@ -0,0 +1,269 @@
+//! A Model Context Protocol server, so an assistant can look verses up and search the local
+//! corpus rather than quoting scripture from memory.
+//!
+//! MCP is JSON-RPC 2.0 over stdio, one message per line, like `flux lsp`. The client opens with
+//! `initialize`, then lists the tools with `tools/list` and calls them with `tools/call`:
+//!
+//! ```text
+//! {"jsonrpc": "2.0", "id": 3, "method": "tools/call",
+//!  "params": {"name": "lookup", "arguments": {"reference": "John 3:16"}}}
+//! {"jsonrpc": "2.0", "id": 3,
+//!  "result": {"content": [{"type": "text", "text": "John 3:16 (KJV)\n..."}], "isError": false}}
+//! ```
+//!
+//! A tool which fails, e.g. on a reference that doesn't parse, answers with `isError` and the
+//! message as its text, so the assistant can see what went wrong and try again. Protocol errors,
+//! like an unknown method, get a JSON-RPC error instead.
+
+use std::io::{self, BufRead, Write};
+
+use fiat_lux::{Bible, Passage, Scope, Translation};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{format, Result};
+
+/// The protocol versions we know how to speak, newest first. A client asking for another gets the
+/// newest, and can hang up if it doesn't speak that.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// The most search hits one call gets; assistants are better served by a tighter query than by
+/// pages of verses.
+const MAX_LIMIT: usize = 50;
+
+// JSON-RPC's error codes
+const PARSE_ERROR: i32 = -32700;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    /// Notifications have no id, and get no response.
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolCall {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct LookupArguments {
+    reference: String,
+    translation: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchArguments {
+    query: String,
+    translation: Option<String>,
+    limit: Option<usize>,
+    #[serde(rename = "in")]
+    scopes: Option<String>,
+}
+
+pub fn mcp(translation: Translation) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let mut stdout = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(Request { id: None, .. }) => continue,
+            Ok(Request {
+                id: Some(id),
+                method,
+                params,
+            }) => match respond(&bible, &method, params, translation) {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err((code, message)) => error(id, code, message),
+            },
+            Err(e) => error(Value::Null, PARSE_ERROR, e.to_string()),
+        };
+
+        writeln!(stdout, "{response}")?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+fn error(id: Value, code: i32, message: String) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn respond(
+    bible: &Bible,
+    method: &str,
+    params: Value,
+    translation: Translation,
+) -> Result<Value, (i32, String)> {
+    match method {
+        "initialize" => {
+            let requested = params["protocolVersion"].as_str().unwrap_or_default();
+            let version = PROTOCOL_VERSIONS
+                .iter()
+                .find(|&&version| version == requested)
+                .unwrap_or(&PROTOCOL_VERSIONS[0]);
+            Ok(json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "flux", "version": env!("CARGO_PKG_VERSION") },
+                "instructions": format!(
+                    "Look up and search the Bible. Quote verses from these tools rather than from \
+                     memory. Unless told otherwise, they use the {translation}."
+                ),
+            }))
+        }
+
+        "ping" => Ok(json!({})),
+
+        "tools/list" => Ok(json!({ "tools": tools() })),
+
+        "tools/call" => {
+            let call = ToolCall::deserialize(params)
+                .map_err(|e| (INVALID_PARAMS, format!("bad params: {e}")))?;
+            let arguments = call.arguments;
+            let text = match call.name.as_str() {
+                "lookup" => arguments_for(arguments)
+                    .and_then(|arguments| lookup(bible, arguments, translation)),
+                "search" => arguments_for(arguments)
+                    .and_then(|arguments| search(bible, arguments, translation)),
+                name => return Err((INVALID_PARAMS, format!("unknown tool '{name}'"))),
+            };
+
+            let is_error = text.is_err();
+            let text = text.unwrap_or_else(|message| message);
+            Ok(json!({
+                "content": [{ "type": "text", "text": text }],
+                "isError": is_error,
+            }))
+        }
+
+        method => Err((METHOD_NOT_FOUND, format!("unknown method '{method}'"))),
+    }
+}
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "lookup",
+            "description": "The text of a Bible passage: a verse, a range of verses, a chapter \
+                            or a whole book, e.g. \"John 3:16\", \"Ps 23\" or \"1 Cor 13:4-7\".",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "reference": { "type": "string", "description": "the passage" },
+                    "translation": {
+                        "type": "string",
+                        "description": "e.g. KJV or ASV; otherwise the user's usual one",
+                    },
+                },
+                "required": ["reference"],
+            },
+        },
+        {
+            "name": "search",
+            "description": "Full-text search of a translation, best matches first. Plain words \
+                            are optional but rank higher the more a verse has; +word must \
+                            appear, -word must not, and \"a phrase\" must appear word for word.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "translation": { "type": "string" },
+                    "limit": {
+                        "type": "integer",
+                        "description":
+                            format!("how many verses, at most {MAX_LIMIT}; 10 if not given"),
+                    },
+                    "in": {
+                        "type": "string",
+                        "description": "only these books (or ot, nt, gospels), comma-separated",
+                    },
+                },
+                "required": ["query"],
+            },
+        },
+    ])
+}
+
+fn arguments_for<T: for<'de> Deserialize<'de>>(arguments: Value) -> Result<T, String> {
+    T::deserialize(arguments).map_err(|e| format!("bad arguments: {e}"))
+}
+
+fn lookup(
+    bible: &Bible,
+    arguments: LookupArguments,
+    translation: Translation,
+) -> Result<String, String> {
+    let translation = pick(arguments.translation, translation)?;
+    let passage: Passage = arguments
+        .reference
+        .parse()
+        .map_err(|e: fiat_lux::ParsePassageError| e.to_string())?;
+    let texts = bible
+        .lookup(passage.book, passage.location, translation)
+        .map_err(|e| e.to_string())?;
+    if texts.is_empty() {
+        return Err(format!("{passage} isn't in the {translation}"));
+    }
+
+    let mut text = format!("{} ({translation})\n", format::group_reference(&texts));
+    for verse in &texts {
+        text += &format!("{}:{} {}\n", verse.chapter, verse.verse, verse.content);
+    }
+    Ok(text)
+}
+
+fn search(
+    bible: &Bible,
+    arguments: SearchArguments,
+    translation: Translation,
+) -> Result<String, String> {
+    let translation = pick(arguments.translation, translation)?;
+    let scopes = match &arguments.scopes {
+        Some(scopes) => scopes
+            .split(',')
+            .map(|scope| scope.trim().parse::<Scope>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?,
+        None => Vec::new(),
+    };
+    let limit = arguments.limit.unwrap_or(10).min(MAX_LIMIT);
+
+    let hits = bible
+        .search_scored(&arguments.query, translation, &scopes)
+        .map_err(|e| e.to_string())?;
+    if hits.is_empty() {
+        return Ok(format!("no verses in the {translation} match that"));
+    }
+
+    let shown = hits.len().min(limit);
+    let mut text = format!(
+        "{shown} of {} matching verses ({translation})\n",
+        hits.len()
+    );
+    for (_, verse) in &hits[..shown] {
+        text += &format!(
+            "{} {}:{} {}\n",
+            verse.book, verse.chapter, verse.verse, verse.content
+        );
+    }
+    Ok(text)
+}
+
+/// The translation a call names, or failing that the server's.
+fn pick(name: Option<String>, translation: Translation) -> Result<Translation, String> {
+    name.map_or(Ok(translation), |name| {
+        name.parse()
+            .map_err(|e: fiat_lux::ParseTranslationError| e.to_string())
+    })
+}
@@ -0,0 +1,248 @@
+use std::{fs, io, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+use crossterm::style::Stylize;
+use fiat_lux::{PartialLocation, Passage, Scope, Text, Translation};
+
+use crate::{
+    config::state_dir,
+    count::plural,
+    import::{self, Source},
+    Result,
+};
+
+#[derive(Clone, Debug, Parser)]
+pub struct NoteArgs {
+    #[clap(subcommand)]
+    command: NoteCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum NoteCommand {
+    /// attach a note to a verse or passage, e.g. "john 3:16"
+    Add { passage: Passage, text: String },
+
+    /// show the notes on any part of a passage
+    Show { passage: Passage },
+
+    /// list every note
+    List,
+
+    /// search your notes, showing each one found with the verses it's attached to
+    Search {
+        /// words to search for, in the same syntax as flux search
+        query: String,
+
+        #[clap(short, long, default_value_t = 10)]
+        limit: usize,
+    },
+
+    /// delete the notes attached to exactly this passage
+    Remove { passage: Passage },
+
+    /// bring in the highlights and notes from another app's export
+    Import {
+        #[clap(value_enum)]
+        source: Source,
+        path: PathBuf,
+    },
+}
+
+/// `translation` is the one Kindle highlights are searched for in, to find where they're from,
+/// and the one search results are shown in.
+pub fn note(args: &NoteArgs, translation: Translation) -> Result<()> {
+    let mut notes = Notes::load()?;
+    match &args.command {
+        NoteCommand::Add { passage, text } => {
+            notes.entries.push((*passage, text.clone()));
+            notes.save()?;
+        }
+
+        NoteCommand::Show { passage } => {
+            for (passage, text) in notes.overlapping(*passage) {
+                println!("{passage}  {text}");
+            }
+        }
+
+        NoteCommand::List => {
+            for (passage, text) in &notes.entries {
+                println!("{passage}  {text}");
+            }
+        }
+
+        NoteCommand::Search { query, limit } => {
+            let bible = crate::open_bible()?;
+            for (_, (passage, text)) in notes.search(query, &[], *limit)? {
+                println!("{}  {text}", passage.to_string().bold());
+                for verse in bible.lookup(passage.book, passage.location, translation)? {
+                    println!("{:>6}  {}", verse.verse, verse.content);
+                }
+                println!();
+            }
+        }
+
+        NoteCommand::Remove { passage } => {
+            let before = notes.entries.len();
+            notes.entries.retain(|(noted, _)| noted != passage);
+            let removed = before - notes.entries.len();
+            notes.save()?;
+            println!("removed {removed} {}", plural(removed, "note"));
+        }
+
+        NoteCommand::Import { source, path } => {
+            let text = fs::read_to_string(path)?;
+            let imported = match source {
+                Source::Youversion => import::youversion(&text),
+                Source::Kindle => {
+                    let bible = crate::open_bible()?;
+                    import::kindle(&text, |content| {
+                        import::locate(&bible, content, translation)
+                    })
+                }
+            };
+
+            // Importing the same file twice shouldn't double everything up.
+            let mut added = 0;
+            for note in imported.notes {
+                if !notes.entries.contains(&note) {
+                    notes.entries.push(note);
+                    added += 1;
+                }
+            }
+            notes.save()?;
+
+            println!("imported {added} {}", plural(added, "note"));
+            for unplaced in &imported.unplaced {
+                eprintln!("couldn't tell where this is from: {unplaced}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Your notes, one per line: the passage and the note, separated by a tab. Tabs, newlines and
+/// backslashes in a note are escaped with a backslash.
+#[derive(Debug, Default)]
+pub struct Notes {
+    entries: Vec<(Passage, String)>,
+}
+
+impl Notes {
+    fn path() -> Option<PathBuf> {
+        state_dir().map(|dir| dir.join("notes.dat"))
+    }
+
+    pub fn load() -> Result<Self> {
+        let text = match Self::path().map(fs::read_to_string) {
+            Some(Ok(text)) => text,
+            Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => return Ok(Self::default()),
+        };
+
+        let entries = text
+            .lines()
+            .filter_map(|line| {
+                let (passage, note) = line.split_once('\t')?;
+                Some((passage.parse().ok()?, unescape(note)))
+            })
+            .collect();
+
+        Ok(Self { entries })
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+
+        let mut text = String::new();
+        for (passage, note) in &self.entries {
+            text.push_str(&format!("{passage}\t{}\n", escape(note)));
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(fs::write(path, text)?)
+    }
+
+    fn overlapping(&self, passage: Passage) -> impl Iterator<Item = &(Passage, String)> {
+        self.entries
+            .iter()
+            .filter(move |(noted, _)| noted.overlaps(&passage))
+    }
+
+    /// The notes on the given books or testaments best matching a search query, with their
+    /// scores, best first. No scopes means notes anywhere.
+    pub fn search(
+        &self,
+        query: &str,
+        scopes: &[Scope],
+        limit: usize,
+    ) -> Result<Vec<(f32, &(Passage, String))>> {
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|(passage, _)| {
+                scopes.is_empty()
+                    || scopes
+                        .iter()
+                        .any(|scope| scope.books().any(|book| book == passage.book))
+            })
+            .collect();
+        let texts: Vec<_> = entries.iter().map(|(_, note)| note.as_str()).collect();
+        Ok(fiat_lux::search_texts(&texts, query, limit)?
+            .into_iter()
+            .map(|(score, idx)| (score, entries[idx]))
+            .collect())
+    }
+
+    /// The notes on any part of a verse.
+    pub fn on(&self, text: &Text) -> Vec<&(Passage, String)> {
+        let verse = Passage {
+            book: text.book,
+            location: Some(PartialLocation {
+                chapter: text.chapter,
+                verse: Some(text.verse),
+                end: None,
+            }),
+        };
+        self.overlapping(verse).collect()
+    }
+}
+
+fn escape(note: &str) -> String {
+    note.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+fn unescape(note: &str) -> String {
+    let mut buf = String::with_capacity(note.len());
+    let mut chars = note.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            buf.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => buf.push('\t'),
+            Some('n') => buf.push('\n'),
+            Some(c) => buf.push(c),
+            None => buf.push('\\'),
+        }
+    }
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn escaping() {
+        let note = "a tab\there,\na newline and a \\ backslash";
+        assert!(!super::escape(note).contains(['\t', '\n']));
+        assert_eq!(note, super::unescape(&super::escape(note)));
+    }
+}
@@ -0,0 +1,50 @@
+//! Which pager long output goes through: the one asked for, then `$PAGER`, bat and `less -R`,
+//! whichever is installed first, or none at all.
+
+use std::path::{Path, MAIN_SEPARATOR};
+
+/// Tried in order when nothing is asked for, or what's asked for isn't installed.
+const FALLBACKS: &[&str] = &["bat", "less -R"];
+
+/// The pager command to use, given the one asked for on the command line or in the config, if
+/// any. `none` turns paging off; so does having none of them installed.
+pub fn choose(asked: Option<&str>) -> Option<String> {
+    if asked.is_some_and(|command| command.trim() == "none") {
+        return None;
+    }
+
+    let env = std::env::var("PAGER").ok();
+    asked
+        .into_iter()
+        .chain(env.as_deref())
+        .chain(FALLBACKS.iter().copied())
+        .filter(|command| !command.trim().is_empty())
+        .find(|command| installed(command))
+        .map(String::from)
+}
+
+/// Whether a command's program can be run, by name or by the path given.
+pub fn installed(command: &str) -> bool {
+    let Some(program) = command.split_whitespace().next() else {
+        return false;
+    };
+    if program.contains(MAIN_SEPARATOR) {
+        return Path::new(program).is_file();
+    }
+
+    // The name is taken as given, dots and all (python3.11, view.sh); Windows also finds `bat`
+    // as bat.exe.
+    let extensions: &[&str] = if cfg!(windows) {
+        &["exe", "cmd", "bat"]
+    } else {
+        &[]
+    };
+    std::env::var_os("PATH").is_some_and(|path| {
+        std::env::split_paths(&path).any(|dir| {
+            dir.join(program).is_file()
+                || extensions
+                    .iter()
+                    .any(|ext| dir.join(format!("{program}.{ext}")).is_file())
+        })
+    })
+}
@@ -0,0 +1,258 @@
+//! A pandoc filter, which turns the scripture references in a document into footnotes giving the
+//! text, or swaps them for the text itself.
+//!
+//! Pandoc hands a filter the document as json on stdin and reads it back from stdout, passing the
+//! output format as the only argument. Pandoc can't pass anything else, so to change how it
+//! works, point `--filter` at a script such as `exec flux --asv pandoc-filter --replace "$@"`.
+
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+};
+
+use clap::Parser;
+use fiat_lux::{Passage, Translation};
+use serde_json::{json, Value};
+
+use crate::Result;
+
+#[derive(Clone, Debug, Parser)]
+pub struct PandocFilterArgs {
+    /// put the text in place of each reference, quoted, rather than in a footnote
+    #[clap(long)]
+    replace: bool,
+
+    /// the output format, which pandoc passes along; it makes no difference here
+    #[clap(hide = true, value_name = "FORMAT")]
+    output_format: Option<String>,
+}
+
+pub fn pandoc_filter(args: &PandocFilterArgs, translation: Translation) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    let mut document: Value = serde_json::from_str(&input).map_err(io::Error::other)?;
+
+    // The same passage tends to come up more than once in a sermon.
+    let mut seen: HashMap<String, Option<String>> = HashMap::new();
+    let mut lookup = |passage: Passage| {
+        seen.entry(passage.to_string())
+            .or_insert_with(|| {
+                // A reference to a chapter that doesn't exist is left as it was; it's worth a
+                // warning, but not worth failing the whole build.
+                match bible.lookup(passage.book, passage.location, translation) {
+                    Ok(texts) if !texts.is_empty() => {
+                        let content: Vec<_> =
+                            texts.iter().map(|text| text.content.as_str()).collect();
+                        Some(content.join(" "))
+                    }
+                    Ok(_) => {
+                        eprintln!("{passage} isn't in the {translation}; leaving it be");
+                        None
+                    }
+                    Err(e) => {
+                        eprintln!("{passage}: {e}; leaving it be");
+                        None
+                    }
+                }
+            })
+            .clone()
+    };
+
+    let mode = if args.replace {
+        Mode::Replace
+    } else {
+        Mode::Footnote
+    };
+    // Only the body; a reference in the title shouldn't sprout a footnote.
+    if let Some(blocks) = document.get_mut("blocks") {
+        walk(blocks, mode, translation, &mut lookup);
+    }
+
+    serde_json::to_writer(io::stdout().lock(), &document).map_err(io::Error::other)?;
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    Footnote,
+    Replace,
+}
+
+/// Element types whose words aren't to be touched: footnotes (including the ones we add), links,
+/// citations and code.
+const SKIP: &[&str] = &[
+    "Note",
+    "Link",
+    "Cite",
+    "Code",
+    "CodeBlock",
+    "RawInline",
+    "RawBlock",
+];
+
+fn walk(
+    value: &mut Value,
+    mode: Mode,
+    translation: Translation,
+    lookup: &mut impl FnMut(Passage) -> Option<String>,
+) {
+    match value {
+        Value::Object(element) => {
+            let skip = element
+                .get("t")
+                .and_then(Value::as_str)
+                .is_some_and(|t| SKIP.contains(&t));
+            if !skip {
+                for child in element.values_mut() {
+                    walk(child, mode, translation, lookup);
+                }
+            }
+        }
+
+        Value::Array(items) => {
+            // Children first, so whatever gets put in here isn't gone over again.
+            for item in items.iter_mut() {
+                walk(item, mode, translation, lookup);
+            }
+            if items.iter().any(|item| item["t"] == "Str") {
+                rewrite(items, mode, translation, lookup);
+            }
+        }
+
+        _ => (),
+    }
+}
+
+/// Rewrite a list of inline elements. A reference is spread over several of them ("John", a
+/// space, "3:16"), so each run of words and spaces is put back together as text to look for
+/// references in.
+fn rewrite(
+    items: &mut Vec<Value>,
+    mode: Mode,
+    translation: Translation,
+    lookup: &mut impl FnMut(Passage) -> Option<String>,
+) {
+    let mut rewritten = Vec::with_capacity(items.len());
+    let mut run = Vec::new();
+    for item in items.drain(..) {
+        if matches!(item["t"].as_str(), Some("Str" | "Space" | "SoftBreak")) {
+            run.push(item);
+        } else {
+            rewrite_run(&mut run, &mut rewritten, mode, translation, lookup);
+            rewritten.push(item);
+        }
+    }
+    rewrite_run(&mut run, &mut rewritten, mode, translation, lookup);
+    *items = rewritten;
+}
+
+fn rewrite_run(
+    run: &mut Vec<Value>,
+    rewritten: &mut Vec<Value>,
+    mode: Mode,
+    translation: Translation,
+    lookup: &mut impl FnMut(Passage) -> Option<String>,
+) {
+    let text: String = run
+        .iter()
+        .map(|item| item["c"].as_str().unwrap_or(" "))
+        .collect();
+    let found: Vec<_> = Passage::find_all(&text)
+        .into_iter()
+        .filter_map(|(range, passage)| Some((range, passage, lookup(passage)?)))
+        .collect();
+
+    // Runs without a reference are passed through as they were, soft line breaks and all.
+    if found.is_empty() {
+        rewritten.append(run);
+        return;
+    }
+    run.clear();
+
+    let mut last = 0;
+    for (range, passage, content) in found {
+        match mode {
+            Mode::Footnote => {
+                rewritten.extend(inlines(&text[last..range.end]));
+                let note = inlines(&format!("{content} ({translation})"));
+                rewritten.push(json!({ "t": "Note", "c": [{ "t": "Para", "c": note }] }));
+            }
+            Mode::Replace => {
+                rewritten.extend(inlines(&text[last..range.start]));
+                rewritten.push(json!({
+                    "t": "Quoted",
+                    "c": [{ "t": "DoubleQuote" }, inlines(&content)],
+                }));
+                rewritten.extend(inlines(&format!(" ({passage}, {translation})")));
+            }
+        }
+        last = range.end;
+    }
+    rewritten.extend(inlines(&text[last..]));
+}
+
+/// Text as pandoc inlines: a Str for each word and a Space for each gap.
+fn inlines(text: &str) -> Vec<Value> {
+    let mut inlines = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let gap = rest.len() - rest.trim_start().len();
+        if gap > 0 {
+            inlines.push(json!({ "t": "Space" }));
+            rest = &rest[gap..];
+            continue;
+        }
+
+        let word = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        inlines.push(json!({ "t": "Str", "c": &rest[..word] }));
+        rest = &rest[word..];
+    }
+    inlines
+}
+
+#[cfg(test)]
+mod tests {
+    use fiat_lux::Translation;
+    use serde_json::{json, Value};
+
+    use super::{inlines, walk, Mode};
+
+    #[test]
+    fn footnotes() {
+        let para = |text: &str| json!([{ "t": "Para", "c": inlines(text) }]);
+        let mut blocks = para("As in John 11:35, he wept.");
+        walk(&mut blocks, Mode::Footnote, Translation::Kjv, &mut |_| {
+            Some(String::from("Jesus wept."))
+        });
+
+        let words: Vec<Value> = blocks[0]["c"].as_array().unwrap().clone();
+        let note = words.iter().position(|item| item["t"] == "Note").unwrap();
+        assert_eq!("11:35", words[note - 1]["c"]);
+        assert_eq!(",", words[note + 1]["c"]);
+        assert_eq!(
+            inlines("Jesus wept. (KJV)"),
+            words[note]["c"][0]["c"].as_array().unwrap().clone()
+        );
+
+        // Nothing to find, nothing changed.
+        let mut blocks = para("Page 12 of the notes.");
+        let before = blocks.clone();
+        walk(&mut blocks, Mode::Footnote, Translation::Kjv, &mut |_| None);
+        assert_eq!(before, blocks);
+    }
+
+    #[test]
+    fn prose_left_alone() {
+        let para = |text: &str| json!([{ "t": "Para", "c": inlines(text) }]);
+        let mut blocks = para("The temperature is 3 degrees, and I am 40 years old.");
+        let before = blocks.clone();
+        walk(
+            &mut blocks,
+            Mode::Replace,
+            Translation::Kjv,
+            &mut |passage| Some(format!("the whole of {passage}")),
+        );
+        assert_eq!(before, blocks);
+    }
+}
@@ -0,0 +1,82 @@
+use clap::Parser;
+use crossterm::style::Stylize;
+use fiat_lux::{Text, Translation};
+
+use crate::Result;
+
+/// Verses to print from a group whose verses differ, before summing up the rest.
+const SHOWN: usize = 5;
+
+#[derive(Clone, Debug, Parser)]
+pub struct ParallelsArgs {
+    /// how alike two verses must be to count, from 0 to 1
+    #[clap(short, long, default_value_t = 0.8)]
+    threshold: f64,
+
+    /// skip verses with fewer words than this
+    #[clap(long, default_value_t = 4)]
+    min_words: usize,
+}
+
+pub fn parallels(args: &ParallelsArgs, translation: Translation) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let groups = bible.parallels(translation, args.threshold, args.min_words)?;
+
+    for (idx, (verses, similarity)) in groups.iter().enumerate() {
+        if idx > 0 {
+            println!();
+        }
+
+        // The formulas of the law run to dozens of verses.
+        let references: Vec<_> = verses.iter().map(reference).collect();
+        println!(
+            "{} {}",
+            wrap(&references).bold(),
+            format!("({similarity:.2})").dark_grey()
+        );
+
+        // A refrain repeated word for word only needs printing once.
+        if verses.iter().all(|text| text.content == verses[0].content) {
+            println!("  {}", verses[0].content);
+            continue;
+        }
+
+        let width = references.iter().map(String::len).max().unwrap_or_default();
+        for (reference, text) in references.iter().zip(verses).take(SHOWN) {
+            println!("  {reference:width$}  {}", text.content);
+        }
+        if verses.len() > SHOWN {
+            println!("  ... and {} more", verses.len() - SHOWN);
+        }
+    }
+
+    let verses: usize = groups.iter().map(|(verses, _)| verses.len()).sum();
+    println!(
+        "\n{} groups of near-identical verses, {verses} verses in all ({translation})",
+        groups.len()
+    );
+    Ok(())
+}
+
+/// Join references with semicolons, breaking lines between references rather than inside them.
+fn wrap(references: &[String]) -> String {
+    const WIDTH: usize = 100;
+    let mut lines = vec![String::new()];
+    for reference in references {
+        let line = lines.last_mut().unwrap();
+        if line.is_empty() {
+            line.push_str(reference);
+        } else if line.len() + reference.len() + 2 <= WIDTH {
+            line.push_str("; ");
+            line.push_str(reference);
+        } else {
+            line.push(';');
+            lines.push(reference.clone());
+        }
+    }
+    lines.join("\n")
+}
+
+fn reference(text: &Text) -> String {
+    format!("{} {}:{}", text.book, text.chapter, text.verse)
+}
@@ -0,0 +1,57 @@
+use clap::Parser;
+use fiat_lux::{Place, Text, Translation};
+
+use crate::{Args, Error, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct PlaceArgs {
+    name: String,
+
+    /// print a geo: uri for the place
+    #[clap(long)]
+    geo: bool,
+
+    /// print an openstreetmap link for the place
+    #[clap(long)]
+    osm: bool,
+
+    /// maximum number of verses to list
+    #[clap(short, long, default_value_t = 10)]
+    limit: usize,
+}
+
+pub fn place(place_args: &PlaceArgs, translation: Translation, args: &Args) -> Result<()> {
+    let place = Place::find(&place_args.name)
+        .ok_or_else(|| Error::UnknownPlace(place_args.name.clone()))?;
+
+    println!("{place}");
+    println!(
+        "{} ({:.4}, {:.4})",
+        place.modern, place.latitude, place.longitude
+    );
+    println!("{}", textwrap::fill(place.description, 100));
+
+    if place_args.geo {
+        println!("{}", place.geo_uri());
+    }
+
+    if place_args.osm {
+        println!("{}", place.osm_url());
+    }
+
+    // Older translations use older names, e.g. Melita for Malta, so we look for all of them.
+    let bible = crate::open_bible()?;
+    let mut texts: Vec<Text> = Vec::new();
+    for name in place.names() {
+        texts.extend(bible.search(&format!("\"{name}\""), translation, place_args.limit)?);
+    }
+    texts.sort();
+    texts.dedup();
+    texts.truncate(place_args.limit);
+
+    if !texts.is_empty() {
+        crate::format_texts(&texts, args);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use std::{fs, io, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+use crossterm::style::Stylize;
+use fiat_lux::{Date, PartialLocation, Plan, Translation};
+
+use crate::{config::state_dir, Args, Error, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct PlanArgs {
+    #[clap(subcommand)]
+    command: PlanCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum PlanCommand {
+    /// list the reading plans
+    List,
+
+    /// start a plan from day one, replacing any plan already underway
+    Start { name: String },
+
+    /// show the next day's reading
+    Today {
+        /// print the chapters in full rather than just which they are
+        #[clap(long)]
+        text: bool,
+    },
+
+    /// mark the next day's reading done
+    Done,
+}
+
+pub fn plan(plan_args: &PlanArgs, translation: Translation, args: &Args) -> Result<()> {
+    match &plan_args.command {
+        PlanCommand::List => {
+            let width = Plan::all()
+                .iter()
+                .map(|plan| plan.name.len())
+                .max()
+                .unwrap_or_default();
+            for plan in Plan::all() {
+                println!(
+                    "{:width$}  {} days, {}",
+                    plan.name, plan.days, plan.description
+                );
+            }
+        }
+
+        PlanCommand::Start { name } => {
+            let plan = Plan::find(name).ok_or_else(|| Error::UnknownPlan(name.clone()))?;
+            let progress = Progress {
+                plan,
+                start: Date::today().days(),
+                done: 0,
+            };
+            progress.save()?;
+            println!("started {}: {}", plan.name, plan.description);
+            print_day(&progress);
+        }
+
+        PlanCommand::Today { text } => {
+            let progress = Progress::load()?.ok_or(Error::NoPlan)?;
+            print_day(&progress);
+            if *text {
+                read(&progress, translation, args)?;
+            }
+        }
+
+        PlanCommand::Done => {
+            let mut progress = Progress::load()?.ok_or(Error::NoPlan)?;
+            if progress.done < progress.plan.days {
+                progress.done += 1;
+                progress.save()?;
+                println!("day {} done", progress.done);
+            }
+            print_day(&progress);
+        }
+    }
+
+    Ok(())
+}
+
+fn print_day(progress: &Progress) {
+    let plan = progress.plan;
+    if progress.done >= plan.days {
+        println!("{} finished, all {} days", plan.name, plan.days);
+        return;
+    }
+
+    let day = progress.done + 1;
+    println!(
+        "{}",
+        format!("day {day} of {} ({})", plan.days, plan.name).bold()
+    );
+    for reading in plan.day(day) {
+        println!("  {reading}");
+    }
+
+    // Going by the calendar, today would be this day of the plan.
+    let due = (Date::today().days() - progress.start + 1).clamp(1, i64::from(plan.days));
+    let behind = due - i64::from(day);
+    if behind > 0 {
+        let days = if behind == 1 { "day" } else { "days" };
+        println!("{}", format!("{behind} {days} behind").dark_grey());
+    }
+}
+
+fn read(progress: &Progress, translation: Translation, args: &Args) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let mut texts = Vec::new();
+    for reading in progress.plan.day(progress.done + 1) {
+        for chapter in reading.chapters {
+            let location = PartialLocation {
+                chapter,
+                verse: None,
+                end: None,
+            };
+            texts.extend(bible.lookup(reading.book, Some(location), translation)?);
+        }
+    }
+
+    println!();
+    crate::print_texts(&texts, translation, args);
+    Ok(())
+}
+
+/// The plan underway, stored as one line of tab-separated fields: the plan's name, the day it
+/// was started (in days since 1970-01-01) and how many of its days are done.
+struct Progress {
+    plan: &'static Plan,
+    start: i64,
+    done: u16,
+}
+
+impl Progress {
+    fn path() -> Option<PathBuf> {
+        state_dir().map(|dir| dir.join("plan.dat"))
+    }
+
+    fn load() -> Result<Option<Self>> {
+        let text = match Self::path().map(fs::read_to_string) {
+            Some(Ok(text)) => text,
+            Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => return Ok(None),
+        };
+
+        let mut fields = text.trim_end().split('\t');
+        let progress = (|| {
+            let plan = Plan::find(fields.next()?)?;
+            let start = fields.next()?.parse().ok()?;
+            let done = fields.next()?.parse().ok()?;
+            Some(Self { plan, start, done })
+        })();
+        Ok(progress)
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let text = format!("{}\t{}\t{}\n", self.plan.name, self.start, self.done);
+        Ok(fs::write(path, text)?)
+    }
+}
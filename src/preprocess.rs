@@ -0,0 +1,98 @@
+//! `flux preprocess`: fill in `{{verse: Romans 8:28 KJV}}` directives in a markdown file with the
+//! passage, quoted, for a static site or notes pipeline to run at build time. Everything else is
+//! copied exactly as it was.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Read, Write},
+    path::PathBuf,
+};
+
+use clap::Parser;
+use fiat_lux::{Passage, Translation};
+
+use crate::{expand::expand_text, format::quote_passage, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct PreprocessArgs {
+    /// the file to read, or - for stdin
+    path: PathBuf,
+
+    /// write the result here instead of to stdout
+    #[clap(short, long, value_name = "PATH")]
+    output: Option<PathBuf>,
+}
+
+pub fn preprocess(preprocess_args: &PreprocessArgs, translation: Translation) -> Result<()> {
+    let text = if preprocess_args.path.as_os_str() == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        fs::read_to_string(&preprocess_args.path)?
+    };
+
+    let bible = crate::open_bible()?;
+    let mut seen: HashMap<String, Option<String>> = HashMap::new();
+    let output = expand_text(&text, |token| {
+        let (passage, translation) = directive(token, translation)?;
+        seen.entry(format!("{passage} {translation}"))
+            .or_insert_with(|| {
+                // A passage that can't be had is left as it was, so it stands out in the built
+                // page.
+                let (text, reference) = quote_passage(&bible, passage, translation)?;
+                Some(format!("“{text}” ({reference}, {translation})"))
+            })
+            .clone()
+    });
+
+    match &preprocess_args.output {
+        Some(path) => fs::write(path, output)?,
+        None => io::stdout().lock().write_all(output.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// The passage and translation a `verse:` directive asks for, the translation being an optional
+/// last word. Anything else between braces isn't ours, and is left alone.
+fn directive(token: &str, default: Translation) -> Option<(Passage, Translation)> {
+    let reference = token.strip_prefix("verse:")?.trim();
+    if let Ok(passage) = reference.parse() {
+        return Some((passage, default));
+    }
+
+    let parsed = reference
+        .rsplit_once(char::is_whitespace)
+        .and_then(|(passage, name)| Some((passage.parse().ok()?, name.parse().ok()?)));
+    if parsed.is_none() {
+        eprintln!("{{{{{token}}}}}: can't make out the passage; leaving it be");
+    }
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use fiat_lux::Translation;
+
+    use super::directive;
+
+    #[test]
+    fn directives() {
+        let parsed = |token| {
+            directive(token, Translation::Kjv)
+                .map(|(passage, translation)| format!("{passage} {translation}"))
+        };
+        assert_eq!(
+            Some("Romans 8:28 ASV"),
+            parsed("verse: Romans 8:28 ASV").as_deref()
+        );
+        assert_eq!(
+            Some("Romans 8:28 KJV"),
+            parsed("verse:Romans 8:28").as_deref()
+        );
+        assert_eq!(Some("3 John 4 KJV"), parsed("verse: 3 John 4").as_deref());
+        assert_eq!(None, parsed("John 3:16"));
+        assert_eq!(None, parsed("title"));
+    }
+}
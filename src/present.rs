@@ -0,0 +1,275 @@
+use std::io::{self, Write};
+
+use clap::Parser;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use fiat_lux::{Book, PartialLocation, Text, Translation};
+
+use crate::Result;
+
+#[derive(Clone, Debug, Parser)]
+pub struct PresentArgs {
+    book: Book,
+    location: Option<PartialLocation>,
+}
+
+pub fn present(args: &PresentArgs, translation: Translation) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let texts = bible.lookup(args.book, args.location, translation)?;
+    if texts.is_empty() {
+        return Ok(());
+    }
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen, cursor::Hide)?;
+
+    // Whatever happens in there, we need to put the terminal back the way we found it before
+    // reporting the error.
+    let result = show(&mut stdout, &texts, translation);
+
+    execute!(stdout, cursor::Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    Ok(result?)
+}
+
+fn show(out: &mut impl Write, texts: &[Text], translation: Translation) -> io::Result<()> {
+    let last = texts.len() - 1;
+    let mut idx = 0;
+
+    loop {
+        draw(out, &texts[idx], translation)?;
+
+        // Resize events (and everything else we don't care about) simply fall through to a redraw.
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        else {
+            continue;
+        };
+
+        match code {
+            KeyCode::Right
+            | KeyCode::Down
+            | KeyCode::PageDown
+            | KeyCode::Enter
+            | KeyCode::Char(' ' | 'n' | 'j' | 'l') => idx = (idx + 1).min(last),
+            KeyCode::Left
+            | KeyCode::Up
+            | KeyCode::PageUp
+            | KeyCode::Backspace
+            | KeyCode::Char('p' | 'k' | 'h') => idx = idx.saturating_sub(1),
+            KeyCode::Home => idx = 0,
+            KeyCode::End => idx = last,
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            _ => (),
+        }
+    }
+}
+
+/// Draw a single verse, centered both ways, with its reference beneath it. The verse is set in
+/// large block letters, as large as will fit, or in ordinary text if even the smallest won't.
+fn draw(out: &mut impl Write, text: &Text, translation: Translation) -> io::Result<()> {
+    let (w, h) = terminal::size()?;
+    let (w, h) = (usize::from(w), usize::from(h));
+
+    // The reference and the blank line above it need room too.
+    let room = h.saturating_sub(2);
+    let large = (1..=3)
+        .rev()
+        .filter_map(|scale| block_letters(&text.content, w * 9 / 10, scale))
+        .find(|lines| lines.len() <= room);
+    let lines = large.unwrap_or_else(|| {
+        // Two thirds of the screen reads well from the back of a room; much wider and people
+        // lose their place moving from one line to the next.
+        let width = (w * 2 / 3).clamp(w.min(20), w);
+        textwrap::wrap(&text.content, width)
+            .into_iter()
+            .map(String::from)
+            .collect()
+    });
+    let reference = format!(
+        "{} {}:{} ({translation})",
+        text.book, text.chapter, text.verse
+    );
+
+    // A blank line separates the text from the reference.
+    let height = lines.len() + 2;
+    let top = h.saturating_sub(height) / 2;
+
+    queue!(out, Clear(ClearType::All))?;
+    for (i, line) in lines.iter().enumerate() {
+        queue!(
+            out,
+            cursor::MoveTo(centered(w, line.chars().count()), to_u16(top + i)),
+            Print(line)
+        )?;
+    }
+
+    queue!(
+        out,
+        cursor::MoveTo(
+            centered(w, reference.chars().count()),
+            to_u16(top + lines.len() + 1)
+        ),
+        SetAttribute(Attribute::Bold),
+        Print(&reference),
+        SetAttribute(Attribute::Reset),
+    )?;
+
+    out.flush()
+}
+
+/// `text` in capitals drawn with block characters, each letter three pixels wide and five high
+/// and each pixel `scale` characters wide and half of `scale` high, wrapped to `width`. `None` if
+/// there's a character the font doesn't have, or a line can't fit a single letter.
+fn block_letters(text: &str, width: usize, scale: usize) -> Option<Vec<String>> {
+    // A pixel's gap after each letter, and a blank row between lines.
+    let letters = width / (4 * scale);
+    if letters == 0 {
+        return None;
+    }
+    let text: String = text
+        .chars()
+        .map(|c| match c {
+            '‘' | '’' => '\'',
+            '“' | '”' => '"',
+            '—' | '–' => '-',
+            c => c.to_ascii_uppercase(),
+        })
+        .collect();
+
+    let mut rows = Vec::new();
+    for line in textwrap::wrap(&text, letters) {
+        if !rows.is_empty() {
+            rows.push(String::new());
+        }
+        let glyphs = line.chars().map(glyph).collect::<Option<Vec<_>>>()?;
+        let pixel = |y: usize, x: usize| {
+            let glyph = glyphs[x / (4 * scale)];
+            let column = x % (4 * scale) / scale;
+            y < 5 * scale && column < 3 && glyph[y / scale] & (0b100 >> column) != 0
+        };
+
+        // Two rows of pixels go in each row of characters, top and bottom halves.
+        for y in (0..5 * scale).step_by(2) {
+            let row: String = (0..glyphs.len() * 4 * scale)
+                .map(|x| match (pixel(y, x), pixel(y + 1, x)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                })
+                .collect();
+            rows.push(row.trim_end().to_string());
+        }
+    }
+    Some(rows)
+}
+
+/// The rows of a letter, top to bottom, its three pixels in the low three bits of each.
+fn glyph(c: char) -> Option<[u8; 5]> {
+    let rows = match c {
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b101, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b101, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b110, 0b001, 0b010, 0b100, 0b111],
+        '3' => [0b110, 0b001, 0b010, 0b001, 0b110],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b110, 0b001, 0b110],
+        '6' => [0b011, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b110],
+        ' ' => [0b000, 0b000, 0b000, 0b000, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        ';' => [0b000, 0b010, 0b000, 0b010, 0b100],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '\'' => [0b010, 0b010, 0b000, 0b000, 0b000],
+        '"' => [0b101, 0b101, 0b000, 0b000, 0b000],
+        '!' => [0b010, 0b010, 0b010, 0b000, 0b010],
+        '?' => [0b110, 0b001, 0b010, 0b000, 0b010],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '(' => [0b001, 0b010, 0b010, 0b010, 0b001],
+        ')' => [0b100, 0b010, 0b010, 0b010, 0b100],
+        _ => return None,
+    };
+    Some(rows)
+}
+
+pub fn centered(width: usize, len: usize) -> u16 {
+    to_u16(width.saturating_sub(len) / 2)
+}
+
+pub fn to_u16(n: usize) -> u16 {
+    u16::try_from(n).unwrap_or(u16::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::block_letters;
+
+    #[test]
+    fn block_letters_fit() {
+        let lines = block_letters("Hi, all", 40, 1).unwrap();
+        assert_eq!(
+            vec![
+                "█ █ ▀█▀         ▄▀▄ █   █",
+                "█▀█  █   ▄      █▀█ █   █",
+                "▀ ▀ ▀▀▀ ▀       ▀ ▀ ▀▀▀ ▀▀▀",
+            ],
+            lines
+        );
+
+        // Ten letters to a line at this width, so the words wrap onto a second line after a gap.
+        let lines = block_letters("Jesus wept.", 40, 1).unwrap();
+        assert_eq!(7, lines.len());
+        assert!(lines[3].is_empty());
+
+        // Twice the size takes twice the room each way.
+        let lines = block_letters("Jesus", 40, 2).unwrap();
+        assert_eq!(5, lines.len());
+        assert_eq!(
+            38,
+            lines.iter().map(|line| line.chars().count()).max().unwrap()
+        );
+
+        assert_eq!(None, block_letters("ἐν ἀρχῇ", 40, 1));
+        assert_eq!(None, block_letters("Jesus", 3, 1));
+    }
+}
@@ -0,0 +1,200 @@
+//! `flux proof`: every quotation in a document with a reference beside it, checked against the
+//! translation, like a spell-checker for scripture.
+
+use std::{
+    fs,
+    io::{self, Read},
+    ops::Range,
+    path::PathBuf,
+};
+
+use clap::Parser;
+use crossterm::style::Stylize;
+use fiat_lux::{Passage, Translation};
+
+use crate::{count::plural, quote, Error, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct ProofArgs {
+    /// the document to check, or - for stdin
+    path: PathBuf,
+
+    /// count words as the same whatever punctuation they have
+    #[clap(long)]
+    ignore_punctuation: bool,
+}
+
+pub fn proof(args: &ProofArgs, translation: Translation) -> Result<()> {
+    let text = if args.path.as_os_str() == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        fs::read_to_string(&args.path)?
+    };
+
+    let bible = crate::open_bible()?;
+    let quotations = quotations(&text);
+    let mut wrong = 0;
+    for quotation in &quotations {
+        let line = text[..quotation.range.start].matches('\n').count() + 1;
+        let translation = quotation.translation.unwrap_or(translation);
+        let passage = quotation.passage;
+        let heading = format!("{line}: {passage} ({translation})");
+
+        let content = match bible.lookup(passage.book, passage.location, translation) {
+            Ok(texts) if !texts.is_empty() => {
+                let content: Vec<_> = texts.iter().map(|text| text.content.as_str()).collect();
+                content.join(" ")
+            }
+            Ok(_) => {
+                println!("{}  not in the {translation}\n", heading.bold());
+                wrong += 1;
+                continue;
+            }
+            Err(e) => {
+                println!("{}  {e}\n", heading.bold());
+                wrong += 1;
+                continue;
+            }
+        };
+
+        let check = quote::check(&content, quotation.quoted, args.ignore_punctuation);
+        if check.discrepancies.is_empty() {
+            println!("{}  {}", heading.bold(), "ok".green());
+            continue;
+        }
+
+        wrong += 1;
+        println!("{}\n  {}", heading.bold(), check.marked());
+        for discrepancy in &check.discrepancies {
+            println!("  - {discrepancy}");
+        }
+        println!();
+    }
+
+    let checked = quotations.len();
+    println!("\nchecked {checked} {}", plural(checked, "quotation"));
+    match wrong {
+        0 => Ok(()),
+        wrong => Err(Error::Misquotations(wrong)),
+    }
+}
+
+/// A quotation with a reference beside it.
+#[derive(Debug)]
+struct Quotation<'a> {
+    /// Where the quotation is, quotation marks and all.
+    range: Range<usize>,
+    /// What's between the quotation marks.
+    quoted: &'a str,
+    passage: Passage,
+    /// The translation named after the reference, as in "(John 3:16 ESV)", if one is.
+    translation: Option<Translation>,
+}
+
+/// Every quotation in `text` with a reference just after it, as in “Jesus wept” (John 11:35), or
+/// just before, as in John 11:35: “Jesus wept.” Quotations with no reference nearby are left out;
+/// there's no telling what they quote.
+fn quotations(text: &str) -> Vec<Quotation<'_>> {
+    let references = Passage::find_all(text);
+
+    // Only punctuation and a little space may come between a quotation and its reference.
+    let adjacent = |between: &str| {
+        between.len() <= 4
+            && between
+                .chars()
+                .all(|c| c.is_whitespace() || "(,:;—–-".contains(c))
+    };
+
+    let mut quotations = Vec::new();
+    for range in quoted_ranges(text) {
+        let after = references.iter().find(|(reference, _)| {
+            reference.start >= range.end && adjacent(&text[range.end..reference.start])
+        });
+        let before = || {
+            references.iter().rev().find(|(reference, _)| {
+                reference.end <= range.start && adjacent(&text[reference.end..range.start])
+            })
+        };
+        let Some((reference, passage)) = after.or_else(before) else {
+            continue;
+        };
+
+        // "(John 3:16 ESV)" names the translation; a user translation's name could be any word,
+        // so only the ones flux knows by name count.
+        let translation = text[reference.end..]
+            .split(|c: char| c.is_whitespace() || c == ')' || c == ',')
+            .find(|word| !word.is_empty())
+            .filter(|_| text[reference.end..].starts_with(' '))
+            .and_then(|word| word.parse().ok())
+            .filter(|translation| !matches!(translation, Translation::User(_)));
+
+        // What's quoted starts and ends inside the marks, which are a byte each for " and three
+        // for “ and ”.
+        let open = text[range.start..].chars().next().map_or(1, char::len_utf8);
+        let close = text[..range.end]
+            .chars()
+            .next_back()
+            .map_or(1, char::len_utf8);
+        quotations.push(Quotation {
+            quoted: &text[range.start + open..range.end - close],
+            range,
+            passage: *passage,
+            translation,
+        });
+    }
+    quotations
+}
+
+/// The byte range of each quotation, marks included: between curly quotes, or between straight
+/// ones, which open and close in turn.
+fn quoted_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut open: Option<(usize, char)> = None;
+    for (idx, c) in text.char_indices() {
+        match (open, c) {
+            (None, '“' | '"') => open = Some((idx, c)),
+            (Some((start, '“')), '”') | (Some((start, '"')), '"') => {
+                ranges.push(start..idx + c.len_utf8());
+                open = None;
+            }
+            _ => {}
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use fiat_lux::Translation;
+
+    use super::quotations;
+
+    #[test]
+    fn finding_quotations() {
+        let text = "He said “Jesus wept” (John 11:35 ESV). Then Genesis 1:1: \"In the \
+            beginning\".\nAnd “a quotation with no reference,” said nobody.";
+        let found: Vec<_> = quotations(text)
+            .into_iter()
+            .map(|quotation| {
+                (
+                    quotation.quoted,
+                    quotation.passage.to_string(),
+                    quotation.translation,
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    "Jesus wept",
+                    String::from("John 11:35"),
+                    Some(Translation::Esv)
+                ),
+                ("In the beginning", String::from("Genesis 1:1"), None),
+            ],
+            found
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use fiat_lux::{
+    Book, PartialLocation, Reference, ReferenceLocator, ReferenceProvider, Translation,
+};
+use qrcode::{render::unicode, QrCode};
+
+use crate::Result;
+
+#[derive(Clone, Debug, Parser)]
+pub struct QrArgs {
+    book: Book,
+    location: Option<PartialLocation>,
+
+    /// also write the code to a png file
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+}
+
+pub fn qr(args: &QrArgs, translation: Translation, provider: ReferenceProvider) -> Result<()> {
+    let locator = ReferenceLocator {
+        book: args.book,
+        location: args.location,
+        translation,
+    };
+    let url = provider.url(&locator);
+    let code = QrCode::new(url.as_bytes())?;
+
+    // Dense1x2 packs two rows of modules into each line of text, which keeps the code roughly
+    // square in a terminal. Light on dark is what most terminals want; phones don't seem to care.
+    let image = code
+        .render::<unicode::Dense1x2>()
+        .dark_color(unicode::Dense1x2::Light)
+        .light_color(unicode::Dense1x2::Dark)
+        .build();
+    println!("{image}\n{url}");
+
+    if let Some(path) = &args.output {
+        let image = code.render::<image::Luma<u8>>().build();
+        image.save(path)?;
+    }
+
+    Ok(())
+}
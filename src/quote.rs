@@ -0,0 +1,211 @@
+//! `flux check-quote`: whether a quotation matches the translation it's said to be from, word for
+//! word, for proofreading a manuscript.
+
+use std::fmt;
+
+use clap::Parser;
+use crossterm::style::Stylize;
+use fiat_lux::{Change, Passage, Translation};
+
+use crate::{Error, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct CheckQuoteArgs {
+    /// the passage quoted, e.g. john 3:16
+    #[clap(long = "ref", required = true, num_args = 1..)]
+    reference: Vec<String>,
+
+    /// the quotation as it's written; an ellipsis at either end means it starts or stops short
+    #[clap(long)]
+    text: String,
+
+    /// count words as the same whatever punctuation they have
+    #[clap(long)]
+    ignore_punctuation: bool,
+}
+
+pub fn check_quote(args: &CheckQuoteArgs, translation: Translation) -> Result<()> {
+    let passage: Passage = args.reference.join(" ").parse()?;
+    let bible = crate::open_bible()?;
+    let texts = bible.lookup(passage.book, passage.location, translation)?;
+    let content: Vec<_> = texts.iter().map(|text| text.content.as_str()).collect();
+    let content = content.join(" ");
+
+    let check = check(&content, &args.text, args.ignore_punctuation);
+    println!("{}", check.marked());
+    if check.discrepancies.is_empty() {
+        println!("matches {passage} ({translation})");
+        return Ok(());
+    }
+
+    println!();
+    for discrepancy in &check.discrepancies {
+        println!("  {discrepancy}");
+    }
+    Err(Error::Misquoted(check.discrepancies.len()))
+}
+
+/// A quotation compared with the text it's from.
+pub(crate) struct Check<'a> {
+    changes: Vec<Change<'a>>,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+/// Words where a quotation and its source part ways.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Discrepancy {
+    /// The source's words, or nothing for words the quotation adds.
+    pub expected: String,
+    /// The quotation's words, or nothing for words it leaves out.
+    pub found: String,
+}
+
+impl fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.expected.as_str(), self.found.as_str()) {
+            (expected, "") => write!(f, "missing “{expected}”"),
+            ("", found) => write!(f, "extra “{found}”"),
+            (expected, found) => write!(f, "“{found}” should be “{expected}”"),
+        }
+    }
+}
+
+impl Check<'_> {
+    /// The quotation with the source's words worked in: what it leaves out or gets wrong in
+    /// green, what it adds or has instead in red.
+    pub fn marked(&self) -> String {
+        let words: Vec<_> = self
+            .changes
+            .iter()
+            .map(|change| match change {
+                Change::Same(word) => word.to_string(),
+                Change::Removed(word) => word.green().bold().to_string(),
+                Change::Added(word) => word.red().crossed_out().to_string(),
+            })
+            .collect();
+        words.join(" ")
+    }
+}
+
+/// Compare a quotation with the text of the passage it quotes. An ellipsis at the start or end of
+/// the quotation excuses whatever of the source it skips there.
+pub(crate) fn check<'a>(source: &'a str, quoted: &'a str, ignore_punctuation: bool) -> Check<'a> {
+    const ELLIPSES: [&str; 2] = ["...", "…"];
+
+    let mut quoted = quoted.trim();
+    let starts_short = match ELLIPSES.iter().find_map(|e| quoted.strip_prefix(e)) {
+        Some(rest) => {
+            quoted = rest;
+            true
+        }
+        None => false,
+    };
+    let stops_short = match ELLIPSES.iter().find_map(|e| quoted.strip_suffix(e)) {
+        Some(rest) => {
+            quoted = rest;
+            true
+        }
+        None => false,
+    };
+
+    let mut changes = if ignore_punctuation {
+        fiat_lux::diff_words_by(source, quoted, |a, b| bare(a) == bare(b))
+    } else {
+        fiat_lux::diff_words(source, quoted)
+    };
+
+    // The ends of the source an ellipsis stands in for.
+    if starts_short {
+        let skipped = changes
+            .iter()
+            .take_while(|change| matches!(change, Change::Removed(_)))
+            .count();
+        changes.drain(..skipped);
+    }
+    if stops_short {
+        let kept = changes.len()
+            - changes
+                .iter()
+                .rev()
+                .take_while(|change| matches!(change, Change::Removed(_)))
+                .count();
+        changes.truncate(kept);
+    }
+
+    let mut discrepancies = Vec::new();
+    let mut current = Discrepancy {
+        expected: String::new(),
+        found: String::new(),
+    };
+    for change in changes.iter().chain([&Change::Same("")]) {
+        let (words, word) = match change {
+            Change::Removed(word) => (&mut current.expected, word),
+            Change::Added(word) => (&mut current.found, word),
+            Change::Same(_) => {
+                if !current.expected.is_empty() || !current.found.is_empty() {
+                    discrepancies.push(current.clone());
+                    current.expected.clear();
+                    current.found.clear();
+                }
+                continue;
+            }
+        };
+        if !words.is_empty() {
+            words.push(' ');
+        }
+        words.push_str(word);
+    }
+
+    Check {
+        changes,
+        discrepancies,
+    }
+}
+
+/// A word without its punctuation.
+fn bare(word: &str) -> String {
+    word.chars().filter(|c| c.is_alphanumeric()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, Discrepancy};
+
+    const SOURCE: &str = "For God so loved the world, that he gave his only begotten Son";
+
+    #[test]
+    fn checking() {
+        assert!(check(SOURCE, "For God so loved...", false)
+            .discrepancies
+            .is_empty());
+        assert!(check(SOURCE, "…he gave his only begotten Son", false)
+            .discrepancies
+            .is_empty());
+
+        let found = check(
+            SOURCE,
+            "For God so loved the world that he gave his Son",
+            false,
+        );
+        assert_eq!(
+            vec![
+                Discrepancy {
+                    expected: String::from("world,"),
+                    found: String::from("world"),
+                },
+                Discrepancy {
+                    expected: String::from("only begotten"),
+                    found: String::new(),
+                },
+            ],
+            found.discrepancies
+        );
+
+        let found = check(
+            SOURCE,
+            "For God so loved the world that he gave his Son",
+            true,
+        );
+        assert_eq!(1, found.discrepancies.len());
+    }
+}
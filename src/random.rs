@@ -0,0 +1,71 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::Parser;
+use fiat_lux::{Passage, Text, Translation};
+
+use crate::{Args, Error, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct RandomArgs {
+    /// draw from the passages on a topic, e.g. comfort or hope (default: any topic)
+    #[clap(long)]
+    topic: Option<String>,
+
+    /// leave out passages longer than this many words, e.g. to fit a post
+    #[clap(long)]
+    max_words: Option<usize>,
+
+    /// list the topics and how many passages each has
+    #[clap(long, conflicts_with_all = ["topic", "max_words"])]
+    topics: bool,
+}
+
+/// A passage picked at random from the topical index, short enough to share.
+pub fn random(random_args: &RandomArgs, translation: Translation, args: &Args) -> Result<()> {
+    if random_args.topics {
+        for topic in fiat_lux::topics() {
+            println!("{topic:<14}{}", fiat_lux::topic(topic).len());
+        }
+        return Ok(());
+    }
+
+    let mut passages: Vec<Passage> = match &random_args.topic {
+        Some(topic) => fiat_lux::topic(topic),
+        None => fiat_lux::topical().map(|(_, passage)| passage).collect(),
+    };
+    if passages.is_empty() {
+        return Err(Error::UnknownTopic(
+            random_args.topic.clone().unwrap_or_default(),
+        ));
+    }
+    passages.sort_by_key(|passage| passage.to_string());
+    passages.dedup();
+
+    let bible = crate::open_bible()?;
+    let mut candidates: Vec<Vec<Text>> = Vec::new();
+    for passage in passages {
+        let texts = bible.lookup(passage.book, passage.location, translation)?;
+        let words: usize = texts
+            .iter()
+            .map(|text| text.content.split_whitespace().count())
+            .sum();
+        if !texts.is_empty() && random_args.max_words.is_none_or(|max| words <= max) {
+            candidates.push(texts);
+        }
+    }
+
+    if candidates.is_empty() {
+        return Err(Error::NothingShortEnough(
+            random_args.max_words.unwrap_or_default(),
+        ));
+    }
+
+    // Nothing here needs to be unpredictable, so the clock will do for a seed.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.subsec_nanos())
+        .unwrap_or_default();
+    let texts = &candidates[nanos as usize % candidates.len()];
+    crate::print_texts(texts, translation, args);
+    Ok(())
+}
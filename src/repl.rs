@@ -0,0 +1,107 @@
+use std::path::PathBuf;
+
+use fiat_lux::{Bible, Book, PartialLocation, Passage, Translation};
+use rustyline::{error::ReadlineError, DefaultEditor};
+
+use crate::{config::state_dir, history, Args, Result, SearchArgs};
+
+const HELP: &str = "\
+john 3:16      look up a passage
+/living water  search
+:asv           switch translation (or :translation NAME)
+:quit          leave (or ctrl-d)";
+
+pub fn repl(translation: Translation, args: &Args) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let mut translation = translation;
+
+    #[cfg(feature = "pager")]
+    crate::PAGE.store(false, std::sync::atomic::Ordering::Relaxed);
+
+    let mut editor = DefaultEditor::new()?;
+    let path = history_path();
+    if let Some(path) = &path {
+        // No history yet is fine.
+        let _ = editor.load_history(path);
+    }
+
+    println!("{HELP}");
+    loop {
+        let line = match editor.readline(&format!("{translation}> ")) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line)?;
+
+        // A bad reference or a query which doesn't parse is no reason to leave.
+        match command(&bible, line, &mut translation, args) {
+            Ok(true) => (),
+            Ok(false) => break,
+            Err(e) => eprintln!("{e}"),
+        }
+    }
+
+    if let Some(path) = &path {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        editor.save_history(path)?;
+    }
+    Ok(())
+}
+
+/// Carry out one line of input, returning whether to keep going.
+fn command(bible: &Bible, line: &str, translation: &mut Translation, args: &Args) -> Result<bool> {
+    if let Some(query) = line.strip_prefix('/') {
+        let search_args = SearchArgs {
+            query: query.trim().to_string(),
+            ..Default::default()
+        };
+        crate::search(bible, &search_args, *translation, args)?;
+        return Ok(true);
+    }
+
+    if let Some(command) = line.strip_prefix(':') {
+        let (name, rest) = command.split_once(' ').unwrap_or((command, ""));
+        let chosen: Translation = match name {
+            "q" | "quit" | "exit" => return Ok(false),
+            "h" | "help" => {
+                println!("{HELP}");
+                return Ok(true);
+            }
+            "t" | "translation" => rest.trim().parse()?,
+            name => name.parse()?,
+        };
+
+        // Any name parses as a translation someone might have added, so try it out before
+        // switching; otherwise a typo means every lookup after it fails. Remote translations
+        // would cost a request, so they're taken on trust.
+        if !chosen.is_remote() {
+            let first = PartialLocation {
+                chapter: 1,
+                verse: Some(1),
+                end: None,
+            };
+            bible.lookup(Book::Genesis, Some(first), chosen)?;
+        }
+        *translation = chosen;
+        return Ok(true);
+    }
+
+    let passage: Passage = line.parse()?;
+    crate::show_passage(bible, passage.book, passage.location, *translation, args)?;
+    history::record_lookup(passage, *translation);
+    Ok(true)
+}
+
+/// Lines typed at the prompt, kept apart from `flux history`, which only has what was looked up.
+fn history_path() -> Option<PathBuf> {
+    state_dir().map(|dir| dir.join("repl_history.txt"))
+}
@@ -0,0 +1,202 @@
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+
+use clap::Parser;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use fiat_lux::{Book, PartialLocation, Text, Translation};
+
+use crate::{
+    present::{centered, to_u16},
+    Result,
+};
+
+#[derive(Clone, Debug, Parser)]
+pub struct RsvpArgs {
+    book: Book,
+    location: Option<PartialLocation>,
+
+    /// reading speed, in words per minute
+    #[clap(short, long, default_value_t = 300)]
+    wpm: u32,
+
+    /// words to show at a time
+    #[clap(short, long, default_value_t = 1)]
+    chunk: usize,
+}
+
+/// A few words at a time, along with the verse they came from.
+struct Frame<'a> {
+    words: String,
+    text: &'a Text,
+}
+
+pub fn rsvp(args: &RsvpArgs, translation: Translation) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let texts = bible.lookup(args.book, args.location, translation)?;
+    let chunk = args.chunk.max(1);
+
+    // Chunks don't run from one verse into the next, so rewinding to a verse always lands on its
+    // first word.
+    let frames: Vec<_> = texts
+        .iter()
+        .flat_map(|text| {
+            let words: Vec<_> = text.content.split_whitespace().collect();
+            words
+                .chunks(chunk)
+                .map(|words| Frame {
+                    words: words.join(" "),
+                    text,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    if frames.is_empty() {
+        return Ok(());
+    }
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen, cursor::Hide)?;
+
+    let result = show(&mut stdout, &frames, args.wpm.max(1), translation);
+
+    execute!(stdout, cursor::Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    Ok(result?)
+}
+
+fn show(
+    out: &mut impl Write,
+    frames: &[Frame],
+    mut wpm: u32,
+    translation: Translation,
+) -> io::Result<()> {
+    let last = frames.len() - 1;
+    let mut idx = 0;
+    let mut paused = false;
+
+    loop {
+        let frame = &frames[idx];
+        draw(out, frame, wpm, paused, translation)?;
+
+        // Nothing pressed before the word's time is up means on to the next one.
+        if !paused && !event::poll(delay(frame, wpm))? {
+            if idx == last {
+                paused = true;
+            } else {
+                idx += 1;
+            }
+            continue;
+        }
+
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        else {
+            continue;
+        };
+
+        match code {
+            KeyCode::Char(' ') | KeyCode::Enter => paused = !paused,
+            KeyCode::Left | KeyCode::Char('h') => idx = idx.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => idx = (idx + 1).min(last),
+            KeyCode::Up | KeyCode::Char('k') => idx = verse_start(frames, idx),
+            KeyCode::Down | KeyCode::Char('j') => idx = next_verse(frames, idx),
+            KeyCode::Char('+' | '=') => wpm += 25,
+            KeyCode::Char('-') => wpm = wpm.saturating_sub(25).max(50),
+            KeyCode::Home => idx = 0,
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            _ => (),
+        }
+    }
+}
+
+/// How long to show a frame. Words ending a clause or sentence get a little longer, which gives
+/// the reader the same pause the punctuation would.
+fn delay(frame: &Frame, wpm: u32) -> Duration {
+    let words = frame.words.split_whitespace().count() as u64;
+    let base = 60_000 * words / u64::from(wpm);
+    let millis = match frame.words.chars().last() {
+        Some('.' | '?' | '!' | ':' | ';') => base * 2,
+        Some(',') => base * 3 / 2,
+        _ => base,
+    };
+    Duration::from_millis(millis)
+}
+
+/// The first frame of the current verse or, if we're already there, of the verse before it.
+fn verse_start(frames: &[Frame], idx: usize) -> usize {
+    let start = |idx: usize| {
+        let text = frames[idx].text;
+        (0..=idx)
+            .rev()
+            .take_while(|&i| std::ptr::eq(frames[i].text, text))
+            .last()
+            .unwrap_or(idx)
+    };
+
+    let first = start(idx);
+    if first == idx && idx > 0 {
+        start(idx - 1)
+    } else {
+        first
+    }
+}
+
+fn next_verse(frames: &[Frame], idx: usize) -> usize {
+    let text = frames[idx].text;
+    (idx..frames.len())
+        .find(|&i| !std::ptr::eq(frames[i].text, text))
+        .unwrap_or(idx)
+}
+
+fn draw(
+    out: &mut impl Write,
+    frame: &Frame,
+    wpm: u32,
+    paused: bool,
+    translation: Translation,
+) -> io::Result<()> {
+    let (w, h) = terminal::size()?;
+    let (w, h) = (usize::from(w), usize::from(h));
+    let middle = h / 2;
+
+    let text = frame.text;
+    let status = format!(
+        "{} {}:{} ({translation})  {wpm} wpm{}",
+        text.book,
+        text.chapter,
+        text.verse,
+        if paused { "  paused" } else { "" }
+    );
+
+    queue!(
+        out,
+        Clear(ClearType::All),
+        cursor::MoveTo(centered(w, frame.words.chars().count()), to_u16(middle)),
+        SetAttribute(Attribute::Bold),
+        Print(&frame.words),
+        SetAttribute(Attribute::Reset),
+        cursor::MoveTo(
+            centered(w, status.chars().count()),
+            to_u16(h.saturating_sub(2))
+        ),
+        SetAttribute(Attribute::Dim),
+        Print(&status),
+        SetAttribute(Attribute::Reset),
+    )?;
+
+    out.flush()
+}
@@ -0,0 +1,273 @@
+//! A small JSON API over HTTP, for web widgets and chat bots which would rather not parse
+//! references themselves.
+//!
+//! ```text
+//! GET /v1/kjv/john/3/16    {"reference": "John 3:16", "translation": "KJV", "text": "...", ...}
+//! GET /v1/kjv/psalms/23    the whole chapter
+//! GET /v1/search?q=grace&translation=asv&limit=5&in=nt
+//! ```
+//!
+//! The verse may be a range, e.g. `/v1/kjv/john/3/16-18`. Search takes the same syntax as
+//! `flux search`, and returns the best hits first with their scores. Anything that goes wrong comes
+//! back as `{"error": "..."}` with a 400 or 404.
+//!
+//! It's plain HTTP/1.1 on std's `TcpListener`, one request per connection and one connection at a
+//! time, which is plenty behind a bot or a cache and keeps the binary free of a web framework.
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader, Write},
+    net::{IpAddr, Ipv4Addr, TcpListener, TcpStream},
+    time::Duration,
+};
+
+use clap::Parser;
+use fiat_lux::{Bible, Passage, Scope, Text, Translation};
+use serde_json::{json, Value};
+
+use crate::{format, Result};
+
+/// How long a client gets to send its request before the connection is dropped, so one stalled
+/// client can't hold up the rest.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The most search hits one request gets.
+const MAX_LIMIT: usize = 100;
+
+#[derive(Clone, Debug, Parser)]
+pub struct ServeArgs {
+    /// the port to listen on
+    #[clap(short, long, default_value_t = 8080)]
+    port: u16,
+
+    /// the address to listen on; 0.0.0.0 makes the api reachable from other machines
+    #[clap(long, default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    bind: IpAddr,
+}
+
+pub fn serve(args: &ServeArgs, translation: Translation) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let listener = TcpListener::bind((args.bind, args.port))?;
+    eprintln!("listening on http://{}", listener.local_addr()?);
+
+    for stream in listener.incoming() {
+        // A client hanging up early is its own problem, not the server's.
+        if let Err(e) = stream.and_then(|stream| handle(&bible, stream, translation)) {
+            eprintln!("{e}");
+        }
+    }
+    Ok(())
+}
+
+fn handle(bible: &Bible, stream: TcpStream, translation: Translation) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // The headers tell us nothing we need, but they have to be read before responding.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let (status, body) = match request_line.split_whitespace().collect::<Vec<_>>()[..] {
+        ["GET", target, _] => route(bible, target, translation),
+        [_, _, _] => (405, json!({ "error": "only GET is supported" })),
+        _ => (400, json!({ "error": "malformed request" })),
+    };
+    respond(&stream, status, &body)
+}
+
+fn respond(mut stream: &TcpStream, status: u16, body: &Value) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\n\
+         Content-Type: application/json; charset=utf-8\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// The status and body for a request target, e.g. `/v1/kjv/john/3/16`.
+fn route(bible: &Bible, target: &str, translation: Translation) -> (u16, Value) {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let segments: Vec<_> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(decode)
+        .collect();
+    let segments: Vec<_> = segments.iter().map(String::as_str).collect();
+
+    let result = match segments[..] {
+        ["v1", "search"] => search(bible, &parse_query(query), translation),
+        ["v1", translation, book, chapter] => lookup(bible, translation, book, chapter),
+        ["v1", translation, book, chapter, verse] => {
+            lookup(bible, translation, book, &format!("{chapter}:{verse}"))
+        }
+        _ => Err((404, format!("no such endpoint: {path}"))),
+    };
+
+    match result {
+        Ok(body) => (200, body),
+        Err((status, message)) => (status, json!({ "error": message })),
+    }
+}
+
+fn lookup(
+    bible: &Bible,
+    translation: &str,
+    book: &str,
+    location: &str,
+) -> Result<Value, (u16, String)> {
+    let translation: Translation = translation.parse().map_err(bad_request)?;
+    let passage = Passage {
+        book: book.parse().map_err(bad_request)?,
+        location: Some(location.parse().map_err(bad_request)?),
+    };
+
+    let texts = bible
+        .lookup(passage.book, passage.location, translation)
+        .map_err(bad_request)?;
+    if texts.is_empty() {
+        return Err((404, format!("{passage} isn't in the {translation}")));
+    }
+
+    let text: Vec<_> = texts.iter().map(|text| text.content.as_str()).collect();
+    Ok(json!({
+        "reference": format::group_reference(&texts),
+        "translation": translation.to_string(),
+        "text": text.join(" "),
+        "verses": texts.iter().map(verse).collect::<Vec<_>>(),
+    }))
+}
+
+fn search(
+    bible: &Bible,
+    params: &HashMap<String, String>,
+    translation: Translation,
+) -> Result<Value, (u16, String)> {
+    let query = params
+        .get("q")
+        .filter(|query| !query.trim().is_empty())
+        .ok_or((400, String::from("search needs a query, e.g. ?q=grace")))?;
+    let translation = match params.get("translation") {
+        Some(name) => name.parse().map_err(bad_request)?,
+        None => translation,
+    };
+    let limit = match params.get("limit") {
+        Some(limit) => limit
+            .parse::<usize>()
+            .map_err(|_| (400, format!("limit should be a number, not '{limit}'")))?,
+        None => 10,
+    };
+    let scopes = match params.get("in") {
+        Some(scopes) => scopes
+            .split(',')
+            .map(|scope| scope.trim().parse::<Scope>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(bad_request)?,
+        None => Vec::new(),
+    };
+
+    let hits = bible
+        .search_scored(query, translation, &scopes)
+        .map_err(bad_request)?;
+    let results: Vec<_> = hits
+        .iter()
+        .take(limit.min(MAX_LIMIT))
+        .map(|(score, text)| {
+            let mut result = verse(text);
+            result["score"] = json!(score);
+            result
+        })
+        .collect();
+
+    Ok(json!({
+        "query": query,
+        "translation": translation.to_string(),
+        "total": hits.len(),
+        "results": results,
+    }))
+}
+
+fn verse(text: &Text) -> Value {
+    json!({
+        "reference": format!("{} {}:{}", text.book, text.chapter, text.verse),
+        "book": text.book.to_string(),
+        "chapter": text.chapter,
+        "verse": text.verse,
+        "text": text.content,
+    })
+}
+
+fn bad_request(e: impl ToString) -> (u16, String) {
+    (400, e.to_string())
+}
+
+/// The parameters of a query string, decoded. A parameter given twice keeps the last value.
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (
+                decode(&key.replace('+', " ")),
+                decode(&value.replace('+', " ")),
+            )
+        })
+        .collect()
+}
+
+/// Undo percent-encoding, leaving anything malformed as it was.
+fn decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let hex = bytes
+            .get(idx + 1..idx + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[idx], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                idx += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                idx += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, parse_query};
+
+    #[test]
+    fn queries() {
+        assert_eq!("by grace", decode("by%20grace"));
+        assert_eq!("100%", decode("100%"));
+        assert_eq!("\"by grace\"", decode("%22by%20grace%22"));
+
+        let params = parse_query("q=%2Bfaith+-works&translation=asv&limit=");
+        assert_eq!("+faith -works", params["q"]);
+        assert_eq!("asv", params["translation"]);
+        assert_eq!("", params["limit"]);
+    }
+}
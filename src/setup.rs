@@ -0,0 +1,144 @@
+//! `flux setup`: a few questions, the first time flux is run at a terminal (or whenever asked),
+//! whose answers go in the config file.
+
+use std::{
+    fs,
+    io::{self, BufRead, IsTerminal, Write},
+};
+
+use fiat_lux::Translation;
+use toml::{Table, Value};
+
+use crate::{config, Result};
+
+/// Whether flux has never been set up: there's no config file, and someone is there to ask.
+pub fn first_run() -> bool {
+    config::config_path().is_some_and(|path| !path.exists())
+        && io::stdin().is_terminal()
+        && io::stdout().is_terminal()
+}
+
+/// Ask for the settings most people change, and write them to the config file. Anything else
+/// already in it stays as it was.
+pub fn setup() -> Result<()> {
+    let Some(path) = config::config_path() else {
+        println!("there's no home directory to keep a config file in");
+        return Ok(());
+    };
+    let mut table: Table = match fs::read_to_string(&path) {
+        Ok(text) => toml::from_str(&text)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Table::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    println!("Setting flux up; press enter to keep what's in brackets.\n");
+
+    let names: Vec<_> = Translation::ALL
+        .into_iter()
+        .map(|translation| translation.to_string())
+        .collect();
+    let current = string(&table, "translation")
+        .unwrap_or("KJV")
+        .to_uppercase();
+    let translation = loop {
+        let answer = ask(
+            &format!("Translation to read ({})", names.join(", ")),
+            &current,
+        )?;
+        match answer.parse::<Translation>() {
+            Ok(translation) => break translation,
+            Err(e) => println!("{e}"),
+        }
+    };
+    table.insert(
+        "translation".into(),
+        Value::String(translation.to_string().to_lowercase()),
+    );
+
+    let color = table.get("color").and_then(Value::as_bool).unwrap_or(true);
+    // Saying yes leaves color to the terminal, since `color = true` would force it into pipes too.
+    if confirm("Color the output", color)? {
+        table.remove("color");
+    } else {
+        table.insert("color".into(), Value::Boolean(false));
+    }
+
+    #[cfg(feature = "pager")]
+    {
+        // Left unset, it's $PAGER, bat or less, whichever is installed.
+        let current = string(&table, "pager").unwrap_or_default().to_string();
+        let pager = ask(
+            "Page long passages through (none for no pager; enter for $PAGER, bat or less)",
+            &current,
+        )?;
+        if !pager.is_empty() {
+            table.insert("pager".into(), Value::String(pager));
+        }
+    }
+
+    #[cfg(feature = "remote")]
+    {
+        println!("\nThe ESV and NIV are fetched online, and need an api key; enter to skip.");
+        let remote = table
+            .entry("remote")
+            .or_insert_with(|| Value::Table(Table::new()));
+        if let Value::Table(remote) = remote {
+            let mut key = |name: &str, prompt: &str| -> io::Result<()> {
+                let current = string(remote, name).unwrap_or_default().to_string();
+                let answer = ask(prompt, &current)?;
+                if !answer.is_empty() {
+                    remote.insert(name.into(), Value::String(answer));
+                }
+                Ok(())
+            };
+            key("esv_key", "ESV api key (https://api.esv.org)")?;
+            key(
+                "api_bible_key",
+                "API.Bible key, for the NIV (https://scripture.api.bible)",
+            )?;
+            key("niv_bible_id", "The NIV's bible id on API.Bible")?;
+        }
+        if table["remote"].as_table().is_some_and(Table::is_empty) {
+            table.remove("remote");
+        }
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, toml::to_string(&table).map_err(io::Error::other)?)?;
+    println!(
+        "\nWrote {}; run flux setup to change it again.\n",
+        path.display()
+    );
+    Ok(())
+}
+
+fn string<'a>(table: &'a Table, key: &str) -> Option<&'a str> {
+    table.get(key).and_then(Value::as_str)
+}
+
+/// The answer to a question, or the default for no answer (or no more input).
+fn ask(prompt: &str, default: &str) -> io::Result<String> {
+    match default {
+        "" => print!("{prompt}: "),
+        default => print!("{prompt} [{default}]: "),
+    }
+    io::stdout().flush()?;
+
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    match line.trim() {
+        "" => Ok(default.to_string()),
+        answer => Ok(answer.to_string()),
+    }
+}
+
+fn confirm(prompt: &str, default: bool) -> io::Result<bool> {
+    let answer = ask(prompt, if default { "Y/n" } else { "y/N" })?;
+    Ok(match answer.to_lowercase().as_str() {
+        "y" | "yes" => true,
+        "n" | "no" => false,
+        _ => default,
+    })
+}
@@ -0,0 +1,51 @@
+use clap::Parser;
+use fiat_lux::{Book, PartialLocation, Passage, Stats, Translation};
+
+use crate::Result;
+
+#[derive(Clone, Debug, Parser)]
+pub struct StatsArgs {
+    book: Book,
+    location: Option<PartialLocation>,
+
+    /// how many of the most frequent words to list
+    #[clap(short = 'n', long, default_value_t = 10)]
+    top: usize,
+
+    /// reading speed for the time estimate, in words per minute
+    #[clap(short, long, default_value_t = 200)]
+    wpm: u32,
+}
+
+pub fn stats(args: &StatsArgs, translation: Translation) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let texts = bible.lookup(args.book, args.location, translation)?;
+    let stats = Stats::new(&texts, args.top);
+
+    let passage = Passage {
+        book: args.book,
+        location: args.location,
+    };
+    let minutes = stats.reading_time(args.wpm).as_secs().div_ceil(60);
+
+    println!("{passage} ({translation})");
+    println!("verses        {:>7}", stats.verses);
+    println!("words         {:>7}", stats.words);
+    println!("unique words  {:>7}", stats.unique_words);
+    println!("reading time  {minutes:>3} min at {} wpm", args.wpm);
+
+    if !stats.top.is_empty() {
+        let width = stats
+            .top
+            .iter()
+            .map(|(word, _)| word.len())
+            .max()
+            .unwrap_or_default();
+        println!("\nmost frequent");
+        for (word, count) in &stats.top {
+            println!("  {word:width$}  {count:>5}");
+        }
+    }
+
+    Ok(())
+}
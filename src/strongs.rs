@@ -0,0 +1,79 @@
+//! What a tagged translation makes of a Strong's number, for checking how consistently a word in
+//! the original is rendered.
+
+use std::{io::Write, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+use fiat_lux::Strongs;
+use serde_json::json;
+
+use crate::{export, Error, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct StrongsArgs {
+    #[clap(subcommand)]
+    command: StrongsCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum StrongsCommand {
+    /// list every english word used for a strong's number, with counts
+    Export {
+        /// e.g. G25 or H430
+        number: Strongs,
+
+        /// the SWORD module to read, which has to be tagged with strong's numbers
+        #[clap(long, default_value = "KJV")]
+        module: String,
+
+        /// output file, .csv or .json (default: csv on stdout)
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+pub fn strongs(args: &StrongsArgs) -> Result<()> {
+    let StrongsCommand::Export {
+        number,
+        module,
+        output,
+    } = &args.command;
+
+    let json = match output.as_deref().map(|path| {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+    }) {
+        None => false,
+        Some(Some(ext)) if ext == "csv" || ext == "json" => ext == "json",
+        Some(_) => return Err(Error::UnknownExport(output.clone().unwrap())),
+    };
+
+    let renderings = crate::open_bible()?.renderings(module, *number)?;
+    if renderings.is_empty() {
+        eprintln!("no words tagged {number} in {module}; is it a module with strong's numbers?");
+    }
+
+    let mut out = export::open_output(output.as_deref())?;
+    if json {
+        let renderings: Vec<_> = renderings
+            .iter()
+            .map(|(rendering, count)| json!({ "rendering": rendering, "count": count }))
+            .collect();
+        let document = json!({
+            "strongs": number.to_string(),
+            "module": module,
+            "renderings": renderings,
+        });
+        serde_json::to_writer_pretty(&mut out, &document).map_err(std::io::Error::other)?;
+        writeln!(out)?;
+    } else {
+        writeln!(out, "strongs,rendering,count")?;
+        for (rendering, count) in &renderings {
+            writeln!(out, "{number},{},{count}", export::csv_field(rendering))?;
+        }
+    }
+
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use std::ops::Range;
+
+use clap::Parser;
+use crossterm::style::Stylize;
+#[cfg(feature = "sword")]
+use fiat_lux::{Bible, PartialLocation, Strongs};
+use fiat_lux::{Scope, Study, Text, Translation};
+
+use crate::{count::plural, Result};
+
+/// Characters of context either side of the word in each concordance line.
+const CONTEXT: usize = 32;
+
+/// The longest bar in the chapter chart.
+const BAR_WIDTH: usize = 40;
+
+/// How many Strong's entries to list, and how many renderings of each.
+#[cfg(feature = "sword")]
+const STRONGS_ENTRIES: usize = 5;
+#[cfg(feature = "sword")]
+const STRONGS_RENDERINGS: usize = 4;
+
+#[derive(Clone, Debug, Parser)]
+pub struct StudyArgs {
+    /// a word or phrase
+    term: String,
+
+    /// only study these books (or ot, nt, gospels), comma-separated
+    #[clap(short, long = "book", alias = "in", value_delimiter = ',')]
+    scopes: Vec<Scope>,
+
+    /// how many concordance lines to show
+    #[clap(short, long, default_value_t = 20)]
+    lines: usize,
+
+    /// how many collocated words to list
+    #[clap(short = 'n', long, default_value_t = 10)]
+    top: usize,
+}
+
+pub fn study(args: &StudyArgs, translation: Translation) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let concordance = bible.concordance(&args.term, translation, &args.scopes)?;
+    let study = Study::new(&concordance, args.top);
+
+    let scopes: Vec<_> = args.scopes.iter().map(ToString::to_string).collect();
+    let within = if scopes.is_empty() {
+        String::new()
+    } else {
+        format!(" in {}", scopes.join(", "))
+    };
+    println!(
+        "{}",
+        format!("\"{}\"{within} ({translation})", args.term).bold()
+    );
+    println!(
+        "{} {} in {} {}",
+        study.occurrences,
+        plural(study.occurrences, "occurrence"),
+        study.verses,
+        plural(study.verses, "verse")
+    );
+    if concordance.is_empty() {
+        return Ok(());
+    }
+
+    // For a single book, chapters without the word are worth seeing too.
+    let mut chapters = study.chapters.clone();
+    if let [Scope::Book(book)] = args.scopes[..] {
+        let last = bible
+            .lookup(book, None, translation)?
+            .last()
+            .map_or(0, |text| text.chapter);
+        chapters = (1..=last)
+            .map(|chapter| {
+                let count = study
+                    .chapters
+                    .iter()
+                    .find(|&&(_, c, _)| c == chapter)
+                    .map_or(0, |&(_, _, count)| count);
+                (book, chapter, count)
+            })
+            .collect();
+    }
+
+    println!("\n{}", "by chapter".bold());
+    let labels: Vec<_> = chapters
+        .iter()
+        .map(|(book, chapter, _)| format!("{book} {chapter}"))
+        .collect();
+    let width = labels.iter().map(String::len).max().unwrap_or_default();
+    let most = chapters
+        .iter()
+        .map(|&(_, _, count)| count)
+        .max()
+        .unwrap_or(1);
+    for (label, &(_, _, count)) in labels.iter().zip(&chapters) {
+        let bar = "█".repeat((count * BAR_WIDTH).div_ceil(most));
+        println!("  {label:width$}  {count:>4}  {bar}");
+    }
+
+    println!("\n{}", "in context".bold());
+    let lines: Vec<_> = concordance
+        .iter()
+        .flat_map(|(text, ranges)| ranges.iter().map(move |range| (text, range)))
+        .collect();
+    let references: Vec<_> = lines
+        .iter()
+        .take(args.lines)
+        .map(|(text, _)| format!("{} {}:{}", text.book, text.chapter, text.verse))
+        .collect();
+    let width = references.iter().map(String::len).max().unwrap_or_default();
+    for (reference, (text, range)) in references.iter().zip(&lines) {
+        println!("  {reference:width$}  {}", kwic(text, range));
+    }
+    if lines.len() > args.lines {
+        println!("  ... and {} more", lines.len() - args.lines);
+    }
+
+    if !study.collocates.is_empty() {
+        println!("\n{}", "found nearby".bold());
+        let width = study
+            .collocates
+            .iter()
+            .map(|(word, _)| word.len())
+            .max()
+            .unwrap_or_default();
+        for (word, count) in &study.collocates {
+            println!("  {word:width$}  {count:>4}");
+        }
+    }
+
+    #[cfg(feature = "sword")]
+    strongs_entries(&bible, &concordance, translation)?;
+
+    Ok(())
+}
+
+/// The Strong's numbers behind the word, from the translation's own SWORD module if it's tagged,
+/// or else CrossWire's KJV, with the original and what else the number is rendered as.
+#[cfg(feature = "sword")]
+fn strongs_entries(
+    bible: &Bible,
+    concordance: &[(Text, Vec<Range<usize>>)],
+    translation: Translation,
+) -> Result<()> {
+    let mut module = translation.to_string();
+    let mut entries: Vec<(Strongs, usize)> = Vec::new();
+    for chapter in concordance.chunk_by(|(a, _), (b, _)| a.book == b.book && a.chapter == b.chapter)
+    {
+        let location = PartialLocation {
+            chapter: chapter[0].0.chapter,
+            verse: None,
+            end: None,
+        };
+        let mut tags = bible.tags(&module, chapter[0].0.book, Some(location));
+        if matches!(tags, Err(fiat_lux::Error::UnknownModule(_))) && module != "KJV" {
+            module = String::from("KJV");
+            tags = bible.tags(&module, chapter[0].0.book, Some(location));
+        }
+        let tags = match tags {
+            Err(fiat_lux::Error::UnknownModule(_)) => {
+                println!("\n{}", "strong's entries".bold());
+                println!(
+                    "  {}",
+                    "install CrossWire's KJV, which is tagged with strong's numbers, to see them"
+                        .dim()
+                );
+                return Ok(());
+            }
+            tags => tags?,
+        };
+
+        for (text, ranges) in chapter {
+            let matched: Vec<_> = ranges
+                .iter()
+                .map(|range| &text.content[range.clone()])
+                .collect();
+            // A tag can cover a few words ("loved him"), so any of them will do.
+            let tagged = tags.iter().filter(|tag| {
+                tag.verse == text.verse
+                    && matched.iter().any(|word| {
+                        tag.word.eq_ignore_ascii_case(word)
+                            || tag
+                                .word
+                                .split(|c: char| !c.is_alphanumeric())
+                                .any(|part| part.eq_ignore_ascii_case(word))
+                    })
+            });
+            for tag in tagged {
+                match entries
+                    .iter_mut()
+                    .find(|(strongs, _)| *strongs == tag.strongs)
+                {
+                    Some((_, count)) => *count += 1,
+                    None => entries.push((tag.strongs, 1)),
+                }
+            }
+        }
+    }
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    entries.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    println!("\n{}", format!("strong's entries ({module})").bold());
+    for &(strongs, count) in entries.iter().take(STRONGS_ENTRIES) {
+        let original = fiat_lux::original_word(strongs)
+            .map(|word| format!(" {word}"))
+            .unwrap_or_default();
+        let renderings: Vec<_> = bible
+            .renderings(&module, strongs)?
+            .into_iter()
+            .take(STRONGS_RENDERINGS)
+            .map(|(rendering, count)| format!("{rendering} ({count})"))
+            .collect();
+        println!(
+            "  {:<6}{original}  {count:>4}  rendered {}",
+            strongs.to_string(),
+            renderings.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// A keyword-in-context line: the word in bold, with the text either side of it cut to the same
+/// width so the keywords line up.
+fn kwic(text: &Text, range: &Range<usize>) -> String {
+    let before = text.content[..range.start].trim_start();
+    let after = text.content[range.end..].trim_end();
+
+    let skip = before.chars().count().saturating_sub(CONTEXT);
+    let before: String = before.chars().skip(skip).collect();
+    let after: String = after.chars().take(CONTEXT).collect();
+
+    format!(
+        "{before:>CONTEXT$}{}{after}",
+        text.content[range.clone()].bold()
+    )
+}
@@ -0,0 +1,108 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, Subcommand};
+use fiat_lux::Translation;
+
+use crate::{Error, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct TranslationCommandArgs {
+    #[clap(subcommand)]
+    command: TranslationCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum TranslationCommand {
+    /// index a dat file as a new translation
+    Add {
+        /// short name used with --translation, e.g. ylt
+        name: Translation,
+        path: PathBuf,
+    },
+
+    /// import a directory of usfm files (one per book) as a new translation
+    Import {
+        /// short name used with --translation
+        name: Translation,
+        dir: PathBuf,
+    },
+
+    /// list available translations
+    List,
+
+    /// remove a translation added with `add`
+    Remove { name: Translation },
+}
+
+pub fn translation(args: &TranslationCommandArgs) -> Result<()> {
+    let bible = crate::open_bible()?;
+    match &args.command {
+        TranslationCommand::Add { name, path } => {
+            let text = fs::read_to_string(path)?;
+            let count = bible.add_translation(*name, &text)?;
+            println!("indexed {count} verses as {name}");
+        }
+
+        TranslationCommand::Import { name, dir } => {
+            let text = read_usfm_dir(dir)?;
+            let count = bible.add_translation(*name, &text)?;
+            println!("indexed {count} verses as {name}");
+        }
+
+        TranslationCommand::List => {
+            for translation in Translation::ALL {
+                println!("{translation}");
+            }
+
+            for translation in bible.user_translations()? {
+                println!("{translation} (user)");
+            }
+
+            #[cfg(feature = "sword")]
+            for module in bible.sword_modules() {
+                println!("{module} (sword)");
+            }
+        }
+
+        TranslationCommand::Remove { name } => {
+            bible.remove_translation(*name)?;
+            println!("removed {name}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert every usfm file in a directory into a single dat file.
+fn read_usfm_dir(dir: &Path) -> Result<String> {
+    let mut lines = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_usfm = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("usfm") || ext.eq_ignore_ascii_case("sfm"));
+
+        if !is_usfm {
+            continue;
+        }
+
+        let text = fs::read_to_string(&path)?;
+        let dat = fiat_lux::usfm_to_dat(&text).map_err(|source| Error::Usfm {
+            path: path.clone(),
+            source,
+        })?;
+        lines.extend(dat.lines().map(String::from));
+    }
+
+    if lines.is_empty() {
+        return Err(Error::NoUsfm(dir.into()));
+    }
+
+    // Ids are zero-padded, so this puts the books in canonical order too.
+    lines.sort();
+    Ok(lines.join("\n") + "\n")
+}
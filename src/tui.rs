@@ -0,0 +1,479 @@
+use clap::Parser;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use fiat_lux::{Bible, Book, PartialLocation, Passage, Text, Translation};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, List, ListState, Paragraph},
+    DefaultTerminal, Frame,
+};
+
+use crate::{history, Result};
+
+/// Width of the book and chapter list down the left side.
+const SIDEBAR_WIDTH: u16 = 24;
+
+/// How many hits the search box lists.
+const SEARCH_LIMIT: usize = 50;
+
+#[derive(Clone, Debug, Parser)]
+pub struct TuiArgs {
+    /// where to start reading (default: wherever you last were)
+    book: Option<Book>,
+    chapter: Option<u16>,
+}
+
+/// `translation` is the one asked for on the command line, if any; otherwise it's whichever you
+/// last read in, or failing that the configured one.
+pub fn tui(
+    args: &TuiArgs,
+    translation: Option<Translation>,
+    configured: Option<Translation>,
+) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let last = history::last_lookup();
+    let translation = translation
+        .or(last.map(|(_, translation)| translation))
+        .or(configured)
+        .unwrap_or(Translation::Kjv);
+    let (book, chapter) = match (args.book, last) {
+        (Some(book), _) => (book, args.chapter.unwrap_or(1)),
+        (None, Some((passage, _))) => (
+            passage.book,
+            passage.location.map_or(1, |location| location.chapter),
+        ),
+        (None, None) => (Book::Genesis, 1),
+    };
+
+    // The bundled translations and any the user has added, plus whichever one we were asked for
+    // if it's neither (e.g. a remote one).
+    let mut translations = Translation::ALL.to_vec();
+    translations.extend(bible.user_translations()?);
+    if !translations.contains(&translation) {
+        translations.push(translation);
+    }
+
+    let mut app = App {
+        bible: &bible,
+        translations,
+        translation,
+        book,
+        chapter: chapter.clamp(1, book.chapters()),
+        texts: Vec::new(),
+        focus: Focus::Verses,
+        scroll: 0,
+        scroll_to: None,
+        page: 0,
+        sidebar: ListState::default(),
+        search: None,
+        status: String::new(),
+    };
+    app.load()?;
+
+    let mut terminal = ratatui::try_init()?;
+
+    // Whatever happens in there, we need to put the terminal back the way we found it before
+    // reporting the error.
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result?;
+
+    history::record_lookup(
+        Passage {
+            book: app.book,
+            location: Some(whole_chapter(app.chapter)),
+        },
+        app.translation,
+    );
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Focus {
+    Sidebar,
+    Verses,
+}
+
+/// An entry in the sidebar: every book, with the chapters of the one being read listed under it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Entry {
+    Book(Book),
+    Chapter(u16),
+}
+
+#[derive(Debug, Default)]
+struct Search {
+    query: String,
+    hits: Vec<Text>,
+    selected: ListState,
+}
+
+struct App<'a> {
+    bible: &'a Bible,
+    translations: Vec<Translation>,
+    translation: Translation,
+    book: Book,
+    chapter: u16,
+    texts: Vec<Text>,
+    focus: Focus,
+
+    /// Lines scrolled past in the verse pane, and a verse to bring to the top on the next draw.
+    scroll: u16,
+    scroll_to: Option<u16>,
+
+    /// Height of the verse pane when last drawn, for paging.
+    page: u16,
+
+    sidebar: ListState,
+    search: Option<Search>,
+    status: String,
+}
+
+impl App<'_> {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            // Resize events (and everything else we don't care about) simply fall through to a
+            // redraw.
+            let Event::Key(
+                key @ KeyEvent {
+                    kind: KeyEventKind::Press,
+                    ..
+                },
+            ) = event::read()?
+            else {
+                continue;
+            };
+
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                return Ok(());
+            }
+
+            if self.search.is_some() {
+                self.search_key(key.code);
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Tab | KeyCode::BackTab => {
+                    self.focus = match self.focus {
+                        Focus::Sidebar => Focus::Verses,
+                        Focus::Verses => Focus::Sidebar,
+                    }
+                }
+                KeyCode::Char('/') => self.search = Some(Search::default()),
+                KeyCode::Char('t') => self.toggle_translation(),
+                KeyCode::Char('n' | ']') | KeyCode::Right => self.step(true),
+                KeyCode::Char('p' | '[') | KeyCode::Left => self.step(false),
+                code => match self.focus {
+                    Focus::Sidebar => self.sidebar_key(code),
+                    Focus::Verses => self.verses_key(code),
+                },
+            }
+        }
+    }
+
+    fn verses_key(&mut self, code: KeyCode) {
+        let page = self.page.max(1);
+        self.scroll = match code {
+            KeyCode::Down | KeyCode::Char('j') => self.scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll.saturating_sub(1),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll.saturating_add(page),
+            KeyCode::PageUp => self.scroll.saturating_sub(page),
+            KeyCode::Home | KeyCode::Char('g') => 0,
+            // Clamped to the last page when drawn.
+            KeyCode::End | KeyCode::Char('G') => u16::MAX,
+            _ => self.scroll,
+        };
+    }
+
+    fn sidebar_key(&mut self, code: KeyCode) {
+        let entries = self.entries();
+        let selected = self.sidebar.selected().unwrap_or_default();
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => self
+                .sidebar
+                .select(Some((selected + 1).min(entries.len() - 1))),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.sidebar.select(Some(selected.saturating_sub(1)))
+            }
+            KeyCode::Home | KeyCode::Char('g') => self.sidebar.select(Some(0)),
+            KeyCode::End | KeyCode::Char('G') => self.sidebar.select(Some(entries.len() - 1)),
+            KeyCode::Enter => {
+                let (book, chapter) = match entries[selected] {
+                    Entry::Book(book) => (book, 1),
+                    Entry::Chapter(chapter) => (self.book, chapter),
+                };
+                self.open(book, chapter);
+                self.focus = Focus::Verses;
+            }
+            _ => (),
+        }
+    }
+
+    fn search_key(&mut self, code: KeyCode) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+
+        match code {
+            KeyCode::Esc => self.search = None,
+            KeyCode::Down => {
+                let last = search.hits.len().saturating_sub(1);
+                let next = search
+                    .selected
+                    .selected()
+                    .map_or(0, |idx| (idx + 1).min(last));
+                search.selected.select(Some(next));
+            }
+            KeyCode::Up => {
+                let previous = search.selected.selected().unwrap_or_default();
+                search.selected.select(Some(previous.saturating_sub(1)));
+            }
+            KeyCode::Enter => {
+                let hit = search
+                    .selected
+                    .selected()
+                    .and_then(|idx| search.hits.get(idx))
+                    .map(|text| (text.book, text.chapter, text.verse));
+                if let Some((book, chapter, verse)) = hit {
+                    self.search = None;
+                    self.open(book, chapter);
+                    self.scroll_to = Some(verse);
+                }
+            }
+            KeyCode::Backspace => {
+                search.query.pop();
+                self.find();
+            }
+            KeyCode::Char(c) => {
+                search.query.push(c);
+                self.find();
+            }
+            _ => (),
+        }
+    }
+
+    /// Run the search as it stands. Half-typed queries often don't parse (an open quote, a
+    /// trailing OR), which just means no hits until there's more.
+    fn find(&mut self) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+
+        search.hits = if search.query.trim().is_empty() {
+            Vec::new()
+        } else {
+            self.bible
+                .search(&search.query, self.translation, SEARCH_LIMIT)
+                .unwrap_or_default()
+        };
+        search
+            .selected
+            .select((!search.hits.is_empty()).then_some(0));
+    }
+
+    fn step(&mut self, forward: bool) {
+        let here = Passage {
+            book: self.book,
+            location: Some(whole_chapter(self.chapter)),
+        };
+        if let Some(Passage {
+            book,
+            location: Some(location),
+        }) = history::adjacent(here, forward)
+        {
+            self.open(book, location.chapter);
+        }
+    }
+
+    /// Switch to the next translation which has this chapter to show.
+    fn toggle_translation(&mut self) {
+        let current = self
+            .translations
+            .iter()
+            .position(|&translation| translation == self.translation)
+            .unwrap_or_default();
+        let count = self.translations.len();
+
+        let previous = self.translation;
+        for offset in 1..count {
+            self.translation = self.translations[(current + offset) % count];
+            match self.load() {
+                Ok(()) => return,
+                Err(e) => self.status = format!("{}: {e}", self.translation),
+            }
+        }
+        self.translation = previous;
+    }
+
+    /// Go to a chapter, or if it can't be read, stay put and say why.
+    fn open(&mut self, book: Book, chapter: u16) {
+        let previous = (self.book, self.chapter);
+        (self.book, self.chapter) = (book, chapter);
+        if let Err(e) = self.load() {
+            (self.book, self.chapter) = previous;
+            self.status = e.to_string();
+        }
+    }
+
+    fn load(&mut self) -> Result<()> {
+        self.texts = self.bible.lookup(
+            self.book,
+            Some(whole_chapter(self.chapter)),
+            self.translation,
+        )?;
+        self.scroll = 0;
+        self.status.clear();
+
+        let here = Entry::Chapter(self.chapter);
+        self.sidebar
+            .select(self.entries().iter().position(|&entry| entry == here));
+        Ok(())
+    }
+
+    fn entries(&self) -> Vec<Entry> {
+        let mut entries = Vec::new();
+        for book in (1..=66).map(Book::from_u8) {
+            entries.push(Entry::Book(book));
+            if book == self.book {
+                entries.extend((1..=book.chapters()).map(Entry::Chapter));
+            }
+        }
+        entries
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [sidebar, pane] =
+            Layout::horizontal([Constraint::Length(SIDEBAR_WIDTH), Constraint::Min(1)]).areas(main);
+
+        self.draw_sidebar(frame, sidebar);
+        match self.search {
+            Some(_) => self.draw_search(frame, pane),
+            None => self.draw_verses(frame, pane),
+        }
+
+        let help = "tab switch  n/p chapter  / search  t translation  q quit";
+        let line = if self.status.is_empty() {
+            Line::from(help.dark_gray())
+        } else {
+            Line::from(self.status.as_str().red())
+        };
+        frame.render_widget(line, status);
+    }
+
+    fn draw_sidebar(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<_> = self
+            .entries()
+            .into_iter()
+            .map(|entry| match entry {
+                Entry::Book(book) if book == self.book => Line::from(book.to_string().bold()),
+                Entry::Book(book) => Line::from(book.to_string()),
+                Entry::Chapter(chapter) => Line::from(format!("  {chapter}")),
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(pane_block("Books", self.focus == Focus::Sidebar))
+            .highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(list, area, &mut self.sidebar);
+    }
+
+    fn draw_verses(&mut self, frame: &mut Frame, area: Rect) {
+        let title = format!("{} {} ({})", self.book, self.chapter, self.translation);
+        let block = pane_block(&title, self.focus == Focus::Verses);
+        let inner = block.inner(area);
+        self.page = inner.height;
+
+        let (lines, starts) = verse_lines(&self.texts, usize::from(inner.width));
+        if let Some(verse) = self.scroll_to.take() {
+            let idx = self.texts.iter().position(|text| text.verse == verse);
+            self.scroll = idx.map_or(0, |idx| crate::present::to_u16(starts[idx]));
+        }
+        let bottom = crate::present::to_u16(lines.len()).saturating_sub(inner.height);
+        self.scroll = self.scroll.min(bottom);
+
+        let lines = if lines.is_empty() {
+            vec![Line::from("not in this translation".dark_gray())]
+        } else {
+            lines
+        };
+        frame.render_widget(
+            Paragraph::new(lines).block(block).scroll((self.scroll, 0)),
+            area,
+        );
+    }
+
+    fn draw_search(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+
+        let [input, hits] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(area);
+        let title = format!("Search ({})", self.translation);
+        frame.render_widget(
+            Paragraph::new(format!("{}▏", search.query)).block(pane_block(&title, true)),
+            input,
+        );
+
+        let width = usize::from(hits.width.saturating_sub(2));
+        let items: Vec<_> = search
+            .hits
+            .iter()
+            .map(|text| {
+                let reference = format!("{} {}:{}  ", text.book, text.chapter, text.verse);
+                let room = width.saturating_sub(reference.chars().count());
+                let content: String = text.content.chars().take(room).collect();
+                Line::from(vec![Span::from(reference).bold(), Span::from(content)])
+            })
+            .collect();
+        let title = format!("{} hits", search.hits.len());
+        let list = List::new(items)
+            .block(pane_block(&title, false))
+            .highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(list, hits, &mut search.selected);
+    }
+}
+
+fn pane_block(title: &str, focused: bool) -> Block<'static> {
+    let block = Block::bordered().title(format!(" {title} "));
+    if focused {
+        block.border_style(Style::new().bold())
+    } else {
+        block.border_style(Style::new().dark_gray())
+    }
+}
+
+/// Verses wrapped to a width, each with its number hanging in the margin, along with the line
+/// each verse starts on.
+fn verse_lines(texts: &[Text], width: usize) -> (Vec<Line<'static>>, Vec<usize>) {
+    let mut lines = Vec::new();
+    let mut starts = Vec::with_capacity(texts.len());
+    for text in texts {
+        starts.push(lines.len());
+        let wrapped = textwrap::wrap(&text.content, width.saturating_sub(5).max(10));
+        for (idx, line) in wrapped.into_iter().enumerate() {
+            let margin = if idx == 0 {
+                Span::from(format!("{:>3}  ", text.verse)).dark_gray()
+            } else {
+                Span::from("     ")
+            };
+            lines.push(Line::from(vec![margin, Span::from(line.into_owned())]));
+        }
+    }
+    (lines, starts)
+}
+
+fn whole_chapter(chapter: u16) -> PartialLocation {
+    PartialLocation {
+        chapter,
+        verse: None,
+        end: None,
+    }
+}
@@ -0,0 +1,141 @@
+use clap::{Parser, ValueEnum};
+use crossterm::style::Stylize;
+use fiat_lux::{Date, Translation};
+
+use crate::{format::group_reference, Args, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct VotdArgs {
+    /// a single line of plain text, for motd and login scripts
+    #[clap(long)]
+    plain: bool,
+
+    /// a single line for a status bar, in the form it expects
+    #[clap(long, value_enum, conflicts_with = "plain")]
+    bar: Option<StatusBar>,
+
+    /// the most characters to put in a status bar, counting the reference
+    #[clap(long, default_value_t = 80, requires = "bar")]
+    max_len: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum StatusBar {
+    /// json for a waybar custom module, with the whole verse as its tooltip
+    Waybar,
+
+    /// text for a polybar custom/script module
+    Polybar,
+
+    /// text for tmux's status-left or status-right
+    Tmux,
+}
+
+pub fn votd(votd_args: &VotdArgs, translation: Translation, args: &Args) -> Result<()> {
+    let today = Date::today();
+    let passage = fiat_lux::verse_of_the_day(today);
+    let bible = crate::open_bible()?;
+    let texts = bible.lookup(passage.book, passage.location, translation)?;
+
+    let content: Vec<_> = texts.iter().map(|text| text.content.as_str()).collect();
+    let content = content.join(" ");
+    let reference = group_reference(&texts);
+
+    if votd_args.plain {
+        println!("{content} -- {reference} ({translation})");
+        return Ok(());
+    }
+
+    if let Some(bar) = votd_args.bar {
+        let short = shorten(&content, &reference, votd_args.max_len);
+        match bar {
+            StatusBar::Waybar => println!(
+                r#"{{"text": "{}", "tooltip": "{}", "class": "votd"}}"#,
+                escape_json(&short),
+                escape_json(&format!("{content}\n{reference} ({translation})"))
+            ),
+            // Polybar reads %{...} as formatting tags and tmux reads #[...] as styles.
+            StatusBar::Polybar => println!("{}", short.replace('%', "%%")),
+            StatusBar::Tmux => println!("{}", short.replace('#', "##")),
+        }
+        return Ok(());
+    }
+
+    println!("{}", format!("Verse of the day, {today}").bold());
+    crate::print_texts(&texts, translation, args);
+    Ok(())
+}
+
+/// Just the reference to today's verse, for a shell prompt. It comes straight from the list the
+/// verses are drawn from, so this never opens the index and costs next to nothing per prompt.
+pub fn prompt_segment(plain: bool) {
+    let passage = fiat_lux::verse_of_the_day(Date::today());
+    if plain {
+        println!("{passage}");
+    } else {
+        println!("{}", passage.to_string().cyan());
+    }
+}
+
+/// The verse followed by its reference, in at most `max_len` characters. A verse too long for
+/// that is cut at a word and given an ellipsis; the reference is always kept whole.
+fn shorten(content: &str, reference: &str, max_len: usize) -> String {
+    let full = format!("{content} {reference}");
+    if full.chars().count() <= max_len {
+        return full;
+    }
+
+    // Room for the verse, leaving space for the ellipsis and the space before the reference.
+    let room = max_len.saturating_sub(reference.chars().count() + 2);
+    let mut cut = String::new();
+    for word in content.split_whitespace() {
+        let len = cut.chars().count() + usize::from(!cut.is_empty()) + word.chars().count();
+        if len > room {
+            break;
+        }
+        if !cut.is_empty() {
+            cut.push(' ');
+        }
+        cut.push_str(word);
+    }
+
+    // Trailing punctuation looks odd in front of an ellipsis.
+    let cut = cut.trim_end_matches(|c: char| !c.is_alphanumeric());
+    if cut.is_empty() {
+        reference.to_string()
+    } else {
+        format!("{cut}… {reference}")
+    }
+}
+
+fn escape_json(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            c if c.is_control() => buf.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => buf.push(c),
+        }
+    }
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::shorten;
+
+    #[test]
+    fn shortening() {
+        let content = "Jesus wept.";
+        assert_eq!("Jesus wept. John 11:35", shorten(content, "John 11:35", 80));
+
+        let content = "For God so loved the world, that he gave his only begotten Son";
+        let short = shorten(content, "John 3:16", 40);
+        assert_eq!("For God so loved the world… John 3:16", short);
+        assert!(short.chars().count() <= 40);
+
+        assert_eq!("John 3:16", shorten(content, "John 3:16", 10));
+    }
+}
@@ -0,0 +1,92 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
+
+use clap::Parser;
+use crossterm::{
+    cursor, execute,
+    terminal::{Clear, ClearType},
+};
+use fiat_lux::{Bible, Passage, Translation};
+use notify::{RecursiveMode, Watcher};
+
+use crate::{Args, Result};
+
+#[derive(Clone, Debug, Parser)]
+pub struct WatchArgs {
+    /// a file with one reference per line, e.g. "Psalms 23" or "John 3:16-18"
+    path: PathBuf,
+}
+
+pub fn watch(watch_args: &WatchArgs, translation: Translation, args: &Args) -> Result<()> {
+    let bible = crate::open_bible()?;
+    let path = watch_args.path.canonicalize()?;
+    let (tx, rx) = mpsc::channel();
+
+    // A lot of editors save by writing a new file and renaming it over the old one, which means
+    // watching the file itself only works until the first save. Instead, we watch the directory
+    // and ignore anything that isn't about our file.
+    let mut watcher = notify::recommended_watcher(tx).map_err(io::Error::other)?;
+    let directory = path.parent().unwrap_or(Path::new("."));
+    watcher
+        .watch(directory, RecursiveMode::NonRecursive)
+        .map_err(io::Error::other)?;
+
+    render(&bible, &path, translation, args)?;
+    while let Ok(event) = rx.recv() {
+        let event = event.map_err(io::Error::other)?;
+        if !event.paths.iter().any(|p| p == &path) {
+            continue;
+        }
+
+        // One save tends to produce a small flurry of events, so we wait for things to settle
+        // before drawing anything.
+        while rx.recv_timeout(Duration::from_millis(100)).is_ok() {}
+        render(&bible, &path, translation, args)?;
+    }
+
+    Ok(())
+}
+
+fn render(bible: &Bible, path: &Path, translation: Translation, args: &Args) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+
+    // The file may be missing for a moment mid-save; the next event will bring it back.
+    let Ok(text) = fs::read_to_string(path) else {
+        return Ok(());
+    };
+
+    let width = terminal_size::terminal_size()
+        .map_or(100, |(terminal_size::Width(w), _)| w)
+        .min(args.config.width());
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // A typo in the middle of an order of service shouldn't blank the whole screen, so bad
+        // lines are reported in place.
+        let passage: Passage = match line.parse() {
+            Ok(passage) => passage,
+            Err(e) => {
+                writeln!(stdout, "\n{line}: {e}")?;
+                continue;
+            }
+        };
+
+        let texts = bible.lookup(passage.book, passage.location, translation)?;
+        if texts.is_empty() {
+            writeln!(stdout, "\n{passage}: not found")?;
+        } else {
+            writeln!(stdout, "{}", crate::build_table(&texts, width, args))?;
+        }
+    }
+
+    stdout.flush()?;
+    Ok(())
+}